@@ -5,6 +5,10 @@ view = View
 git-description = Git commit {$hash} on {$date}
 not-loaded = Not Loaded
 not-supported = Not Supported
+device-disconnected = {$name} (Disconnected)
+yes = Yes
+no = No
+unknown = Unknown
 
 
 # === Pages ===
@@ -14,6 +18,7 @@ memory = Memory
 gpu = Graphics
 network = Networks
 storage = Storage Devices
+hardware = Hardware
 processes = Processes
 
 # System page
@@ -24,9 +29,29 @@ os-version = OS Version
 kernel-version = Kernel
 vendor = Vendor
 system-stats = System Statistics
+boot-info = Boot
+uptime = Uptime
+boot-time = Booted At
+boot-duration = Last Boot Duration
+boot-firmware = Firmware
+boot-loader = Boot Loader
+boot-kernel = Kernel
+boot-initrd = Initial RAM Disk
+boot-userspace = Userspace
+boot-unfinished = Still booting
+sessions = Sessions
+session-from = from {$host}
+session-idle = Idle for {$duration}
+kernel-cmdline = Kernel Command Line
 process-count = Running Processes
 thread-count = Total Threads
 open-files = Open File Descriptors
+pressure = Pressure
+io = IO
+cgroup-pressure = Pressure by Cgroup
+cgroup-pressure-detail = CPU {$cpu} · Memory {$memory} · IO {$io}
+pressure-some = Some Tasks Stalled
+pressure-full = All Tasks Stalled
 
 # CPU page
 processor-info = Processor Information
@@ -41,6 +66,38 @@ architecture = Architecture
 processor-stats = Processor Statistics
 frequency = Frequency
 global-utilization = Global Utilization
+load-average = Load Average
+context-switches = Context Switches
+interrupts = Interrupts
+cpu-time = CPU Time
+cpu-user = User
+cpu-nice = Nice
+cpu-system = System
+cpu-iowait = IO Wait
+cpu-irq = IRQ
+cpu-softirq = Soft IRQ
+cpu-steal = Steal
+cpu-guest = Guest
+cpu-usage = Usage
+cpu-topology = Topology
+cpu-socket = Socket {$id}
+numa-node = NUMA Node {$id}
+shared-cache = Shared L{$level} Cache ({$size})
+core-performance = Performance Core {$id}
+core-efficiency = Efficiency Core {$id}
+core-uniform = Core {$id}
+cpu-frequency = Frequency
+core-frequency = CPU {$id}: {$frequency}
+current-frequency = Current Frequency
+min-frequency = Minimum Frequency
+max-frequency = Maximum Frequency
+base-frequency = Base Frequency
+scaling-governor = Scaling Governor
+energy-performance-preference = Energy Performance Preference
+power-profile = Power Profile
+core-throttle-events = Core Throttling Events
+package-throttle-events = Package Throttling Events
+cpu-pressure = CPU Pressure
 
 # Memory Page
 memory-info = Memory Information
@@ -51,11 +108,52 @@ dram-type = DRAM Type
 memory-stats = Memory Statistics
 used-memory = Used Memory
 used-swap = Used Swap
+memory-composition = Memory Composition
+memory-used = Used
+memory-page-tables = Page Tables
+memory-slab = Slab
+memory-huge-pages = Huge Pages
+memory-shared = Shared
+memory-dirty = Dirty and Writeback
+memory-cached = Cached
+memory-buffers = Buffers
+memory-free = Free
+memory-available = Available
+swap = Swap
+swap-cached = Swap Cached
+zram = zram
+zswap = zswap
+compressed-size = {$original} in {$compressed} ({$ratio}:1)
+memory-pressure = Memory Pressure
+memory-paging = Paging
+paged-in = Paged In
+paged-out = Paged Out
+swapped-in = Swapped In
+swapped-out = Swapped Out
+pages-per-sec = {$pages} pages
+major-faults = Major Faults
+oom-kills = OOM Kills Since Boot
+oom-events = Out of Memory Kills
+oom-victim = {$name} ({$pid})
+oom-unknown-victim = Unknown Process
+seconds-ago = {$seconds} s ago
+memory-modules = Memory Modules
+dimm-slot = Slot
+dimm-size = Size
+dimm-type = Type
+dimm-speed = Speed
+dimm-configured-speed = Configured Speed
+dimm-manufacturer = Manufacturer
+dimm-part-number = Part Number
+dimm-ecc = ECC
+dimm-errors = ECC Errors
+dimm-error-counts = {$corrected} corrected, {$uncorrected} uncorrected
 
 # GPU Page
 gpu-info = GPU Information
 gpu-name = Name
 gpu-vendor = Vendor
+gpu-pci-address = PCI Address
 vram-total = Total VRAM
 gpu-kernel-driver = Kernel Driver
 gpu-user-driver = Userspace Driver
@@ -71,6 +169,19 @@ gpu-vram-frequency = VRAM Frequency
 gpu-encode = Encoder Utilization
 gpu-decode = Decoder Utilization
 gpu-processes = Number of Processes
+gpu-label = {$name} #{$index}
+gpu-all-metrics = All
+gpu-proc-title = Processes
+gpu-proc-pid = PID
+gpu-proc-usage = Usage
+gpu-proc-vram = VRAM
+gpu-proc-encode = Encode
+gpu-proc-decode = Decode
+gpu-proc-type = Type
+gpu-proc-graphics = Graphics
+gpu-proc-compute = Compute
+show-in-processes = Show in Processes
+gpu-label-address = {$name} #{$index} ({$address})
 
 # Network Page
 net-info = Network Information
@@ -89,6 +200,52 @@ tx-total = Total Sent
 tx-errors = Send Errors
 is-up = Is Up
 mtu = MTU
+net-addressing = Addressing
+net-addresses = Addresses
+net-gateways = Default Gateways
+net-dns = DNS Servers
+net-link-speed = Link Speed
+net-duplex = Duplex
+net-mbps = {$value} Mbit/s
+net-wireless = Wireless
+net-ssid = SSID
+net-signal = Signal Strength
+net-bitrate = Bitrate
+net-band = Frequency Band
+net-interfaces = Interfaces
+net-connections = Connections
+net-talkers = Top Talkers
+net-talkers-note = Estimated from TCP connections, without loopback traffic. Connections of other users' processes are only counted when running as root.
+net-talkers-idle = No process is using the network
+net-talker-rates = {$rx}/s received, {$tx}/s sent
+net-usage = Data Usage
+net-usage-amounts = {$rx} received, {$tx} sent
+net-usage-today = Today
+net-usage-cycle = Since {$start}
+net-billing-day = Billing Cycle Starts On Day
+net-quota = Data Quota
+net-no-quota = No Quota
+net-projected = Projected Usage
+net-projected-of = {$projected} of {$quota}
+net-bridge-members = Bridged Interfaces
+net-favorite = Pin to Front
+net-quota-warning = At the current rate this interface will exceed its quota before the cycle ends
+conn-search = Search by address, port or process
+conn-listening-only = Listening Only
+conn-protocol = Protocol
+conn-local = Local Address
+conn-remote = Remote Address
+conn-state = State
+conn-pid = PID
+conn-process = Process
+conn-listening = Listening
+conn-established = Established
+conn-syn-sent = Connecting
+conn-syn-received = Accepting
+conn-closing = Closing
+conn-time-wait = Time Wait
+conn-close-wait = Close Wait
+conn-closed = Closed
 
 # Storage Page
 storage-info = Storage Device Information
@@ -107,6 +264,91 @@ disk-temp = Temperature
 disk-serial = Serial Number
 part-label = Partition Label
 smart-status = SMART Status
+io-pressure = IO Pressure
+disk-throughput = Throughput
+disk-iops = Requests per Second
+disk-latency = Latency
+disk-queue-depth = Queue Depth
+disk-utilization = Utilization
+disk-performance = IO Performance
+disk-read-write = { $read } read, { $write } write
+disk-in-flight = Requests in Flight
+storage-removable = Removable Media
+storage-mount = Mount
+storage-unmount = Unmount
+storage-power-off = Safely Remove
+storage-busy = The filesystem is in use by these processes. Close them and try again.
+storage-busy-unknown = The filesystem is in use by processes that could not be inspected.
+storage-retry = Try Again
+storage-activity = Activity
+storage-health = Health
+storage-layout = Layout
+storage-usage = Disk Usage
+
+analysis-unmounted = This device has no mounted filesystem to analyze.
+analysis-intro = Find out what takes up space on { $mount_point }. Only this filesystem is scanned, and folders you have no access to are skipped.
+analysis-start = Analyze
+analysis-scanning = Scanning… { $entries } items, { $size }
+analysis-cancel = Cancel
+analysis-open = Open in File Manager
+analysis-rescan = Analyze Again
+analysis-largest = Largest Items
+analysis-denied = { $count } folders could not be read
+analysis-other = { $count } smaller items
+
+block-disk = Disk
+block-partition = Partition
+block-luks = LUKS Encrypted
+block-crypt = Encrypted
+block-vg = LVM Volume Group
+block-lv = LVM Logical Volume
+block-mapper = Device Mapper
+block-raid = RAID ({ $level })
+block-degraded = { $missing } missing
+block-used = { $used } of { $size } used
+block-pools = Pools
+
+smart-read = Read SMART Data
+smart-reload = Reload
+smart-unread = Reading the drive's health data requires administrator rights.
+smart-health = Drive Health
+smart-assessment = Overall Assessment
+smart-passed = Passed
+smart-failed = Failing
+smart-power-on = Powered On
+smart-hours = { $hours } hours
+smart-wear = Endurance Used
+smart-media-errors = Media Errors
+smart-running = Running Self-Test
+smart-test-remaining = { $percent }% of test remaining
+smart-test-progress = { $operation } in progress ({ $percent }% completed)
+smart-self-tests = Self-Test Log
+smart-no-self-tests = No self-tests have been run
+smart-id = ID
+smart-attribute = Attribute
+smart-value = Value
+smart-worst = Worst
+smart-threshold = Threshold
+smart-raw = Raw Value
+smart-state = Status
+smart-ok = OK
+smart-warning = Warning
+smart-failing = Failing
+
+# Hardware page
+hardware-copy = Copy as Text
+hardware-board = System Board
+hardware-product = Product
+hardware-board-name = Motherboard
+hardware-bios = BIOS
+hardware-chassis = Chassis
+hardware-pci = PCI Devices
+hardware-usb = USB Devices
+hardware-audio = Audio Devices
+hardware-input = Input Devices
+hardware-driver = Driver: {$driver}
+hardware-no-driver = No driver
+hardware-speed = {$speed} Mbit/s
 
 # Processes page
 details = Details
@@ -118,8 +360,11 @@ internal-name = Internal Name
 cmd-line = Command Line
 exe = Executable
 status = Status
+proc-net-rx = TCP Received (Estimate)
+proc-net-tx = TCP Sent (Estimate)
 
 name = Name
 gpu = GPU
 mem = Memory
 disk = Disk
+proc-net = Network (TCP)
@@ -28,6 +28,8 @@ pub struct AppModel {
     key_binds: HashMap<menu::KeyBind, MenuAction>,
     // Configuration data that persists between application runs.
     config: Config,
+    /// Nav item of the processes page, for jumping to a process from other pages.
+    processes_page: Option<nav_bar::Id>,
 }
 
 /// Messages emitted by the application and its widgets.
@@ -39,9 +41,19 @@ pub enum Message {
     ToggleContextPage(ContextPage),
     UpdateConfig(Config),
     LaunchUrl(String),
+    ShowProcess(u32),
+    Pressure(crate::sys::pressure::SystemPressure),
+    ProcessTraffic(std::collections::HashMap<u32, crate::sys::traffic::ProcessTraffic>),
     // Settings
     SetScaleByCore(bool),
     SetMulticoreView(bool),
+    SetCgroupPressure(bool),
+    SetBillingDay(u8),
+    SetDataQuota(String, u64),
+    SetHideLoopback(bool),
+    SetHideVirtual(bool),
+    SetGroupVirtual(bool),
+    SetFavoriteInterface(String, bool),
 
     SystemPage(page::system::SystemMessage),
     CpuPage(page::cpu::CpuMessage),
@@ -49,6 +61,7 @@ pub enum Message {
     GpuPage(page::gpu::GpuMessage),
     NetworkPage(page::network::NetworkMessage),
     StoragePage(page::storage::StorageMessage),
+    HardwarePage(page::hardware::HardwareMessage),
     ProcessPage(page::processes::ProcessMessage),
 }
 
@@ -94,18 +107,20 @@ impl Application for AppModel {
                         config
                     }
                 })
-                .unwrap_or_default(),
+                .unwrap_or_default()
+                .sanitized(),
+            processes_page: None,
         };
         app.nav
             .insert()
             .text(fl!("system"))
-            .data(Box::new(page::system::SystemPage::new()) as Box<dyn page::Page>)
+            .data(Box::new(page::system::SystemPage::new(app.config.clone())) as Box<dyn page::Page>)
             .icon(icon::from_name("preferences-desktop-symbolic"))
             .activate();
         app.nav
             .insert()
             .text(fl!("cpu"))
-            .data(Box::new(page::cpu::CpuPage::new()) as Box<dyn page::Page>)
+            .data(Box::new(page::cpu::CpuPage::new(app.config.clone())) as Box<dyn page::Page>)
             .icon(icon::from_name("firmware-manager-symbolic"));
         app.nav
             .insert()
@@ -120,7 +135,7 @@ impl Application for AppModel {
         app.nav
             .insert()
             .text(fl!("network"))
-            .data(Box::new(page::network::NetworkPage::new()) as Box<dyn page::Page>)
+            .data(Box::new(page::network::NetworkPage::new(app.config.clone())) as Box<dyn page::Page>)
             .icon(icon::from_name("network-wireless-symbolic"));
         app.nav
             .insert()
@@ -128,13 +143,23 @@ impl Application for AppModel {
             .data(Box::new(page::storage::StoragePage::new()) as Box<dyn page::Page>)
             .icon(icon::from_name("media-floppy-symbolic"));
         app.nav
+            .insert()
+            .text(fl!("hardware"))
+            .data(Box::new(page::hardware::HardwarePage::new()) as Box<dyn page::Page>)
+            .icon(icon::from_name("computer-symbolic"));
+        app.processes_page = app
+            .nav
             .insert()
             .text(fl!("processes"))
             .data(
                 Box::new(page::processes::ProcessPage::new(app.config.clone()))
                     as Box<dyn page::Page>,
             )
-            .icon(icon::from_name("utilities-terminal-symbolic"));
+            .icon(icon::from_name("utilities-terminal-symbolic"))
+            .id()
+            .apply(Some);
+
+        app.update_visibility();
 
         // Create a startup command that sets the window title.
         let command = app.update_title();
@@ -244,7 +269,7 @@ impl Application for AppModel {
                         tracing::error!(?why, "app config error");
                     }
 
-                    Message::UpdateConfig(update.config)
+                    Message::UpdateConfig(update.config.sanitized())
                 }),
         );
 
@@ -284,6 +309,14 @@ impl Application for AppModel {
                 }
             },
 
+            Message::ShowProcess(_) => {
+                if let Some(id) = self.processes_page {
+                    self.nav.activate(id);
+                    self.update_visibility();
+                    tasks.push(self.update_title());
+                }
+            }
+
             Message::SetScaleByCore(state) => {
                 self.config
                     .set_scale_by_core(
@@ -302,6 +335,80 @@ impl Application for AppModel {
                     .unwrap();
             }
 
+            Message::SetCgroupPressure(state) => {
+                self.config
+                    .set_cgroup_pressure(
+                        &cosmic_config::Config::new(Self::APP_ID, Config::VERSION).unwrap(),
+                        state,
+                    )
+                    .unwrap();
+            }
+
+            Message::SetBillingDay(day) => {
+                self.config
+                    .set_billing_day(
+                        &cosmic_config::Config::new(Self::APP_ID, Config::VERSION).unwrap(),
+                        day,
+                    )
+                    .unwrap();
+            }
+
+            Message::SetDataQuota(interface, quota) => {
+                let mut quotas = self.config.data_quotas.clone();
+                if quota == 0 {
+                    quotas.remove(&interface);
+                } else {
+                    quotas.insert(interface, quota);
+                }
+                self.config
+                    .set_data_quotas(
+                        &cosmic_config::Config::new(Self::APP_ID, Config::VERSION).unwrap(),
+                        quotas,
+                    )
+                    .unwrap();
+            }
+
+            Message::SetHideLoopback(state) => {
+                self.config
+                    .set_hide_loopback(
+                        &cosmic_config::Config::new(Self::APP_ID, Config::VERSION).unwrap(),
+                        state,
+                    )
+                    .unwrap();
+            }
+
+            Message::SetHideVirtual(state) => {
+                self.config
+                    .set_hide_virtual(
+                        &cosmic_config::Config::new(Self::APP_ID, Config::VERSION).unwrap(),
+                        state,
+                    )
+                    .unwrap();
+            }
+
+            Message::SetGroupVirtual(state) => {
+                self.config
+                    .set_group_virtual(
+                        &cosmic_config::Config::new(Self::APP_ID, Config::VERSION).unwrap(),
+                        state,
+                    )
+                    .unwrap();
+            }
+
+            Message::SetFavoriteInterface(interface, favorite) => {
+                let mut favorites = self.config.favorite_interfaces.clone();
+                favorites.retain(|name| *name != interface);
+                if favorite {
+                    favorites.push(interface);
+                }
+                self.config
+                    .set_favorite_interfaces(
+                        &cosmic_config::Config::new(Self::APP_ID, Config::VERSION).unwrap(),
+                        favorites,
+                    )
+                    .unwrap();
+            }
+
             _ => {}
         }
 
@@ -317,6 +424,7 @@ impl Application for AppModel {
     fn on_nav_select(&mut self, id: nav_bar::Id) -> Task<Self::Message> {
         // Activate the page in the model.
         self.nav.activate(id);
+        self.update_visibility();
 
         self.update_title()
     }
@@ -373,11 +481,41 @@ impl AppModel {
                     widget::toggler(self.config.multicore_view)
                         .on_toggle(Message::SetMulticoreView),
                 ))
+                .add(widget::settings::item(
+                    "Show Per-Cgroup Pressure",
+                    widget::toggler(self.config.cgroup_pressure)
+                        .on_toggle(Message::SetCgroupPressure),
+                ))
+                .apply(Element::from),
+            widget::settings::section()
+                .title("Network Settings")
+                .add(widget::settings::item(
+                    "Hide Loopback Interface",
+                    widget::toggler(self.config.hide_loopback).on_toggle(Message::SetHideLoopback),
+                ))
+                .add(widget::settings::item(
+                    "Hide Virtual Interfaces",
+                    widget::toggler(self.config.hide_virtual).on_toggle(Message::SetHideVirtual),
+                ))
+                .add(widget::settings::item(
+                    "Group Virtual Interfaces Under Their Bridge",
+                    widget::toggler(self.config.group_virtual).on_toggle(Message::SetGroupVirtual),
+                ))
                 .apply(Element::from),
         ])
         .apply(Element::from)
     }
 
+    /// Tells every page whether it is the one shown, so hidden pages can pause their polling.
+    fn update_visibility(&mut self) {
+        let active = self.nav.active();
+        for entity in self.nav.iter().collect::<Vec<Entity>>() {
+            if let Some(page) = self.nav.data_mut::<Box<dyn page::Page>>(entity) {
+                page.set_visible(entity == active);
+            }
+        }
+    }
+
     /// Updates the header and window titles.
     pub fn update_title(&mut self) -> Task<Message> {
         let mut window_title = fl!("app-title");
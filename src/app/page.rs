@@ -1,7 +1,10 @@
 pub mod cpu;
+mod devices;
 pub mod gpu;
+pub mod hardware;
 pub mod memory;
 pub mod network;
+mod pressure;
 pub mod processes;
 pub mod storage;
 pub mod system;
@@ -31,6 +34,9 @@ pub trait Page {
         None
     }
 
+    /// Called when the page is shown or hidden, so polling only the page needs can be paused
+    fn set_visible(&mut self, _: bool) {}
+
     fn subscription(&self) -> Vec<cosmic::iced::Subscription<Message>> {
         vec![]
     }
@@ -1,9 +1,17 @@
 use std::collections::VecDeque;
+use std::time::Duration;
 
+use super::pressure::PressureHistory;
 use crate::{
     app::Message,
+    config::Config,
     fl,
     helpers::{format_number, get_bytes},
+    sys::{
+        cpu::{CpuActivity, CpuSampler, CpuStates},
+        cpufreq::{self, CpuFrequency, PowerProfiles},
+        topology::{self, CoreKind, CpuTopology},
+    },
 };
 use cosmic::{
     iced::{self, stream, Subscription},
@@ -20,32 +28,401 @@ use monitord_protocols::{
 #[derive(Debug, Clone)]
 pub enum CpuMessage {
     Snapshot(CpuInfo),
+    Activity(CpuActivity),
+    SelectView(widget::segmented_button::Entity),
+    Frequency(CpuFrequency),
+    PowerProfiles(PowerProfiles),
+}
+
+/// What the left side of the CPU page shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CpuView {
+    Usage,
+    Frequency,
+    Topology,
 }
 
 pub struct CpuPage {
-    cpu_usage_history: VecDeque<f32>,
+    cpu_state_history: VecDeque<CpuStates>,
+    core_state_history: Vec<VecDeque<CpuStates>>,
+    cpu_activity: Option<CpuActivity>,
     cpu_info: Option<CpuInfo>,
+    // The layout does not change while running, so it is only read once
+    topology: CpuTopology,
+    view_list: widget::segmented_button::SingleSelectModel,
+    cpu_frequency: Option<CpuFrequency>,
+    // Per-core frequency (out of the core's maximum)
+    frequency_history: Vec<VecDeque<f32>>,
+    power_profiles: Option<PowerProfiles>,
+    pressure: PressureHistory,
+    // Configuration data that persists between application runs.
+    config: Config,
 }
 
 impl CpuPage {
-    pub fn new() -> Self {
+    pub fn new(config: Config) -> Self {
+        let mut view_list = widget::segmented_button::SingleSelectModel::default();
+        view_list
+            .insert()
+            .text(fl!("cpu-usage"))
+            .data(CpuView::Usage)
+            .activate();
+        view_list
+            .insert()
+            .text(fl!("cpu-frequency"))
+            .data(CpuView::Frequency);
+        view_list
+            .insert()
+            .text(fl!("cpu-topology"))
+            .data(CpuView::Topology);
+
         Self {
-            cpu_usage_history: VecDeque::from(vec![0.0; 30]),
+            cpu_state_history: VecDeque::from(vec![CpuStates::default(); 30]),
+            core_state_history: Vec::new(),
+            cpu_activity: None,
             cpu_info: None,
+            topology: topology::read(),
+            view_list,
+            cpu_frequency: None,
+            frequency_history: Vec::new(),
+            power_profiles: None,
+            pressure: PressureHistory::new(),
+            config,
         }
     }
+
+    /// Utilization of a logical CPU (out of 1) from the latest sample
+    fn logical_cpu_usage(&self, cpu: u32) -> f32 {
+        self.cpu_activity
+            .as_ref()
+            .and_then(|activity| {
+                let index = activity.core_ids.iter().position(|id| *id == cpu)?;
+                Some(activity.cores.get(index)?.values().iter().sum::<f32>() / 100.0)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Sockets, NUMA nodes and last level cache domains, with a tile per logical CPU
+    fn topology_view(&self) -> Element<Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        let mut packages = widget::column().spacing(cosmic.space_xs());
+        for package in &self.topology.packages {
+            let mut column = widget::column()
+                .spacing(cosmic.space_xxs())
+                .push(widget::text::heading(fl!("cpu-socket", id = package.id)));
+
+            for node in &package.nodes {
+                if let Some(id) = node.id {
+                    column = column.push(widget::text::body(fl!("numa-node", id = id)));
+                }
+
+                for cache in &node.caches {
+                    let cores = cache
+                        .cores
+                        .iter()
+                        .map(|core| {
+                            let label = match core.kind {
+                                CoreKind::Performance => fl!("core-performance", id = core.id),
+                                CoreKind::Efficiency => fl!("core-efficiency", id = core.id),
+                                CoreKind::Uniform => fl!("core-uniform", id = core.id),
+                            };
+                            let threads = core
+                                .threads
+                                .iter()
+                                .map(|thread| {
+                                    widget::canvas(crate::widget::usage_cell::UsageCell {
+                                        label: thread.to_string(),
+                                        usage: self.logical_cpu_usage(*thread),
+                                    })
+                                    .width(iced::Length::Fixed(36.0))
+                                    .height(iced::Length::Fixed(36.0))
+                                    .apply(Element::from)
+                                })
+                                .collect();
+
+                            widget::column()
+                                .spacing(cosmic.space_xxxs())
+                                .push(widget::text::caption(label))
+                                .push(
+                                    widget::row::with_children(threads)
+                                        .spacing(cosmic.space_xxxs()),
+                                )
+                                .apply(Element::from)
+                        })
+                        .collect();
+
+                    column = column.push(
+                        widget::column()
+                            .spacing(cosmic.space_xxs())
+                            .padding(cosmic.space_xs())
+                            .push(widget::text::caption(fl!(
+                                "shared-cache",
+                                level = cache.level,
+                                size = get_bytes(cache.size_bytes)
+                            )))
+                            .push(
+                                widget::flex_row(cores)
+                                    .row_spacing(cosmic.space_xxs())
+                                    .column_spacing(cosmic.space_xs()),
+                            )
+                            .apply(widget::layer_container)
+                            .layer(cosmic::cosmic_theme::Layer::Primary),
+                    );
+                }
+            }
+
+            packages = packages.push(column);
+        }
+
+        packages.apply(widget::scrollable).apply(Element::from)
+    }
+
+    /// One frequency graph per core, scaled to that core's maximum frequency
+    fn frequency_view(&self) -> Element<Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        let Some(frequency) = &self.cpu_frequency else {
+            return widget::text::body(fl!("not-supported")).apply(Element::from);
+        };
+
+        let graphs = self
+            .frequency_history
+            .iter()
+            .zip(&frequency.cores)
+            .map(|(history, core)| {
+                widget::column()
+                    .spacing(cosmic.space_xxxs())
+                    .push(widget::text::caption(fl!(
+                        "core-frequency",
+                        id = core.cpu,
+                        frequency = format_frequency(core.current_khz)
+                    )))
+                    .push(
+                        widget::canvas(crate::widget::graph::LineGraph {
+                            points: history.iter().cloned().collect(),
+                        })
+                        .width(iced::Length::Fill)
+                        .height(iced::Length::Fill),
+                    )
+                    .apply(Element::from)
+            })
+            .collect::<Vec<_>>();
+
+        let mut rows = widget::column().spacing(cosmic.space_xxs());
+        let mut graphs = graphs.into_iter().peekable();
+        while graphs.peek().is_some() {
+            rows = rows.push(
+                widget::row::with_children(graphs.by_ref().take(4).collect())
+                    .spacing(cosmic.space_xxs())
+                    .height(iced::Length::Fill),
+            );
+        }
+        rows.apply(Element::from)
+    }
+
+    /// Frequency limits, scaling policy and throttling
+    fn frequency_section(&self) -> Option<Element<Message>> {
+        let frequency = self.cpu_frequency.as_ref()?;
+        let current = frequency.cores.iter().map(|core| core.current_khz);
+        let min = frequency.cores.iter().map(|core| core.min_khz).min();
+        let max = frequency.cores.iter().map(|core| core.max_khz).max();
+        let base = frequency
+            .cores
+            .iter()
+            .filter_map(|core| core.base_khz)
+            .max();
+
+        widget::settings::section()
+            .title(fl!("cpu-frequency"))
+            .add(widget::settings::item(
+                fl!("current-frequency"),
+                format!(
+                    "{} - {}",
+                    format_frequency(current.clone().min().unwrap_or_default()),
+                    format_frequency(current.max().unwrap_or_default())
+                )
+                .apply(widget::text::body),
+            ))
+            .add_maybe(min.map(|min| {
+                widget::settings::item(
+                    fl!("min-frequency"),
+                    format_frequency(min).apply(widget::text::body),
+                )
+            }))
+            .add_maybe(max.map(|max| {
+                widget::settings::item(
+                    fl!("max-frequency"),
+                    format_frequency(max).apply(widget::text::body),
+                )
+            }))
+            .add_maybe(base.map(|base| {
+                widget::settings::item(
+                    fl!("base-frequency"),
+                    format_frequency(base).apply(widget::text::body),
+                )
+            }))
+            // Changing the policy needs administrator rights, which belong with the daemon
+            .add(
+                widget::settings::item::builder(fl!("scaling-governor"))
+                    .description(frequency.available_governors.join(", "))
+                    .control(
+                        frequency
+                            .governor
+                            .clone()
+                            .unwrap_or_else(|| fl!("not-supported"))
+                            .apply(widget::text::body),
+                    ),
+            )
+            .add_maybe(
+                frequency
+                    .energy_performance_preference
+                    .as_ref()
+                    .map(|preference| {
+                        widget::settings::item(
+                            fl!("energy-performance-preference"),
+                            preference.clone().apply(widget::text::body),
+                        )
+                    }),
+            )
+            .add_maybe(self.power_profiles.as_ref().map(|profiles| {
+                widget::settings::item::builder(fl!("power-profile"))
+                    .description(profiles.available.join(", "))
+                    .control(profiles.active.clone().apply(widget::text::body))
+            }))
+            .add(widget::settings::item(
+                fl!("core-throttle-events"),
+                frequency
+                    .core_throttle_count
+                    .to_string()
+                    .apply(widget::text::body),
+            ))
+            .add(widget::settings::item(
+                fl!("package-throttle-events"),
+                frequency
+                    .package_throttle_count
+                    .to_string()
+                    .apply(widget::text::body),
+            ))
+            .apply(Element::from)
+            .apply(Some)
+    }
+
+    fn state_labels() -> [String; 8] {
+        [
+            fl!("cpu-user"),
+            fl!("cpu-nice"),
+            fl!("cpu-system"),
+            fl!("cpu-iowait"),
+            fl!("cpu-irq"),
+            fl!("cpu-softirq"),
+            fl!("cpu-steal"),
+            fl!("cpu-guest"),
+        ]
+    }
+
+    fn stacked_graph(history: &VecDeque<CpuStates>) -> Element<'static, Message> {
+        widget::canvas(crate::widget::graph::StackedGraph {
+            series: (0..8)
+                .map(|state| {
+                    history
+                        .iter()
+                        .map(|states| states.values()[state] / 100.0)
+                        .collect()
+                })
+                .collect(),
+            markers: Vec::new(),
+        })
+        .width(iced::Length::Fill)
+        .height(iced::Length::Fill)
+        .apply(Element::from)
+    }
+
+    /// The global breakdown, or one small graph per core when the multicore view is enabled
+    fn history_view(&self) -> Element<Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        let graphs = if self.config.multicore_view && !self.core_state_history.is_empty() {
+            let mut rows = widget::column().spacing(cosmic.space_xxs());
+            for cores in self.core_state_history.chunks(4) {
+                rows = rows.push(
+                    widget::row::with_children(cores.iter().map(Self::stacked_graph).collect())
+                        .spacing(cosmic.space_xxs())
+                        .height(iced::Length::Fill),
+                );
+            }
+            rows.apply(Element::from)
+        } else {
+            Self::stacked_graph(&self.cpu_state_history)
+        };
+
+        let legend = Self::state_labels()
+            .into_iter()
+            .enumerate()
+            .map(|(index, label)| {
+                widget::row()
+                    .spacing(cosmic.space_xxxs())
+                    .align_y(iced::Alignment::Center)
+                    .push(
+                        widget::canvas(crate::widget::graph::LegendSwatch { index })
+                            .width(iced::Length::Fixed(10.0))
+                            .height(iced::Length::Fixed(10.0)),
+                    )
+                    .push(widget::text::caption(label))
+                    .apply(Element::from)
+            })
+            .collect();
+
+        widget::column()
+            .spacing(cosmic.space_xxs())
+            .width(iced::Length::Fill)
+            .push(graphs)
+            .push(widget::row::with_children(legend).spacing(cosmic.space_xs()))
+            .apply(Element::from)
+    }
 }
 
 impl super::Page for CpuPage {
     fn update(&mut self, msg: Message) -> cosmic::app::Task<Message> {
         let tasks = Vec::new();
         match msg {
+            Message::UpdateConfig(config) => self.config = config,
+            Message::Pressure(pressure) => self.pressure.push(pressure.cpu),
             Message::CpuPage(CpuMessage::Snapshot(snapshot)) => {
-                self.cpu_usage_history
-                    .push_back(snapshot.global_utilization_percent as f32 / 100.0);
-                self.cpu_usage_history.pop_front();
                 self.cpu_info = Some(snapshot);
             }
+            Message::CpuPage(CpuMessage::SelectView(view)) => self.view_list.activate(view),
+            Message::CpuPage(CpuMessage::Frequency(frequency)) => {
+                self.frequency_history
+                    .resize_with(frequency.cores.len(), || VecDeque::from(vec![0.0; 30]));
+                for (history, core) in self.frequency_history.iter_mut().zip(&frequency.cores) {
+                    history.push_back(core.current_khz as f32 / core.max_khz.max(1) as f32);
+                    history.pop_front();
+                }
+
+                self.cpu_frequency = Some(frequency);
+            }
+            Message::CpuPage(CpuMessage::PowerProfiles(profiles)) => {
+                self.power_profiles = Some(profiles);
+            }
+            Message::CpuPage(CpuMessage::Activity(activity)) => {
+                self.cpu_state_history.push_back(activity.global);
+                self.cpu_state_history.pop_front();
+
+                self.core_state_history
+                    .resize_with(activity.cores.len(), || {
+                        VecDeque::from(vec![CpuStates::default(); 30])
+                    });
+                for (history, states) in self.core_state_history.iter_mut().zip(&activity.cores) {
+                    history.push_back(*states);
+                    history.pop_front();
+                }
+
+                self.cpu_activity = Some(activity);
+            }
             _ => {}
         }
 
@@ -59,11 +436,19 @@ impl super::Page for CpuPage {
             widget::row()
                 .spacing(cosmic.space_xxs())
                 .push(
-                    widget::canvas(crate::widget::graph::LineGraph {
-                        points: self.cpu_usage_history.iter().cloned().collect(),
-                    })
-                    .width(iced::Length::Fill)
-                    .height(iced::Length::Fill),
+                    widget::column()
+                        .spacing(cosmic.space_xxs())
+                        .width(iced::Length::Fill)
+                        .push(
+                            widget::segmented_control::horizontal(&self.view_list).on_activate(
+                                |entity| Message::CpuPage(CpuMessage::SelectView(entity)),
+                            ),
+                        )
+                        .push(match self.view_list.active_data::<CpuView>() {
+                            Some(CpuView::Frequency) => self.frequency_view(),
+                            Some(CpuView::Topology) => self.topology_view(),
+                            _ => self.history_view(),
+                        }),
                 )
                 .push(widget::settings::view_column(vec![
                     widget::settings::section()
@@ -149,8 +534,56 @@ impl super::Page for CpuPage {
                                 .to_string()
                                 .apply(widget::text::body),
                         ))
+                        .add_maybe(self.cpu_activity.as_ref().map(|activity| {
+                            widget::settings::item(
+                                fl!("load-average"),
+                                activity
+                                    .load_average
+                                    .map(|load| format_number(load as f64))
+                                    .join(", ")
+                                    .apply(widget::text::body),
+                            )
+                        }))
+                        .add_maybe(self.cpu_activity.as_ref().map(|activity| {
+                            widget::settings::item(
+                                fl!("context-switches"),
+                                format!("{}/s", activity.context_switches_per_sec.round())
+                                    .apply(widget::text::body),
+                            )
+                        }))
+                        .add_maybe(self.cpu_activity.as_ref().map(|activity| {
+                            widget::settings::item(
+                                fl!("interrupts"),
+                                format!("{}/s", activity.interrupts_per_sec.round())
+                                    .apply(widget::text::body),
+                            )
+                        }))
                         .apply(widget::scrollable)
                         .apply(Element::from),
+                    self.cpu_activity
+                        .as_ref()
+                        .map(|activity| {
+                            Self::state_labels()
+                                .into_iter()
+                                .zip(activity.global.values())
+                                .fold(
+                                    widget::settings::section().title(fl!("cpu-time")),
+                                    |section, (label, percent)| {
+                                        section.add(widget::settings::item(
+                                            label,
+                                            format!("{}%", format_number(percent as f64))
+                                                .apply(widget::text::body),
+                                        ))
+                                    },
+                                )
+                                .apply(Element::from)
+                        })
+                        .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
+                    self.frequency_section()
+                        .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
+                    self.pressure
+                        .section(fl!("cpu-pressure"))
+                        .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
                 ]))
                 .apply(Element::from)
         } else {
@@ -159,27 +592,85 @@ impl super::Page for CpuPage {
     }
 
     fn subscription(&self) -> Vec<Subscription<Message>> {
-        vec![Subscription::run(|| {
-            stream::channel(1, |mut sender| async move {
-                let mut service = MonitordServiceClient::connect("http://127.0.0.1:50051")
-                    .await
-                    .unwrap();
+        vec![
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let mut service = MonitordServiceClient::connect("http://127.0.0.1:50051")
+                        .await
+                        .unwrap();
 
-                let request = tonic::Request::new(SnapshotRequest { interval_ms: 1000 });
+                    let request = tonic::Request::new(SnapshotRequest { interval_ms: 1000 });
+
+                    let mut stream = service.stream_cpu_info(request).await.unwrap().into_inner();
+
+                    loop {
+                        let message = stream.message().await.unwrap();
+
+                        if let Some(message) = message {
+                            sender
+                                .send(Message::CpuPage(CpuMessage::Snapshot(message)))
+                                .await
+                                .unwrap();
+                        }
+                    }
+                })
+            }),
+            // The daemon only reports overall utilization, the breakdown comes from /proc/stat
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let mut sampler = CpuSampler::default();
 
-                let mut stream = service.stream_cpu_info(request).await.unwrap().into_inner();
+                    loop {
+                        let (returned, activity) = tokio::task::spawn_blocking(move || {
+                            let activity = sampler.sample();
+                            (sampler, activity)
+                        })
+                        .await
+                        .unwrap();
+                        sampler = returned;
 
-                loop {
-                    let message = stream.message().await.unwrap();
+                        if let Some(activity) = activity {
+                            sender
+                                .send(Message::CpuPage(CpuMessage::Activity(activity)))
+                                .await
+                                .unwrap();
+                        }
 
-                    if let Some(message) = message {
+                        tokio::time::sleep(Duration::from_millis(1000)).await;
+                    }
+                })
+            }),
+            // Scaling state is only in sysfs, and power profiles are only known to power-profiles-daemon
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let connection = zbus::Connection::system().await.ok();
+
+                    loop {
+                        let frequency = tokio::task::spawn_blocking(cpufreq::read).await.unwrap();
                         sender
-                            .send(Message::CpuPage(CpuMessage::Snapshot(message)))
+                            .send(Message::CpuPage(CpuMessage::Frequency(frequency)))
                             .await
                             .unwrap();
+
+                        let profiles = match &connection {
+                            Some(connection) => cpufreq::power_profiles(connection).await,
+                            None => None,
+                        };
+                        if let Some(profiles) = profiles {
+                            sender
+                                .send(Message::CpuPage(CpuMessage::PowerProfiles(profiles)))
+                                .await
+                                .unwrap();
+                        }
+
+                        tokio::time::sleep(Duration::from_millis(1000)).await;
                     }
-                }
-            })
-        })]
+                })
+            }),
+        ]
     }
 }
+
+fn format_frequency(khz: u64) -> String {
+    format!("{} GHz", format_number(khz as f64 / 1_000_000.0))
+}
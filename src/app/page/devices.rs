@@ -0,0 +1,137 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use cosmic::widget::segmented_button::{Entity, SingleSelectModel};
+
+use crate::fl;
+
+/// How long a missing device keeps its tab before it is removed
+const GRACE_PERIOD: Duration = Duration::from_secs(10);
+/// How long a removed device's data is kept for it to come back to
+const DETACHED_LIFETIME: Duration = Duration::from_secs(10 * 60);
+/// Removed devices kept at most, the ones removed longest ago are dropped first
+const DETACHED_LIMIT: usize = 16;
+
+/// Tabs for devices that can appear and disappear at runtime, keyed by a stable identifier
+pub struct DeviceTabs<D: 'static> {
+    pub model: SingleSelectModel,
+    entities: HashMap<String, Entity>,
+    labels: HashMap<String, String>,
+    disconnected: HashMap<String, Instant>,
+    // Removed devices with when they were removed, kept for a while so they regain their
+    // history when they come back
+    detached: HashMap<String, (Instant, D)>,
+}
+
+impl<D: 'static> DeviceTabs<D> {
+    pub fn new() -> Self {
+        Self {
+            model: SingleSelectModel::default(),
+            entities: HashMap::new(),
+            labels: HashMap::new(),
+            disconnected: HashMap::new(),
+            detached: HashMap::new(),
+        }
+    }
+
+    /// Inserts or reconnects a device present in the latest snapshot and returns its data
+    pub fn upsert(&mut self, id: &str, label: String, create: impl FnOnce() -> D) -> &mut D {
+        let entity = if let Some(entity) = self.entities.get(id) {
+            *entity
+        } else {
+            let data = self
+                .detached
+                .remove(id)
+                .map(|(_, data)| data)
+                .unwrap_or_else(create);
+            let entity = self.model.insert().text(label.clone()).data(data).id();
+            self.entities.insert(id.to_owned(), entity);
+            if self.model.active_data::<D>().is_none() {
+                self.model.activate(entity);
+            }
+            entity
+        };
+
+        if self.disconnected.remove(id).is_some() || self.labels.get(id) != Some(&label) {
+            self.model.text_set(entity, label.clone());
+        }
+        self.labels.insert(id.to_owned(), label);
+
+        self.model.data_mut::<D>(entity).unwrap()
+    }
+
+    /// Marks devices missing from the latest snapshot as disconnected, and removes them once
+    /// they have been gone for longer than the grace period
+    pub fn retain_present(&mut self, present: &HashSet<String>) {
+        let now = Instant::now();
+        let missing = self
+            .entities
+            .keys()
+            .filter(|id| !present.contains(*id))
+            .cloned()
+            .collect::<Vec<String>>();
+
+        for id in missing {
+            let entity = self.entities[&id];
+            let since = match self.disconnected.get(&id) {
+                Some(since) => *since,
+                None => {
+                    let name = self.labels.get(&id).cloned().unwrap_or_default();
+                    self.model
+                        .text_set(entity, fl!("device-disconnected", name = name));
+                    self.disconnected.insert(id.clone(), now);
+                    now
+                }
+            };
+
+            if now.duration_since(since) < GRACE_PERIOD {
+                continue;
+            }
+
+            self.remove(&id);
+        }
+
+        self.detached
+            .retain(|_, (removed, _)| now.duration_since(*removed) < DETACHED_LIFETIME);
+    }
+
+    /// Removes the tab of a device right away, keeping its data in case it comes back
+    pub fn remove(&mut self, id: &str) {
+        let Some(entity) = self.entities.remove(id) else {
+            return;
+        };
+
+        let was_active = self.model.active() == entity;
+        let position = self.model.position(entity).unwrap_or_default();
+        if let Some(data) = self.model.data_remove::<D>(entity) {
+            self.detached.insert(id.to_owned(), (Instant::now(), data));
+            if self.detached.len() > DETACHED_LIMIT {
+                if let Some(oldest) = self
+                    .detached
+                    .iter()
+                    .min_by_key(|(_, (removed, _))| *removed)
+                    .map(|(id, _)| id.clone())
+                {
+                    self.detached.remove(&oldest);
+                }
+            }
+        }
+        self.model.remove(entity);
+        self.labels.remove(id);
+        self.disconnected.remove(id);
+
+        // Fall back to the neighbouring tab rather than showing nothing
+        if was_active {
+            self.model.activate_position(position.saturating_sub(1));
+        }
+    }
+
+    /// Moves the tab of a device to the given position
+    pub fn move_to(&mut self, id: &str, position: u16) {
+        if let Some(entity) = self.entities.get(id) {
+            if self.model.position(*entity) != Some(position) {
+                self.model.position_set(*entity, position);
+            }
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use std::collections::{HashMap, VecDeque};
+mod metric;
+mod process;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
 
 use cosmic::{
     iced::{self, stream, Subscription},
@@ -11,30 +15,186 @@ use monitord_protocols::{
     protocols::MonitordServiceClient,
 };
 
-use crate::{app::Message, fl};
+use super::devices::DeviceTabs;
+use crate::{
+    app::Message,
+    fl,
+    sys::{
+        drm::{DrmSampler, GpuProcess},
+        pci,
+    },
+};
+use metric::GpuMetric;
+use process::{GpuProcessTableCategory, GpuProcessTableItem};
 
 #[derive(Debug, Clone)]
 pub enum GpuMessage {
     Snapshot(GpuList),
+    Processes(Vec<GpuProcess>),
     SelectTab(widget::segmented_button::Entity),
+    SelectMetric(widget::segmented_button::Entity),
+    SelectProcess(widget::table::Entity),
+    SortProcesses(GpuProcessTableCategory),
 }
 
 struct GpuDevice {
     info: GpuInfo,
-    history: VecDeque<f32>,
+    address: Option<String>,
+    // Only metrics the GPU has reported at least once get a history
+    history: HashMap<GpuMetric, VecDeque<f32>>,
+    processes: widget::table::SingleSelectModel<GpuProcessTableItem, GpuProcessTableCategory>,
+    pid_to_entity: HashMap<u32, widget::table::Entity>,
+    // Engine usage from DRM fdinfo by PID, only known once the GPU is matched to its card
+    engines: HashMap<u32, GpuProcess>,
+    has_engines: bool,
+}
+
+impl GpuDevice {
+    /// The encode, decode and type columns are only added once DRM reports engine usage for the
+    /// GPU, drivers like NVIDIA's do not
+    fn process_table(
+        engines: bool,
+    ) -> widget::table::SingleSelectModel<GpuProcessTableItem, GpuProcessTableCategory> {
+        let mut categories = vec![
+            GpuProcessTableCategory::Pid,
+            GpuProcessTableCategory::Name,
+            GpuProcessTableCategory::Usage,
+            GpuProcessTableCategory::Vram,
+        ];
+        if engines {
+            categories.extend([
+                GpuProcessTableCategory::Encode,
+                GpuProcessTableCategory::Decode,
+                GpuProcessTableCategory::Type,
+            ]);
+        }
+        widget::table::SingleSelectModel::new(categories)
+    }
+
+    /// Replaces the process table with one showing the engine columns
+    fn show_engines(&mut self) {
+        let sort = self.processes.get_sort();
+        self.processes = Self::process_table(true);
+        self.pid_to_entity.clear();
+        if let Some((category, ascending)) = sort {
+            self.processes.sort(category, ascending);
+        }
+        self.has_engines = true;
+    }
+
+    /// Updates the process table in place from the daemon's process list, keyed by PID
+    fn sync_processes(&mut self) {
+        let mut alive = HashSet::with_capacity(self.info.process_info.len());
+        for process in self.info.process_info.iter() {
+            alive.insert(process.pid);
+            let engines = self.engines.get(&process.pid);
+            if let Some(item) = self
+                .pid_to_entity
+                .get(&process.pid)
+                .and_then(|entity| self.processes.item_mut(*entity))
+            {
+                item.update(process, engines);
+            } else {
+                let entity = self
+                    .processes
+                    .insert(GpuProcessTableItem::new(process, engines))
+                    .id();
+                self.pid_to_entity.insert(process.pid, entity);
+            }
+        }
+        self.pid_to_entity.retain(|pid, entity| {
+            if alive.contains(pid) {
+                true
+            } else {
+                self.processes.remove(*entity);
+                false
+            }
+        });
+
+        if let Some(sort) = self.processes.get_sort() {
+            self.processes.sort(sort.0, sort.1);
+        } else {
+            self.processes.sort(GpuProcessTableCategory::Usage, false)
+        }
+    }
+}
+
+/// How a GPU from a snapshot is keyed and labelled
+struct GpuIdentity {
+    id: String,
+    label: String,
+    address: Option<String>,
 }
 
 pub struct GpuPage {
-    gpu_list: widget::segmented_button::SingleSelectModel,
-    name_to_entity: HashMap<String, widget::segmented_button::Entity>,
+    gpu_list: DeviceTabs<GpuDevice>,
+    // GPUs are not hotplugged, so the DRM cards are only listed once
+    cards: Vec<pci::GpuCard>,
+    // Graphed metric, the entity without data shows all of them side by side
+    metric_list: widget::segmented_button::SingleSelectModel,
+    /// DRM engine usage is only sampled while the page is shown
+    visible: bool,
 }
 
 impl GpuPage {
     pub fn new() -> Self {
+        let mut metric_list = widget::segmented_button::SingleSelectModel::default();
+        metric_list.insert().text(fl!("gpu-all-metrics")).activate();
+        for metric in GpuMetric::ALL {
+            metric_list.insert().text(metric.label()).data(metric);
+        }
+
         Self {
-            gpu_list: widget::segmented_button::SingleSelectModel::default(),
-            name_to_entity: HashMap::new(),
+            gpu_list: DeviceTabs::new(),
+            cards: pci::gpu_cards(),
+            metric_list,
+            visible: false,
+        }
+    }
+
+    fn graph(points: Vec<f32>) -> Element<'static, Message> {
+        widget::canvas(crate::widget::graph::LineGraph { points })
+            .width(iced::Length::Fill)
+            .height(iced::Length::Fill)
+            .apply(Element::from)
+    }
+
+    /// Graphs the selected metric, or every metric the GPU reports as small multiples
+    fn history_view<'a>(&'a self, gpu: &'a GpuDevice) -> Element<'a, Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        if let Some(metric) = self.metric_list.active_data::<GpuMetric>() {
+            return match gpu.history.get(metric) {
+                Some(history) => Self::graph(metric.normalize(history)),
+                None => widget::text::body(fl!("not-supported")).apply(Element::from),
+            };
+        }
+
+        let graphs = GpuMetric::ALL
+            .iter()
+            .filter_map(|metric| {
+                gpu.history.get(metric).map(|history| {
+                    widget::column()
+                        .spacing(cosmic.space_xxxs())
+                        .width(iced::Length::Fill)
+                        .push(widget::text::caption(metric.label()))
+                        .push(Self::graph(metric.normalize(history)))
+                        .apply(Element::from)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut rows = widget::column().spacing(cosmic.space_xxs());
+        let mut graphs = graphs.into_iter().peekable();
+        while graphs.peek().is_some() {
+            rows = rows.push(
+                widget::row::with_children(graphs.by_ref().take(2).collect())
+                    .spacing(cosmic.space_xxs())
+                    .height(iced::Length::Fill),
+            );
         }
+        rows.apply(Element::from)
     }
 }
 
@@ -44,31 +204,84 @@ impl super::Page for GpuPage {
 
         match msg {
             Message::GpuPage(GpuMessage::Snapshot(snapshot)) => {
-                for gpu in snapshot.gpus.iter() {
-                    let entity = if let Some(entity) = self.name_to_entity.get(&gpu.name) {
-                        entity.clone()
-                    } else {
-                        let entity = self
-                            .gpu_list
-                            .insert()
-                            .text(gpu.name.clone())
-                            .data(GpuDevice {
-                                info: gpu.clone(),
-                                history: VecDeque::from(vec![0.0; 30]),
-                            })
-                            .id();
-                        self.name_to_entity.insert(gpu.name.clone(), entity.clone());
-                        entity
-                    };
-                    let device = self.gpu_list.data_mut::<GpuDevice>(entity).unwrap();
+                let identities = identify(&snapshot.gpus, &self.cards);
+                let mut present = HashSet::new();
+                for (gpu, identity) in snapshot.gpus.iter().zip(identities) {
+                    present.insert(identity.id.clone());
+                    let device = self
+                        .gpu_list
+                        .upsert(&identity.id, identity.label, || GpuDevice {
+                            info: gpu.clone(),
+                            address: None,
+                            history: HashMap::new(),
+                            processes: GpuDevice::process_table(false),
+                            pid_to_entity: HashMap::new(),
+                            engines: HashMap::new(),
+                            has_engines: false,
+                        });
                     device.info = gpu.clone();
-                    device
-                        .history
-                        .push_back(gpu.core_utilization_percent as f32 / 100.0);
-                    device.history.pop_front();
+                    if device.address != identity.address {
+                        device.engines.clear();
+                    }
+                    device.address = identity.address;
+                    device.sync_processes();
+                    for metric in GpuMetric::ALL {
+                        let value = metric.sample(gpu);
+                        if value.is_none() && !device.history.contains_key(&metric) {
+                            continue;
+                        }
+                        let history = device
+                            .history
+                            .entry(metric)
+                            .or_insert_with(|| VecDeque::from(vec![0.0; 30]));
+                        history.push_back(value.unwrap_or_default());
+                        history.pop_front();
+                    }
+                }
+                self.gpu_list.retain_present(&present);
+            }
+            Message::GpuPage(GpuMessage::Processes(processes)) => {
+                for entity in self.gpu_list.model.iter().collect::<Vec<_>>() {
+                    let Some(device) = self.gpu_list.model.data_mut::<GpuDevice>(entity) else {
+                        continue;
+                    };
+                    let Some(address) = device.address.as_ref() else {
+                        continue;
+                    };
+
+                    device.engines = processes
+                        .iter()
+                        .filter(|process| &process.address == address)
+                        .map(|process| (process.pid, process.clone()))
+                        .collect();
+                    if !device.has_engines && !device.engines.is_empty() {
+                        device.show_engines();
+                    }
+                    device.sync_processes();
+                }
+            }
+            Message::GpuPage(GpuMessage::SelectTab(tab)) => self.gpu_list.model.activate(tab),
+            Message::GpuPage(GpuMessage::SelectMetric(metric)) => self.metric_list.activate(metric),
+            Message::GpuPage(GpuMessage::SelectProcess(process)) => {
+                let active = self.gpu_list.model.active();
+                if let Some(device) = self.gpu_list.model.data_mut::<GpuDevice>(active) {
+                    device.processes.activate(process);
+                }
+            }
+            Message::GpuPage(GpuMessage::SortProcesses(category)) => {
+                let active = self.gpu_list.model.active();
+                if let Some(device) = self.gpu_list.model.data_mut::<GpuDevice>(active) {
+                    if let Some(sort) = device.processes.get_sort() {
+                        if sort.0 == category {
+                            device.processes.sort(category, !sort.1);
+                        } else {
+                            device.processes.sort(category, false)
+                        }
+                    } else {
+                        device.processes.sort(category, false)
+                    }
                 }
             }
-            Message::GpuPage(GpuMessage::SelectTab(tab)) => self.gpu_list.activate(tab),
             _ => {}
         }
 
@@ -82,167 +295,220 @@ impl super::Page for GpuPage {
         widget::column()
             .spacing(cosmic.space_xs())
             .push(
-                widget::tab_bar::horizontal(&self.gpu_list)
+                widget::tab_bar::horizontal(&self.gpu_list.model)
                     .on_activate(|entity| Message::GpuPage(GpuMessage::SelectTab(entity))),
             )
-            .push_maybe(self.gpu_list.active_data::<GpuDevice>().map(|gpu| {
-                widget::row()
-                    .spacing(cosmic.space_xxs())
-                    .push(
-                        widget::canvas(crate::widget::graph::LineGraph {
-                            points: gpu.history.iter().cloned().collect(),
-                        })
-                        .width(iced::Length::Fill)
-                        .height(iced::Length::Fill),
-                    )
+            .push_maybe(self.gpu_list.model.active_data::<GpuDevice>().map(|gpu| {
+                let selected_pid = gpu
+                    .processes
+                    .item(gpu.processes.active())
+                    .map(|item| item.pid);
+
+                widget::column()
+                    .spacing(cosmic.space_xs())
                     .push(
-                        widget::settings::view_column(vec![
-                            widget::settings::section()
-                                .title(fl!("gpu-info"))
-                                .add(widget::settings::item(
-                                    fl!("gpu-name"),
-                                    gpu.info.name.clone().apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("gpu-vendor"),
-                                    gpu.info.vendor.clone().apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("vram-total"),
-                                    gpu.info
-                                        .vram_total_bytes
-                                        .apply(crate::helpers::get_bytes)
-                                        .apply(widget::text::body),
-                                ))
-                                .add_maybe(gpu.info.driver_info.as_ref().map(|driv| {
-                                    widget::settings::item(
-                                        fl!("gpu-kernel-driver"),
-                                        driv.kernel_driver.clone().apply(widget::text::body),
-                                    )
-                                }))
-                                .add_maybe(gpu.info.driver_info.as_ref().map(|driv| {
-                                    widget::settings::item(
-                                        fl!("gpu-user-driver"),
-                                        driv.userspace_driver.clone().apply(widget::text::body),
-                                    )
-                                }))
-                                .add_maybe(gpu.info.driver_info.as_ref().map(|driv| {
-                                    widget::settings::item(
-                                        fl!("gpu-driver-version"),
-                                        driv.driver_version.clone().apply(widget::text::body),
-                                    )
-                                }))
-                                .apply(Element::from),
-                            widget::settings::section()
-                                .title(fl!("gpu-stats"))
-                                .add(widget::settings::item(
-                                    fl!("vram-used"),
-                                    gpu.info
-                                        .vram_used_bytes
-                                        .apply(crate::helpers::get_bytes)
-                                        .apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("core-utilization"),
-                                    format!(
-                                        "{}%",
-                                        gpu.info
-                                            .core_utilization_percent
-                                            .apply(crate::helpers::format_number)
-                                    )
-                                    .apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("vram-utilization"),
-                                    format!(
-                                        "{}%",
-                                        gpu.info
-                                            .memory_utilization_percent
-                                            .apply(crate::helpers::format_number)
+                        widget::row()
+                            .spacing(cosmic.space_xxs())
+                            .height(iced::Length::FillPortion(2))
+                            .push(
+                                widget::column()
+                                    .spacing(cosmic.space_xxs())
+                                    .width(iced::Length::Fill)
+                                    .push(
+                                        widget::segmented_control::horizontal(&self.metric_list)
+                                            .on_activate(|entity| {
+                                                Message::GpuPage(GpuMessage::SelectMetric(entity))
+                                            }),
                                     )
-                                    .apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("gpu-temperature"),
-                                    format!(
-                                        "{}°C",
-                                        gpu.info
-                                            .temperature_celsius
-                                            .apply(crate::helpers::format_number)
-                                    )
-                                    .apply(widget::text::body),
-                                ))
-                                .add_maybe(gpu.info.power_usage_watts.map(|power| {
-                                    widget::settings::item(
-                                        fl!("gpu-power"),
-                                        format!("{} W", power.apply(crate::helpers::format_number))
+                                    .push(self.history_view(gpu)),
+                            )
+                            .push(
+                                widget::settings::view_column(vec![
+                                    widget::settings::section()
+                                        .title(fl!("gpu-info"))
+                                        .add(widget::settings::item(
+                                            fl!("gpu-name"),
+                                            gpu.info.name.clone().apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("gpu-vendor"),
+                                            gpu.info.vendor.clone().apply(widget::text::body),
+                                        ))
+                                        .add_maybe(gpu.address.as_ref().map(|address| {
+                                            widget::settings::item(
+                                                fl!("gpu-pci-address"),
+                                                address.clone().apply(widget::text::body),
+                                            )
+                                        }))
+                                        .add(widget::settings::item(
+                                            fl!("vram-total"),
+                                            gpu.info
+                                                .vram_total_bytes
+                                                .apply(crate::helpers::get_bytes)
+                                                .apply(widget::text::body),
+                                        ))
+                                        .add_maybe(gpu.info.driver_info.as_ref().map(|driv| {
+                                            widget::settings::item(
+                                                fl!("gpu-kernel-driver"),
+                                                driv.kernel_driver
+                                                    .clone()
+                                                    .apply(widget::text::body),
+                                            )
+                                        }))
+                                        .add_maybe(gpu.info.driver_info.as_ref().map(|driv| {
+                                            widget::settings::item(
+                                                fl!("gpu-user-driver"),
+                                                driv.userspace_driver
+                                                    .clone()
+                                                    .apply(widget::text::body),
+                                            )
+                                        }))
+                                        .add_maybe(gpu.info.driver_info.as_ref().map(|driv| {
+                                            widget::settings::item(
+                                                fl!("gpu-driver-version"),
+                                                driv.driver_version
+                                                    .clone()
+                                                    .apply(widget::text::body),
+                                            )
+                                        }))
+                                        .apply(Element::from),
+                                    widget::settings::section()
+                                        .title(fl!("gpu-stats"))
+                                        .add(widget::settings::item(
+                                            fl!("vram-used"),
+                                            gpu.info
+                                                .vram_used_bytes
+                                                .apply(crate::helpers::get_bytes)
+                                                .apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("core-utilization"),
+                                            format!(
+                                                "{}%",
+                                                gpu.info
+                                                    .core_utilization_percent
+                                                    .apply(crate::helpers::format_number)
+                                            )
                                             .apply(widget::text::body),
-                                    )
-                                }))
-                                .add_maybe(gpu.info.core_frequency_mhz.map(|frequency| {
-                                    widget::settings::item(
-                                        fl!("gpu-core-frequency"),
-                                        format!(
-                                            "{} MHz",
-                                            frequency.apply(crate::helpers::format_number)
-                                        )
-                                        .apply(widget::text::body),
-                                    )
-                                }))
-                                .add_maybe(gpu.info.memory_frequency_mhz.map(|frequency| {
-                                    widget::settings::item(
-                                        fl!("gpu-vram-frequency"),
-                                        format!(
-                                            "{} MHz",
-                                            frequency.apply(crate::helpers::format_number)
-                                        )
-                                        .apply(widget::text::body),
-                                    )
-                                }))
-                                .add_maybe(gpu.info.encoder_info.map(|encoder| {
-                                    widget::settings::item(
-                                        fl!("gpu-encode"),
-                                        format!(
-                                            "{}%",
-                                            encoder
-                                                .video_encode_utilization_percent
-                                                .apply(crate::helpers::format_number)
-                                        )
-                                        .apply(widget::text::body),
-                                    )
-                                }))
-                                .add_maybe(gpu.info.encoder_info.map(|encoder| {
-                                    widget::settings::item(
-                                        fl!("gpu-decode"),
-                                        format!(
-                                            "{}%",
-                                            encoder
-                                                .video_decode_utilization_percent
-                                                .apply(crate::helpers::format_number)
-                                        )
-                                        .apply(widget::text::body),
-                                    )
-                                }))
-                                .add(widget::settings::item(
-                                    fl!("gpu-processes"),
-                                    gpu.info
-                                        .process_info
-                                        .len()
-                                        .to_string()
-                                        .apply(widget::text::body),
-                                ))
-                                .apply(widget::scrollable)
-                                .apply(Element::from),
-                        ])
-                        .apply(widget::scrollable),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("vram-utilization"),
+                                            format!(
+                                                "{}%",
+                                                gpu.info
+                                                    .memory_utilization_percent
+                                                    .apply(crate::helpers::format_number)
+                                            )
+                                            .apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("gpu-temperature"),
+                                            format!(
+                                                "{}°C",
+                                                gpu.info
+                                                    .temperature_celsius
+                                                    .apply(crate::helpers::format_number)
+                                            )
+                                            .apply(widget::text::body),
+                                        ))
+                                        .add_maybe(gpu.info.power_usage_watts.map(|power| {
+                                            widget::settings::item(
+                                                fl!("gpu-power"),
+                                                format!(
+                                                    "{} W",
+                                                    power.apply(crate::helpers::format_number)
+                                                )
+                                                .apply(widget::text::body),
+                                            )
+                                        }))
+                                        .add_maybe(gpu.info.core_frequency_mhz.map(|frequency| {
+                                            widget::settings::item(
+                                                fl!("gpu-core-frequency"),
+                                                format!(
+                                                    "{} MHz",
+                                                    frequency.apply(crate::helpers::format_number)
+                                                )
+                                                .apply(widget::text::body),
+                                            )
+                                        }))
+                                        .add_maybe(gpu.info.memory_frequency_mhz.map(|frequency| {
+                                            widget::settings::item(
+                                                fl!("gpu-vram-frequency"),
+                                                format!(
+                                                    "{} MHz",
+                                                    frequency.apply(crate::helpers::format_number)
+                                                )
+                                                .apply(widget::text::body),
+                                            )
+                                        }))
+                                        .add_maybe(gpu.info.encoder_info.map(|encoder| {
+                                            widget::settings::item(
+                                                fl!("gpu-encode"),
+                                                format!(
+                                                    "{}%",
+                                                    encoder
+                                                        .video_encode_utilization_percent
+                                                        .apply(crate::helpers::format_number)
+                                                )
+                                                .apply(widget::text::body),
+                                            )
+                                        }))
+                                        .add_maybe(gpu.info.encoder_info.map(|encoder| {
+                                            widget::settings::item(
+                                                fl!("gpu-decode"),
+                                                format!(
+                                                    "{}%",
+                                                    encoder
+                                                        .video_decode_utilization_percent
+                                                        .apply(crate::helpers::format_number)
+                                                )
+                                                .apply(widget::text::body),
+                                            )
+                                        }))
+                                        .add(widget::settings::item(
+                                            fl!("gpu-processes"),
+                                            gpu.info
+                                                .process_info
+                                                .len()
+                                                .to_string()
+                                                .apply(widget::text::body),
+                                        ))
+                                        .apply(widget::scrollable)
+                                        .apply(Element::from),
+                                ])
+                                .apply(widget::scrollable),
+                            ),
+                    )
+                    .push(widget::text::heading(fl!("gpu-proc-title")))
+                    .push(
+                        widget::table(&gpu.processes)
+                            .on_item_left_click(|entity| {
+                                Message::GpuPage(GpuMessage::SelectProcess(entity))
+                            })
+                            .on_category_left_click(|category| {
+                                Message::GpuPage(GpuMessage::SortProcesses(category))
+                            })
+                            .apply(widget::scrollable)
+                            .height(iced::Length::FillPortion(1)),
+                    )
+                    .push(
+                        widget::row().push(widget::horizontal_space()).push(
+                            fl!("show-in-processes")
+                                .apply(widget::button::text)
+                                .on_press_maybe(selected_pid.map(Message::ShowProcess)),
+                        ),
                     )
                     .apply(Element::from)
             }))
             .apply(Element::from)
     }
 
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
     fn subscription(&self) -> Vec<Subscription<Message>> {
-        vec![Subscription::run(|| {
+        let mut subscriptions = vec![Subscription::run(|| {
             stream::channel(1, |mut sender| async move {
                 let mut service = MonitordServiceClient::connect("http://127.0.0.1:50051")
                     .await
@@ -263,6 +529,238 @@ impl super::Page for GpuPage {
                     }
                 }
             })
-        })]
+        })];
+
+        // Per-engine usage comes from DRM fdinfo, which the daemon does not forward. Finding it
+        // walks the descriptors of all processes, so it is only done while the page is shown.
+        if self.visible {
+            subscriptions.push(Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let mut sampler = DrmSampler::default();
+
+                    loop {
+                        let (returned, processes) = tokio::task::spawn_blocking(move || {
+                            let processes = sampler.sample();
+                            (sampler, processes)
+                        })
+                        .await
+                        .unwrap();
+                        sampler = returned;
+
+                        sender
+                            .send(Message::GpuPage(GpuMessage::Processes(processes)))
+                            .await
+                            .unwrap();
+
+                        tokio::time::sleep(Duration::from_millis(1000)).await;
+                    }
+                })
+            }));
+        }
+
+        subscriptions
+    }
+}
+
+/// Keys GPUs by PCI bus address, so identical cards keep their own tabs and history. The daemon
+/// reports the address where it knows it. GPUs it has none for are matched to one of the
+/// remaining DRM cards on what both sides know about the card: vendor, model name and memory
+/// size, and get its address only when exactly one card fits and no other GPU fits that card.
+/// The rest are keyed by name, and tell identical GPUs apart by the order the daemon lists them
+/// in.
+fn identify(gpus: &[GpuInfo], cards: &[pci::GpuCard]) -> Vec<GpuIdentity> {
+    let reported = gpus
+        .iter()
+        .map(|gpu| gpu.pci_bus_id.as_deref().map(pci::normalize_address))
+        .collect::<Vec<_>>();
+    let cards = cards
+        .iter()
+        .filter(|card| !reported.contains(&Some(card.address.clone())))
+        .collect::<Vec<_>>();
+    let unaddressed = gpus
+        .iter()
+        .zip(&reported)
+        .filter_map(|(gpu, address)| address.is_none().then_some(gpu))
+        .collect::<Vec<_>>();
+
+    let fits = |gpu: &GpuInfo, card: &pci::GpuCard| {
+        pci::vendor_matches(&card.vendor_id, &gpu.vendor) != Some(false)
+            && card
+                .model
+                .as_ref()
+                .map_or(true, |model| model.eq_ignore_ascii_case(gpu.name.trim()))
+            // Drivers round VRAM differently, so sizes within 1% are taken to be the same
+            && card.vram_total_bytes.map_or(true, |vram| {
+                vram.abs_diff(gpu.vram_total_bytes) <= gpu.vram_total_bytes / 100
+            })
+    };
+    let mut occurrences = HashMap::<&str, usize>::new();
+
+    gpus.iter()
+        .zip(reported.iter().cloned())
+        .map(|(gpu, reported)| {
+            let index = occurrences
+                .entry(gpu.name.as_str())
+                .and_modify(|index| *index += 1)
+                .or_insert(1);
+            let duplicated = gpus.iter().filter(|other| other.name == gpu.name).count() > 1;
+
+            let address = reported.or_else(|| {
+                let mut candidates = cards.iter().filter(|card| fits(gpu, card));
+                match (candidates.next(), candidates.next()) {
+                    (Some(card), None)
+                        if unaddressed.iter().filter(|other| fits(other, card)).count() == 1 =>
+                    {
+                        Some(card.address.clone())
+                    }
+                    _ => None,
+                }
+            });
+
+            let id = address
+                .clone()
+                .unwrap_or_else(|| format!("{}#{}", gpu.name, index));
+            let label = match (&address, duplicated) {
+                (_, false) => gpu.name.clone(),
+                (Some(address), true) => fl!(
+                    "gpu-label-address",
+                    name = gpu.name.as_str(),
+                    index = *index,
+                    address = address.as_str()
+                ),
+                (None, true) => fl!("gpu-label", name = gpu.name.as_str(), index = *index),
+            };
+
+            GpuIdentity { id, label, address }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gpu(name: &str, vendor: &str, vram_total_bytes: u64) -> GpuInfo {
+        GpuInfo {
+            name: name.to_owned(),
+            vendor: vendor.to_owned(),
+            vram_total_bytes,
+            ..Default::default()
+        }
+    }
+
+    fn card(address: &str, vendor_id: &str, vram_total_bytes: Option<u64>) -> pci::GpuCard {
+        pci::GpuCard {
+            address: address.to_owned(),
+            vendor_id: vendor_id.to_owned(),
+            model: None,
+            vram_total_bytes,
+        }
+    }
+
+    fn ids(gpus: &[GpuInfo], cards: &[pci::GpuCard]) -> Vec<String> {
+        identify(gpus, cards)
+            .into_iter()
+            .map(|identity| identity.id)
+            .collect()
+    }
+
+    #[test]
+    fn same_vendor_gpus_keep_their_cards_when_reordered() {
+        let cards = [
+            card("0000:03:00.0", "0x1002", Some(8 << 30)),
+            card("0000:0a:00.0", "0x1002", Some(16 << 30)),
+        ];
+        let small = gpu("Radeon RX 6600", "AMD", 8 << 30);
+        let large = gpu("Radeon RX 6800", "AMD", (16 << 30) - (64 << 20));
+
+        assert_eq!(
+            ids(&[small.clone(), large.clone()], &cards),
+            ["0000:03:00.0", "0000:0a:00.0"]
+        );
+        assert_eq!(
+            ids(&[large, small], &cards),
+            ["0000:0a:00.0", "0000:03:00.0"]
+        );
+    }
+
+    #[test]
+    fn unknown_vendor_still_matches() {
+        let cards = [card("0000:00:02.0", "0x1234", None)];
+        assert_eq!(
+            ids(&[gpu("Some GPU", "Some Vendor", 0)], &cards),
+            ["0000:00:02.0"]
+        );
+    }
+
+    #[test]
+    fn indistinguishable_gpus_get_no_address() {
+        let cards = [
+            card("0000:01:00.0", "0x10de", None),
+            card("0000:02:00.0", "0x10de", None),
+        ];
+        let gpus = [
+            gpu("NVIDIA GeForce RTX 3080", "NVIDIA", 10 << 30),
+            gpu("NVIDIA GeForce RTX 3080", "NVIDIA", 10 << 30),
+        ];
+        assert_eq!(
+            ids(&gpus, &cards),
+            ["NVIDIA GeForce RTX 3080#1", "NVIDIA GeForce RTX 3080#2"]
+        );
+    }
+
+    #[test]
+    fn reported_addresses_tell_identical_gpus_apart() {
+        let cards = [
+            card("0000:01:00.0", "0x10de", None),
+            card("0000:02:00.0", "0x10de", None),
+        ];
+        let with_address = |address: &str| GpuInfo {
+            pci_bus_id: Some(address.to_owned()),
+            ..gpu("NVIDIA GeForce RTX 4090", "NVIDIA", 24 << 30)
+        };
+
+        // NVML pads the PCI domain to eight digits
+        assert_eq!(
+            ids(
+                &[
+                    with_address("00000000:02:00.0"),
+                    with_address("00000000:01:00.0")
+                ],
+                &cards
+            ),
+            ["0000:02:00.0", "0000:01:00.0"]
+        );
+    }
+
+    #[test]
+    fn unreported_gpus_match_the_remaining_cards() {
+        let cards = [
+            card("0000:00:02.0", "0x8086", None),
+            card("0000:01:00.0", "0x10de", None),
+        ];
+        let gpus = [
+            GpuInfo {
+                pci_bus_id: Some("0000:01:00.0".to_owned()),
+                ..gpu("NVIDIA GeForce RTX 4090", "NVIDIA", 24 << 30)
+            },
+            gpu("Intel Graphics", "Intel", 0),
+        ];
+        assert_eq!(ids(&gpus, &cards), ["0000:01:00.0", "0000:00:02.0"]);
+    }
+
+    #[test]
+    fn nvidia_model_names_tell_cards_apart() {
+        let mut cards = [
+            card("0000:01:00.0", "0x10de", None),
+            card("0000:02:00.0", "0x10de", None),
+        ];
+        cards[0].model = Some("NVIDIA GeForce RTX 4090".to_owned());
+        cards[1].model = Some("NVIDIA GeForce RTX 3060".to_owned());
+        let gpus = [
+            gpu("NVIDIA GeForce RTX 3060", "NVIDIA Corporation", 12 << 30),
+            gpu("NVIDIA GeForce RTX 4090", "NVIDIA Corporation", 24 << 30),
+        ];
+        assert_eq!(ids(&gpus, &cards), ["0000:02:00.0", "0000:01:00.0"]);
     }
 }
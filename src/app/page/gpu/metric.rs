@@ -0,0 +1,77 @@
+use std::collections::VecDeque;
+
+use monitord_protocols::monitord::GpuInfo;
+
+use crate::fl;
+
+/// A GPU statistic that is kept as a history and can be graphed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GpuMetric {
+    Utilization,
+    Vram,
+    Temperature,
+    Power,
+    CoreClock,
+    MemoryClock,
+    Encoder,
+    Decoder,
+}
+
+impl GpuMetric {
+    pub const ALL: [GpuMetric; 8] = [
+        Self::Utilization,
+        Self::Vram,
+        Self::Temperature,
+        Self::Power,
+        Self::CoreClock,
+        Self::MemoryClock,
+        Self::Encoder,
+        Self::Decoder,
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Utilization => fl!("core-utilization"),
+            Self::Vram => fl!("vram-used"),
+            Self::Temperature => fl!("gpu-temperature"),
+            Self::Power => fl!("gpu-power"),
+            Self::CoreClock => fl!("gpu-core-frequency"),
+            Self::MemoryClock => fl!("gpu-vram-frequency"),
+            Self::Encoder => fl!("gpu-encode"),
+            Self::Decoder => fl!("gpu-decode"),
+        }
+    }
+
+    /// Reads the raw value of this metric from a snapshot, if the GPU reports it
+    pub fn sample(self, info: &GpuInfo) -> Option<f32> {
+        match self {
+            Self::Utilization => Some(info.core_utilization_percent as f32),
+            Self::Vram => (info.vram_total_bytes > 0)
+                .then(|| info.vram_used_bytes as f32 / info.vram_total_bytes as f32 * 100.0),
+            Self::Temperature => Some(info.temperature_celsius as f32),
+            Self::Power => info.power_usage_watts.map(|power| power as f32),
+            Self::CoreClock => info.core_frequency_mhz.map(|frequency| frequency as f32),
+            Self::MemoryClock => info.memory_frequency_mhz.map(|frequency| frequency as f32),
+            Self::Encoder => info
+                .encoder_info
+                .map(|encoder| encoder.video_encode_utilization_percent as f32),
+            Self::Decoder => info
+                .encoder_info
+                .map(|encoder| encoder.video_decode_utilization_percent as f32),
+        }
+    }
+
+    /// Scales a history to the graph's range of 0 to 1. Percentages use a fixed scale,
+    /// temperatures are shown up to at least 100°C and everything else is relative to the
+    /// highest value seen.
+    pub fn normalize(self, history: &VecDeque<f32>) -> Vec<f32> {
+        // `f32::max` ignores NaN, which the daemon reports for 0/0 utilization
+        let max = history.iter().cloned().fold(0.0, f32::max);
+        let scale = match self {
+            Self::Utilization | Self::Vram | Self::Encoder | Self::Decoder => 100.0,
+            Self::Temperature => max.max(100.0),
+            Self::Power | Self::CoreClock | Self::MemoryClock => max.max(1.0),
+        };
+        history.iter().map(|value| value / scale).collect()
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    fl, helpers,
+    sys::drm::{GpuProcess, GpuProcessKind},
+};
+use cosmic::{iced::Length, widget};
+use lazy_static::lazy_static;
+use monitord_protocols::monitord::GpuProcessInfo;
+use std::borrow::Cow;
+
+lazy_static! {
+    static ref GPU_PROC_PID: String = fl!("gpu-proc-pid");
+    static ref GPU_PROC_NAME: String = fl!("name");
+    static ref GPU_PROC_USAGE: String = fl!("gpu-proc-usage");
+    static ref GPU_PROC_VRAM: String = fl!("gpu-proc-vram");
+    static ref GPU_PROC_ENCODE: String = fl!("gpu-proc-encode");
+    static ref GPU_PROC_DECODE: String = fl!("gpu-proc-decode");
+    static ref GPU_PROC_TYPE: String = fl!("gpu-proc-type");
+    static ref GPU_PROC_GRAPHICS: String = fl!("gpu-proc-graphics");
+    static ref GPU_PROC_COMPUTE: String = fl!("gpu-proc-compute");
+}
+
+/// A process using the GPU as reported by the daemon, with engine usage from DRM fdinfo where
+/// the driver provides it
+pub struct GpuProcessTableItem {
+    pub pid: u32,
+    name: String,
+    utilization_percent: f64,
+    vram_bytes: u64,
+    engines: Option<GpuProcess>,
+    pid_text: Cow<'static, str>,
+    name_text: Cow<'static, str>,
+    usage: Cow<'static, str>,
+    vram: Cow<'static, str>,
+    encode: Cow<'static, str>,
+    decode: Cow<'static, str>,
+}
+
+impl GpuProcessTableItem {
+    pub fn new(process: &GpuProcessInfo, engines: Option<&GpuProcess>) -> Self {
+        let mut item = Self {
+            pid: process.pid,
+            name: String::new(),
+            utilization_percent: process.gpu_utilization_percent,
+            vram_bytes: process.gpu_memory_bytes,
+            engines: None,
+            pid_text: process.pid.to_string().into(),
+            name_text: Cow::Borrowed(""),
+            usage: Self::percent_text(Some(process.gpu_utilization_percent)),
+            vram: helpers::get_bytes(process.gpu_memory_bytes).into(),
+            encode: Cow::Borrowed(""),
+            decode: Cow::Borrowed(""),
+        };
+        item.update(process, engines);
+        item
+    }
+
+    /// Refreshes the row in place, only reformatting the columns whose values changed
+    pub fn update(&mut self, process: &GpuProcessInfo, engines: Option<&GpuProcess>) {
+        // Processes the daemon could not name get the name the kernel knows them by
+        let name = Some(process.process_name.as_str())
+            .filter(|name| !name.is_empty())
+            .or(engines.map(|engines| engines.name.as_str()))
+            .unwrap_or_default();
+        if name != self.name {
+            self.name = name.to_owned();
+            self.name_text = self.name.clone().into();
+        }
+        if process.gpu_utilization_percent != self.utilization_percent {
+            self.utilization_percent = process.gpu_utilization_percent;
+            self.usage = Self::percent_text(Some(self.utilization_percent));
+        }
+        if process.gpu_memory_bytes != self.vram_bytes {
+            self.vram_bytes = process.gpu_memory_bytes;
+            self.vram = helpers::get_bytes(self.vram_bytes).into();
+        }
+        if engines != self.engines.as_ref() {
+            self.encode = Self::percent_text(engines.map(|engines| engines.encode_percent));
+            self.decode = Self::percent_text(engines.map(|engines| engines.decode_percent));
+            self.engines = engines.cloned();
+        }
+    }
+
+    /// Engine usage the driver does not report is left blank rather than shown as idle
+    fn percent_text(percent: Option<f64>) -> Cow<'static, str> {
+        percent.map_or(Cow::Borrowed(""), |percent| {
+            format!("{}%", percent.round()).into()
+        })
+    }
+}
+
+impl widget::table::ItemInterface<GpuProcessTableCategory> for GpuProcessTableItem {
+    fn get_icon(&self, category: GpuProcessTableCategory) -> Option<widget::Icon> {
+        match category {
+            GpuProcessTableCategory::Name => {
+                Some(widget::icon::from_name("application-default-symbolic").icon())
+            }
+            _ => None,
+        }
+    }
+
+    fn get_text(&self, category: GpuProcessTableCategory) -> Cow<'static, str> {
+        match category {
+            GpuProcessTableCategory::Pid => self.pid_text.clone(),
+            GpuProcessTableCategory::Name => self.name_text.clone(),
+            GpuProcessTableCategory::Usage => self.usage.clone(),
+            GpuProcessTableCategory::Vram => self.vram.clone(),
+            GpuProcessTableCategory::Encode => self.encode.clone(),
+            GpuProcessTableCategory::Decode => self.decode.clone(),
+            GpuProcessTableCategory::Type => {
+                match self.engines.as_ref().map(|engines| engines.kind) {
+                    Some(GpuProcessKind::Graphics) => GPU_PROC_GRAPHICS.as_str().into(),
+                    Some(GpuProcessKind::Compute) => GPU_PROC_COMPUTE.as_str().into(),
+                    None => Cow::Borrowed(""),
+                }
+            }
+        }
+    }
+
+    fn compare(&self, other: &Self, category: GpuProcessTableCategory) -> std::cmp::Ordering {
+        let engine = |item: &Self, percent: fn(&GpuProcess) -> f64| {
+            item.engines.as_ref().map(percent).unwrap_or(-1.0)
+        };
+        match category {
+            GpuProcessTableCategory::Pid => self.pid.cmp(&other.pid),
+            GpuProcessTableCategory::Name => other
+                .name
+                .to_ascii_lowercase()
+                .cmp(&self.name.to_ascii_lowercase()),
+            GpuProcessTableCategory::Usage => self
+                .utilization_percent
+                .total_cmp(&other.utilization_percent),
+            GpuProcessTableCategory::Vram => self.vram_bytes.cmp(&other.vram_bytes),
+            GpuProcessTableCategory::Encode => engine(self, |engines| engines.encode_percent)
+                .total_cmp(&engine(other, |engines| engines.encode_percent)),
+            GpuProcessTableCategory::Decode => engine(self, |engines| engines.decode_percent)
+                .total_cmp(&engine(other, |engines| engines.decode_percent)),
+            GpuProcessTableCategory::Type => {
+                let kind = |item: &Self| item.engines.as_ref().map(|engines| engines.kind as u8);
+                kind(self).cmp(&kind(other))
+            }
+        }
+    }
+}
+
+#[derive(Default, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum GpuProcessTableCategory {
+    Pid,
+    #[default]
+    Name,
+    Usage,
+    Vram,
+    Encode,
+    Decode,
+    Type,
+}
+
+impl std::fmt::Display for GpuProcessTableCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Pid => GPU_PROC_PID.as_str(),
+                Self::Name => GPU_PROC_NAME.as_str(),
+                Self::Usage => GPU_PROC_USAGE.as_str(),
+                Self::Vram => GPU_PROC_VRAM.as_str(),
+                Self::Encode => GPU_PROC_ENCODE.as_str(),
+                Self::Decode => GPU_PROC_DECODE.as_str(),
+                Self::Type => GPU_PROC_TYPE.as_str(),
+            }
+        )
+    }
+}
+
+impl widget::table::ItemCategory for GpuProcessTableCategory {
+    fn width(&self) -> cosmic::iced::Length {
+        match self {
+            Self::Pid => Length::Fixed(80.0),
+            Self::Name => Length::Fixed(240.0),
+            Self::Usage => Length::Fixed(80.0),
+            Self::Vram => Length::Fixed(100.0),
+            Self::Encode => Length::Fixed(80.0),
+            Self::Decode => Length::Fixed(80.0),
+            Self::Type => Length::Fixed(100.0),
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use std::{fmt::Write, sync::Arc, time::Duration};
+
+use cosmic::{
+    iced::{self, stream, Subscription},
+    prelude::*,
+    widget,
+};
+use futures_util::SinkExt;
+
+use crate::{
+    app::Message,
+    fl,
+    sys::hardware::{self, Dmi, IdNames, Inventory, PciDevice, UsbDevice},
+};
+
+/// Messages that are emitted that are relevant to the Hardware page
+#[derive(Debug, Clone)]
+pub enum HardwareMessage {
+    Inventory(Inventory),
+    /// Puts the whole inventory on the clipboard as plain text
+    Copy,
+}
+
+/// A device in a PCI or USB tree, flattened with its depth for display
+struct TreeRow {
+    depth: u16,
+    name: String,
+    detail: String,
+    driver: String,
+}
+
+pub struct HardwarePage {
+    inventory: Option<Inventory>,
+}
+
+impl HardwarePage {
+    pub fn new() -> Self {
+        Self { inventory: None }
+    }
+
+    /// Product, board, firmware and chassis, leaving out what the firmware does not fill in
+    fn board_fields(dmi: &Dmi) -> Vec<(String, String)> {
+        let join = |parts: &[&Option<String>]| {
+            Some(
+                parts
+                    .iter()
+                    .filter_map(|part| part.as_deref())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+            .filter(|joined| !joined.is_empty())
+        };
+
+        let bios = join(&[&dmi.bios_vendor, &dmi.bios_version]).map(|bios| match &dmi.bios_date {
+            Some(date) => format!("{bios} ({date})"),
+            None => bios,
+        });
+        [
+            (
+                fl!("hardware-product"),
+                join(&[&dmi.system_vendor, &dmi.product_name, &dmi.product_version]),
+            ),
+            (
+                fl!("hardware-board-name"),
+                join(&[&dmi.board_vendor, &dmi.board_name, &dmi.board_version]),
+            ),
+            (fl!("hardware-bios"), bios),
+            (
+                fl!("hardware-chassis"),
+                dmi.chassis.map(|chassis| chassis.to_owned()),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| Some((label, value?)))
+        .collect()
+    }
+
+    fn driver_text(driver: Option<&str>) -> String {
+        match driver {
+            Some(driver) => fl!("hardware-driver", driver = driver),
+            None => fl!("hardware-no-driver"),
+        }
+    }
+
+    fn pci_rows(devices: &[PciDevice], depth: u16, rows: &mut Vec<TreeRow>) {
+        for device in devices {
+            rows.push(TreeRow {
+                depth,
+                name: format!("{} {}", device.vendor, device.device),
+                detail: format!("{} · {}", device.class, device.address),
+                driver: Self::driver_text(device.driver.as_deref()),
+            });
+            Self::pci_rows(&device.children, depth + 1, rows);
+        }
+    }
+
+    fn usb_rows(devices: &[UsbDevice], depth: u16, rows: &mut Vec<TreeRow>) {
+        for device in devices {
+            let mut detail = format!("{} · {}", device.id, device.port);
+            if let Some(speed) = &device.speed {
+                detail.push_str(&format!(
+                    " · {}",
+                    fl!("hardware-speed", speed = speed.clone())
+                ));
+            }
+            rows.push(TreeRow {
+                depth,
+                name: format!("{} {}", device.vendor, device.product),
+                detail,
+                driver: Self::driver_text(
+                    Some(device.drivers.join(", "))
+                        .filter(|drivers| !drivers.is_empty())
+                        .as_deref(),
+                ),
+            });
+            Self::usb_rows(&device.children, depth + 1, rows);
+        }
+    }
+
+    fn tree_section<'a>(title: String, rows: Vec<TreeRow>) -> Option<Element<'a, Message>> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        if rows.is_empty() {
+            return None;
+        }
+
+        rows.into_iter()
+            .fold(widget::settings::section().title(title), |section, row| {
+                section.add(widget::settings::item_row(vec![
+                    widget::horizontal_space()
+                        .width(iced::Length::Fixed(f32::from(row.depth * cosmic.space_m())))
+                        .apply(Element::from),
+                    widget::column()
+                        .push(widget::text::body(row.name))
+                        .push(widget::text::caption(row.detail))
+                        .apply(Element::from),
+                    widget::horizontal_space().apply(Element::from),
+                    widget::text::caption(row.driver).apply(Element::from),
+                ]))
+            })
+            .apply(Element::from)
+            .apply(Some)
+    }
+
+    /// The inventory as indented plain text, laid out like the page
+    fn report(inventory: &Inventory) -> String {
+        let mut report = String::new();
+
+        let _ = writeln!(report, "{}", fl!("hardware-board"));
+        for (label, value) in Self::board_fields(&inventory.dmi) {
+            let _ = writeln!(report, "  {label}: {value}");
+        }
+
+        let mut trees = Vec::new();
+        let mut rows = Vec::new();
+        Self::pci_rows(&inventory.pci, 1, &mut rows);
+        trees.push((fl!("hardware-pci"), rows));
+        let mut rows = Vec::new();
+        Self::usb_rows(&inventory.usb, 1, &mut rows);
+        trees.push((fl!("hardware-usb"), rows));
+        for (title, rows) in trees {
+            let _ = writeln!(report, "\n{title}");
+            for row in rows {
+                let indent = "  ".repeat(usize::from(row.depth));
+                let _ = writeln!(
+                    report,
+                    "{indent}{} [{}] {}",
+                    row.name, row.detail, row.driver
+                );
+            }
+        }
+
+        let _ = writeln!(report, "\n{}", fl!("hardware-audio"));
+        for card in inventory.sound.iter() {
+            let _ = writeln!(report, "  {}: {} [{}]", card.index, card.name, card.id);
+        }
+
+        let _ = writeln!(report, "\n{}", fl!("hardware-input"));
+        for device in inventory.input.iter() {
+            let _ = writeln!(report, "  {} [{}]", device.name, device.handlers.join(" "));
+        }
+
+        report
+    }
+}
+
+impl super::Page for HardwarePage {
+    fn update(&mut self, msg: crate::app::Message) -> cosmic::app::Task<crate::app::Message> {
+        let mut tasks = Vec::new();
+        match msg {
+            Message::HardwarePage(HardwareMessage::Inventory(inventory)) => {
+                self.inventory = Some(inventory);
+            }
+            Message::HardwarePage(HardwareMessage::Copy) => {
+                if let Some(inventory) = &self.inventory {
+                    tasks.push(iced::clipboard::write(Self::report(inventory)));
+                }
+            }
+            _ => {}
+        }
+
+        cosmic::app::Task::batch(tasks)
+    }
+
+    fn view(&self) -> Element<Message> {
+        let Some(inventory) = &self.inventory else {
+            return widget::horizontal_space().apply(Element::from);
+        };
+
+        let board = Self::board_fields(&inventory.dmi);
+        let board =
+            (!board.is_empty()).then(|| {
+                widget::settings::section()
+                    .title(fl!("hardware-board"))
+                    .extend(board.into_iter().map(|(label, value)| {
+                        widget::settings::item(label, widget::text::body(value))
+                    }))
+                    .apply(Element::from)
+            });
+
+        let mut pci = Vec::new();
+        Self::pci_rows(&inventory.pci, 0, &mut pci);
+        let mut usb = Vec::new();
+        Self::usb_rows(&inventory.usb, 0, &mut usb);
+
+        let audio = (!inventory.sound.is_empty()).then(|| {
+            widget::settings::section()
+                .title(fl!("hardware-audio"))
+                .extend(inventory.sound.iter().map(|card| {
+                    widget::settings::item::builder(card.name.clone())
+                        .description(format!("{} · {}", card.index, card.id))
+                        .control(widget::horizontal_space())
+                }))
+                .apply(Element::from)
+        });
+
+        let input = (!inventory.input.is_empty()).then(|| {
+            widget::settings::section()
+                .title(fl!("hardware-input"))
+                .extend(inventory.input.iter().map(|device| {
+                    widget::settings::item(
+                        device.name.clone(),
+                        widget::text::caption(device.handlers.join(" ")),
+                    )
+                }))
+                .apply(Element::from)
+        });
+
+        let copy = widget::row()
+            .push(widget::horizontal_space())
+            .push(
+                fl!("hardware-copy")
+                    .apply(widget::button::standard)
+                    .on_press(Message::HardwarePage(HardwareMessage::Copy)),
+            )
+            .apply(Element::from);
+
+        widget::settings::view_column(
+            [
+                Some(copy),
+                board,
+                Self::tree_section(fl!("hardware-pci"), pci),
+                Self::tree_section(fl!("hardware-usb"), usb),
+                audio,
+                input,
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+        )
+        .apply(widget::scrollable)
+        .apply(Element::from)
+    }
+
+    fn subscription(&self) -> Vec<Subscription<Message>> {
+        vec![Subscription::run(|| {
+            stream::channel(1, |mut sender| async move {
+                // The ID databases are several megabytes, so they are parsed once
+                let names = Arc::new(tokio::task::spawn_blocking(IdNames::load).await.unwrap());
+                loop {
+                    let names = names.clone();
+                    let inventory = tokio::task::spawn_blocking(move || hardware::read(&names))
+                        .await
+                        .unwrap();
+                    sender
+                        .send(Message::HardwarePage(HardwareMessage::Inventory(inventory)))
+                        .await
+                        .unwrap();
+
+                    // Devices come and go rarely, hotplugged ones show up on the next read
+                    tokio::time::sleep(Duration::from_millis(10000)).await;
+                }
+            })
+        })]
+    }
+}
@@ -1,3 +1,5 @@
+mod module;
+
 use cosmic::{
     iced::{self, stream, Subscription},
     prelude::*,
@@ -9,26 +11,417 @@ use monitord_protocols::{
     protocols::MonitordServiceClient,
 };
 use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
-use crate::{app::Message, fl};
+use super::pressure::PressureHistory;
+use crate::{
+    app::Message,
+    fl,
+    helpers::{format_number, get_bytes},
+    sys::{
+        dimm::{self, MemoryModule},
+        memory::{self, Compression, MemoryComposition, MemoryDetails},
+        vmstat::{self, OomVictim, PagingActivity, VmstatSampler},
+    },
+};
+use module::{MemoryModuleTableCategory, MemoryModuleTableItem};
 
 /// Messages that are emitted relevant to the Memory Page
 #[derive(Debug, Clone)]
 pub enum MemoryMessage {
     Snapshot(MemoryInfo),
+    Details(MemoryDetails),
+    Modules(Vec<MemoryModule>),
+    SelectModule(widget::table::Entity),
+    SortModules(MemoryModuleTableCategory),
+    Paging(PagingActivity),
+    OomKill(OomVictim),
+    SelectGraph(widget::segmented_button::Entity),
+}
+
+/// Which history the graph shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MemoryGraph {
+    Composition,
+    Swap,
+    Paging,
+    Faults,
+}
+
+/// A run of the kernel OOM killer, placed on the timeline by paging sample
+struct OomEvent {
+    /// Only known when the kernel log is readable
+    victim: Option<OomVictim>,
+    sample: u64,
+    at: Instant,
 }
 
+/// Samples shown in every history
+const HISTORY_LEN: usize = 30;
+/// Older OOM events are forgotten
+const OOM_EVENT_LIMIT: usize = 20;
+
 pub struct MemoryPage {
     memory_usage_history: VecDeque<f32>,
     memory_info: Option<MemoryInfo>,
+    composition_history: VecDeque<MemoryComposition>,
+    memory_details: Option<MemoryDetails>,
+    pressure: PressureHistory,
+    modules: Vec<MemoryModule>,
+    module_model:
+        widget::table::SingleSelectModel<MemoryModuleTableItem, MemoryModuleTableCategory>,
+    graph_list: widget::segmented_button::SingleSelectModel,
+    // Used swap (out of the total)
+    swap_history: VecDeque<f32>,
+    paging_history: VecDeque<PagingActivity>,
+    paging: Option<PagingActivity>,
+    // Paging samples seen so far, the timeline OOM events are placed on
+    paging_samples: u64,
+    oom_events: VecDeque<OomEvent>,
 }
 
 impl MemoryPage {
     pub fn new() -> Self {
+        let mut graph_list = widget::segmented_button::SingleSelectModel::default();
+        graph_list
+            .insert()
+            .text(fl!("memory-composition"))
+            .data(MemoryGraph::Composition)
+            .activate();
+        graph_list
+            .insert()
+            .text(fl!("swap"))
+            .data(MemoryGraph::Swap);
+        graph_list
+            .insert()
+            .text(fl!("memory-paging"))
+            .data(MemoryGraph::Paging);
+        graph_list
+            .insert()
+            .text(fl!("major-faults"))
+            .data(MemoryGraph::Faults);
+
         Self {
             memory_usage_history: VecDeque::from(vec![0.0; 30]),
             memory_info: None,
+            composition_history: VecDeque::from(vec![MemoryComposition::default(); 30]),
+            memory_details: None,
+            pressure: PressureHistory::new(),
+            modules: Vec::new(),
+            module_model: widget::table::SingleSelectModel::new(vec![
+                MemoryModuleTableCategory::Slot,
+                MemoryModuleTableCategory::Size,
+                MemoryModuleTableCategory::Type,
+                MemoryModuleTableCategory::Speed,
+                MemoryModuleTableCategory::ConfiguredSpeed,
+                MemoryModuleTableCategory::Manufacturer,
+                MemoryModuleTableCategory::PartNumber,
+                MemoryModuleTableCategory::Ecc,
+                MemoryModuleTableCategory::Errors,
+            ]),
+            graph_list,
+            swap_history: VecDeque::from(vec![0.0; HISTORY_LEN]),
+            paging_history: VecDeque::from(vec![PagingActivity::default(); HISTORY_LEN]),
+            paging: None,
+            paging_samples: 0,
+            oom_events: VecDeque::new(),
+        }
+    }
+
+    /// Positions of OOM kills that are still within the graphed history
+    fn oom_markers(&self) -> Vec<usize> {
+        self.oom_events
+            .iter()
+            .filter_map(|event| {
+                let age = self.paging_samples.checked_sub(event.sample)? as usize;
+                (HISTORY_LEN - 1).checked_sub(age)
+            })
+            .collect()
+    }
+
+    /// Rates scaled to the busiest sample in the history
+    fn normalized(series: Vec<Vec<f64>>) -> Vec<Vec<f32>> {
+        let max = (0..HISTORY_LEN)
+            .map(|i| {
+                series
+                    .iter()
+                    .filter_map(|values| values.get(i))
+                    .sum::<f64>()
+            })
+            .fold(1.0, f64::max);
+        series
+            .into_iter()
+            .map(|values| {
+                values
+                    .into_iter()
+                    .map(|value| (value / max) as f32)
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn legend(labels: Vec<String>) -> Element<'static, Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        let legend = labels
+            .into_iter()
+            .enumerate()
+            .map(|(index, label)| {
+                widget::row()
+                    .spacing(cosmic.space_xxxs())
+                    .align_y(iced::Alignment::Center)
+                    .push(
+                        widget::canvas(crate::widget::graph::LegendSwatch { index })
+                            .width(iced::Length::Fixed(10.0))
+                            .height(iced::Length::Fixed(10.0)),
+                    )
+                    .push(widget::text::caption(label))
+                    .apply(Element::from)
+            })
+            .collect();
+        widget::flex_row(legend)
+            .column_spacing(cosmic.space_xs())
+            .apply(Element::from)
+    }
+
+    fn composition_labels() -> [String; 8] {
+        [
+            fl!("memory-used"),
+            fl!("memory-page-tables"),
+            fl!("memory-slab"),
+            fl!("memory-huge-pages"),
+            fl!("memory-shared"),
+            fl!("memory-dirty"),
+            fl!("memory-cached"),
+            fl!("memory-buffers"),
+        ]
+    }
+
+    /// Composition bar and history, or overall load until the first breakdown arrives
+    fn history_view(&self) -> Element<Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        let Some(details) = &self.memory_details else {
+            return widget::canvas(crate::widget::graph::LineGraph {
+                points: self.memory_usage_history.iter().cloned().collect(),
+            })
+            .width(iced::Length::Fill)
+            .height(iced::Length::Fill)
+            .apply(Element::from);
+        };
+
+        let fraction = |composition: &MemoryComposition, value: u64| {
+            value as f32 / composition.total.max(1) as f32
+        };
+        let bar = widget::canvas(crate::widget::graph::StackedBar {
+            values: details
+                .composition
+                .values()
+                .iter()
+                .map(|value| fraction(&details.composition, *value))
+                .collect(),
+        })
+        .width(iced::Length::Fill)
+        .height(iced::Length::Fixed(24.0));
+
+        let series = (0..8)
+            .map(|part| {
+                self.composition_history
+                    .iter()
+                    .map(|composition| fraction(composition, composition.values()[part]))
+                    .collect()
+            })
+            .collect();
+
+        widget::column()
+            .spacing(cosmic.space_xxs())
+            .width(iced::Length::Fill)
+            .push(bar)
+            .push(self.graph(series))
+            .push(Self::legend(Self::composition_labels().to_vec()))
+            .apply(Element::from)
+    }
+
+    fn graph(&self, series: Vec<Vec<f32>>) -> Element<Message> {
+        widget::canvas(crate::widget::graph::StackedGraph {
+            series,
+            markers: self.oom_markers(),
+        })
+        .width(iced::Length::Fill)
+        .height(iced::Length::Fill)
+        .apply(Element::from)
+    }
+
+    /// The selected history, with OOM kills marked on every timeline
+    fn graph_view(&self) -> Element<Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        let graph = match self.graph_list.active_data::<MemoryGraph>() {
+            Some(MemoryGraph::Swap) => widget::column()
+                .spacing(cosmic.space_xxs())
+                .push(self.graph(vec![self.swap_history.iter().cloned().collect()]))
+                .push(Self::legend(vec![fl!("used-swap")]))
+                .apply(Element::from),
+            Some(MemoryGraph::Paging) => widget::column()
+                .spacing(cosmic.space_xxs())
+                .push(self.graph(Self::normalized(vec![
+                    self.paging_history
+                        .iter()
+                        .map(|paging| paging.page_in_bytes_per_sec)
+                        .collect(),
+                    self.paging_history
+                        .iter()
+                        .map(|paging| paging.page_out_bytes_per_sec)
+                        .collect(),
+                ])))
+                .push(Self::legend(vec![fl!("paged-in"), fl!("paged-out")]))
+                .apply(Element::from),
+            Some(MemoryGraph::Faults) => widget::column()
+                .spacing(cosmic.space_xxs())
+                .push(self.graph(Self::normalized(vec![self
+                    .paging_history
+                    .iter()
+                    .map(|paging| paging.major_faults_per_sec)
+                    .collect()])))
+                .push(Self::legend(vec![fl!("major-faults")]))
+                .apply(Element::from),
+            _ => self.history_view(),
+        };
+
+        widget::column()
+            .spacing(cosmic.space_xxs())
+            .width(iced::Length::Fill)
+            .push(
+                widget::segmented_control::horizontal(&self.graph_list)
+                    .on_activate(|entity| Message::MemoryPage(MemoryMessage::SelectGraph(entity))),
+            )
+            .push(graph)
+            .apply(Element::from)
+    }
+
+    fn paging_section(&self) -> Option<Element<Message>> {
+        let paging = self.paging?;
+        let rate = |bytes: f64| format!("{}/s", get_bytes(bytes as u64));
+        let per_sec = |count: f64| format!("{}/s", count.round());
+
+        widget::settings::section()
+            .title(fl!("memory-paging"))
+            .add(widget::settings::item(
+                fl!("paged-in"),
+                rate(paging.page_in_bytes_per_sec).apply(widget::text::body),
+            ))
+            .add(widget::settings::item(
+                fl!("paged-out"),
+                rate(paging.page_out_bytes_per_sec).apply(widget::text::body),
+            ))
+            .add(widget::settings::item(
+                fl!("swapped-in"),
+                fl!("pages-per-sec", pages = per_sec(paging.swap_in_per_sec))
+                    .apply(widget::text::body),
+            ))
+            .add(widget::settings::item(
+                fl!("swapped-out"),
+                fl!("pages-per-sec", pages = per_sec(paging.swap_out_per_sec))
+                    .apply(widget::text::body),
+            ))
+            .add(widget::settings::item(
+                fl!("major-faults"),
+                per_sec(paging.major_faults_per_sec).apply(widget::text::body),
+            ))
+            .add_maybe(paging.oom_kills.map(|kills| {
+                widget::settings::item(
+                    fl!("oom-kills"),
+                    kills.to_string().apply(widget::text::body),
+                )
+            }))
+            .apply(Element::from)
+            .apply(Some)
+    }
+
+    /// Most recent OOM kills first
+    fn oom_section(&self) -> Option<Element<Message>> {
+        if self.oom_events.is_empty() {
+            return None;
         }
+
+        self.oom_events
+            .iter()
+            .rev()
+            .fold(
+                widget::settings::section().title(fl!("oom-events")),
+                |section, event| {
+                    section.add(widget::settings::item(
+                        match &event.victim {
+                            Some(victim) => {
+                                fl!("oom-victim", name = victim.name.as_str(), pid = victim.pid)
+                            }
+                            None => fl!("oom-unknown-victim"),
+                        },
+                        fl!("seconds-ago", seconds = event.at.elapsed().as_secs())
+                            .apply(widget::text::body),
+                    ))
+                },
+            )
+            .apply(Element::from)
+            .apply(Some)
+    }
+
+    fn composition_section(details: &MemoryDetails) -> Element<Message> {
+        let composition = &details.composition;
+        Self::composition_labels()
+            .into_iter()
+            .zip(composition.values())
+            .chain([
+                (fl!("memory-free"), composition.free),
+                (fl!("memory-available"), composition.available),
+            ])
+            .fold(
+                widget::settings::section().title(fl!("memory-composition")),
+                |section, (label, bytes)| {
+                    section.add(widget::settings::item(
+                        label,
+                        get_bytes(bytes).apply(widget::text::body),
+                    ))
+                },
+            )
+            .apply(Element::from)
+    }
+
+    fn swap_section(details: &MemoryDetails) -> Element<Message> {
+        let swap = &details.swap;
+        let compression = |title: String, compression: &Compression| {
+            widget::settings::item(
+                title,
+                fl!(
+                    "compressed-size",
+                    original = get_bytes(compression.original_bytes),
+                    compressed = get_bytes(compression.compressed_bytes),
+                    ratio = format_number((compression.ratio() * 100.0).round() / 100.0)
+                )
+                .apply(widget::text::body),
+            )
+        };
+
+        widget::settings::section()
+            .title(fl!("swap"))
+            .add(widget::settings::item(
+                fl!("swap-cached"),
+                get_bytes(swap.cached).apply(widget::text::body),
+            ))
+            .add_maybe(
+                swap.zram
+                    .as_ref()
+                    .map(|zram| compression(fl!("zram"), zram)),
+            )
+            .add_maybe(
+                swap.zswap
+                    .as_ref()
+                    .map(|zswap| compression(fl!("zswap"), zswap)),
+            )
+            .apply(Element::from)
     }
 }
 
@@ -36,6 +429,7 @@ impl super::Page for MemoryPage {
     fn update(&mut self, msg: Message) -> cosmic::app::Task<Message> {
         let tasks = Vec::new();
         match msg {
+            Message::Pressure(pressure) => self.pressure.push(pressure.memory),
             Message::MemoryPage(MemoryMessage::Snapshot(snapshot)) => {
                 self.memory_usage_history
                     .push_back(snapshot.memory_load_percent as f32 / 100.0);
@@ -43,6 +437,82 @@ impl super::Page for MemoryPage {
 
                 self.memory_info = Some(snapshot);
             }
+            Message::MemoryPage(MemoryMessage::Details(details)) => {
+                self.composition_history.push_back(details.composition);
+                self.composition_history.pop_front();
+                self.swap_history
+                    .push_back(details.swap.used as f32 / details.swap.total.max(1) as f32);
+                self.swap_history.pop_front();
+
+                self.memory_details = Some(details);
+            }
+            // Modules only change when error counters do, so the table is rebuilt only then
+            Message::MemoryPage(MemoryMessage::Modules(modules)) if modules != self.modules => {
+                let old_sort = self.module_model.get_sort();
+                self.module_model.clear();
+                for module in &modules {
+                    self.module_model
+                        .insert(MemoryModuleTableItem::new(module.clone()));
+                }
+                if let Some(sort) = old_sort {
+                    self.module_model.sort(sort.0, sort.1);
+                }
+                self.modules = modules;
+            }
+            Message::MemoryPage(MemoryMessage::Paging(paging)) => {
+                self.paging_samples += 1;
+                // The counter catches kills the kernel log is not readable for
+                let previous = self.paging.and_then(|previous| previous.oom_kills);
+                if let (Some(previous), Some(current)) = (previous, paging.oom_kills) {
+                    for _ in previous..current {
+                        self.oom_events.push_back(OomEvent {
+                            victim: None,
+                            sample: self.paging_samples,
+                            at: Instant::now(),
+                        });
+                    }
+                }
+
+                self.oom_events
+                    .drain(..self.oom_events.len().saturating_sub(OOM_EVENT_LIMIT));
+
+                self.paging_history.push_back(paging);
+                self.paging_history.pop_front();
+                self.paging = Some(paging);
+            }
+            Message::MemoryPage(MemoryMessage::OomKill(victim)) => {
+                // The log and the counter usually report the same kill within a few seconds
+                let unnamed = self.oom_events.iter_mut().rev().find(|event| {
+                    event.victim.is_none() && event.at.elapsed() < Duration::from_secs(10)
+                });
+                match unnamed {
+                    Some(event) => event.victim = Some(victim),
+                    None => self.oom_events.push_back(OomEvent {
+                        victim: Some(victim),
+                        sample: self.paging_samples,
+                        at: Instant::now(),
+                    }),
+                }
+                self.oom_events
+                    .drain(..self.oom_events.len().saturating_sub(OOM_EVENT_LIMIT));
+            }
+            Message::MemoryPage(MemoryMessage::SelectGraph(graph)) => {
+                self.graph_list.activate(graph)
+            }
+            Message::MemoryPage(MemoryMessage::SelectModule(module)) => {
+                self.module_model.activate(module)
+            }
+            Message::MemoryPage(MemoryMessage::SortModules(category)) => {
+                if let Some(sort) = self.module_model.get_sort() {
+                    if sort.0 == category {
+                        self.module_model.sort(category, !sort.1);
+                    } else {
+                        self.module_model.sort(category, false)
+                    }
+                } else {
+                    self.module_model.sort(category, false)
+                }
+            }
             _ => {}
         }
 
@@ -53,15 +523,10 @@ impl super::Page for MemoryPage {
         if let Some(memory_info) = &self.memory_info {
             let theme = cosmic::theme::active();
             let cosmic = theme.cosmic();
-            widget::row()
+            let overview = widget::row()
                 .spacing(cosmic.space_xxs())
-                .push(
-                    widget::canvas(crate::widget::graph::LineGraph {
-                        points: self.memory_usage_history.iter().cloned().collect(),
-                    })
-                    .width(iced::Length::Fill)
-                    .height(iced::Length::Fill),
-                )
+                .height(iced::Length::FillPortion(2))
+                .push(self.graph_view())
                 .push(
                     widget::settings::view_column(vec![
                         widget::settings::section()
@@ -70,14 +535,14 @@ impl super::Page for MemoryPage {
                                 fl!("total-memory"),
                                 memory_info
                                     .total_memory_bytes
-                                    .apply(crate::helpers::get_bytes)
+                                    .apply(get_bytes)
                                     .apply(widget::text::body),
                             ))
                             .add(widget::settings::item(
                                 fl!("total-swap"),
                                 memory_info
                                     .swap_total_bytes
-                                    .apply(crate::helpers::get_bytes)
+                                    .apply(get_bytes)
                                     .apply(widget::text::body),
                             ))
                             .add_maybe(memory_info.dram_info.as_ref().map(|i| {
@@ -99,20 +564,58 @@ impl super::Page for MemoryPage {
                                 fl!("used-memory"),
                                 memory_info
                                     .used_memory_bytes
-                                    .apply(crate::helpers::get_bytes)
+                                    .apply(get_bytes)
                                     .apply(widget::text::body),
                             ))
                             .add(widget::settings::item(
                                 fl!("used-swap"),
                                 memory_info
                                     .swap_used_bytes
-                                    .apply(crate::helpers::get_bytes)
+                                    .apply(get_bytes)
                                     .apply(widget::text::body),
                             ))
                             .apply(Element::from),
+                        self.memory_details
+                            .as_ref()
+                            .map(Self::composition_section)
+                            .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
+                        self.memory_details
+                            .as_ref()
+                            .map(Self::swap_section)
+                            .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
+                        self.paging_section()
+                            .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
+                        self.oom_section()
+                            .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
+                        self.pressure
+                            .section(fl!("memory-pressure"))
+                            .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
                     ])
                     .apply(widget::scrollable),
-                )
+                );
+
+            // Module details need root or an EDAC driver, without either there is nothing to show
+            let modules = (!self.modules.is_empty()).then(|| {
+                widget::column()
+                    .spacing(cosmic.space_xxs())
+                    .height(iced::Length::FillPortion(1))
+                    .push(widget::text::heading(fl!("memory-modules")))
+                    .push(
+                        widget::table(&self.module_model)
+                            .on_item_left_click(|entity| {
+                                Message::MemoryPage(MemoryMessage::SelectModule(entity))
+                            })
+                            .on_category_left_click(|category| {
+                                Message::MemoryPage(MemoryMessage::SortModules(category))
+                            })
+                            .apply(widget::scrollable),
+                    )
+            });
+
+            widget::column()
+                .spacing(cosmic.space_xs())
+                .push(overview)
+                .push_maybe(modules)
                 .apply(Element::from)
         } else {
             widget::horizontal_space().apply(Element::from)
@@ -120,31 +623,118 @@ impl super::Page for MemoryPage {
     }
 
     fn subscription(&self) -> Vec<Subscription<Message>> {
-        vec![Subscription::run(|| {
-            stream::channel(1, |mut sender| async move {
-                let mut service = MonitordServiceClient::connect("http://127.0.0.1:50051")
-                    .await
-                    .unwrap();
+        vec![
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let mut service = MonitordServiceClient::connect("http://127.0.0.1:50051")
+                        .await
+                        .unwrap();
 
-                let request = tonic::Request::new(SnapshotRequest { interval_ms: 1000 });
+                    let request = tonic::Request::new(SnapshotRequest { interval_ms: 1000 });
 
-                let mut stream = service
-                    .stream_memory_info(request)
-                    .await
-                    .unwrap()
-                    .into_inner();
+                    let mut stream = service
+                        .stream_memory_info(request)
+                        .await
+                        .unwrap()
+                        .into_inner();
 
-                loop {
-                    let message = stream.message().await.unwrap();
+                    loop {
+                        let message = stream.message().await.unwrap();
 
-                    if let Some(message) = message {
+                        if let Some(message) = message {
+                            sender
+                                .send(Message::MemoryPage(MemoryMessage::Snapshot(message)))
+                                .await
+                                .unwrap();
+                        }
+                    }
+                })
+            }),
+            // The daemon only reports totals, the breakdown comes from /proc/meminfo
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    loop {
+                        let details = tokio::task::spawn_blocking(memory::read).await.unwrap();
+                        if let Some(details) = details {
+                            sender
+                                .send(Message::MemoryPage(MemoryMessage::Details(details)))
+                                .await
+                                .unwrap();
+                        }
+
+                        tokio::time::sleep(Duration::from_millis(1000)).await;
+                    }
+                })
+            }),
+            // The daemon only reports the DRAM type and frequency, modules come from SMBIOS and
+            // EDAC, of which only the EDAC error counters change
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let smbios = tokio::task::spawn_blocking(dimm::read_smbios)
+                        .await
+                        .unwrap();
+
+                    loop {
+                        let smbios = smbios.clone();
+                        let modules = tokio::task::spawn_blocking(move || dimm::read(&smbios))
+                            .await
+                            .unwrap();
                         sender
-                            .send(Message::MemoryPage(MemoryMessage::Snapshot(message)))
+                            .send(Message::MemoryPage(MemoryMessage::Modules(modules)))
                             .await
                             .unwrap();
+
+                        tokio::time::sleep(Duration::from_millis(1000)).await;
                     }
-                }
-            })
-        })]
+                })
+            }),
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let mut sampler = VmstatSampler::default();
+
+                    loop {
+                        if let Some(paging) = sampler.sample() {
+                            sender
+                                .send(Message::MemoryPage(MemoryMessage::Paging(paging)))
+                                .await
+                                .unwrap();
+                        }
+
+                        tokio::time::sleep(Duration::from_millis(1000)).await;
+                    }
+                })
+            }),
+            // Victims are only named in the kernel log, which may be restricted to root
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    use tokio::io::{AsyncBufReadExt, AsyncSeekExt};
+
+                    let Ok(mut kmsg) = tokio::fs::File::open("/dev/kmsg").await else {
+                        return;
+                    };
+                    // Only kills from now on are of interest
+                    if kmsg.seek(std::io::SeekFrom::End(0)).await.is_err() {
+                        return;
+                    }
+
+                    let mut records = tokio::io::BufReader::new(kmsg).lines();
+                    loop {
+                        match records.next_line().await {
+                            Ok(Some(record)) => {
+                                if let Some(victim) = vmstat::parse_oom_victim(&record) {
+                                    sender
+                                        .send(Message::MemoryPage(MemoryMessage::OomKill(victim)))
+                                        .await
+                                        .unwrap();
+                                }
+                            }
+                            Ok(None) => break,
+                            // Records overwritten before they were read fail once, then reading resumes
+                            Err(_) => continue,
+                        }
+                    }
+                })
+            }),
+        ]
     }
 }
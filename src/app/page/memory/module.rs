@@ -0,0 +1,164 @@
+use crate::{fl, helpers, sys::dimm::MemoryModule};
+use cosmic::{iced::Length, widget};
+use lazy_static::lazy_static;
+use std::borrow::Cow;
+
+lazy_static! {
+    static ref DIMM_SLOT: String = fl!("dimm-slot");
+    static ref DIMM_SIZE: String = fl!("dimm-size");
+    static ref DIMM_TYPE: String = fl!("dimm-type");
+    static ref DIMM_SPEED: String = fl!("dimm-speed");
+    static ref DIMM_CONFIGURED_SPEED: String = fl!("dimm-configured-speed");
+    static ref DIMM_MANUFACTURER: String = fl!("dimm-manufacturer");
+    static ref DIMM_PART_NUMBER: String = fl!("dimm-part-number");
+    static ref DIMM_ECC: String = fl!("dimm-ecc");
+    static ref DIMM_ERRORS: String = fl!("dimm-errors");
+    static ref DIMM_YES: String = fl!("yes");
+    static ref DIMM_NO: String = fl!("no");
+    static ref DIMM_UNKNOWN: String = fl!("unknown");
+}
+
+pub struct MemoryModuleTableItem {
+    pub module: MemoryModule,
+    slot: Cow<'static, str>,
+    size: Cow<'static, str>,
+    speed: Cow<'static, str>,
+    configured_speed: Cow<'static, str>,
+    errors: Cow<'static, str>,
+}
+
+impl MemoryModuleTableItem {
+    pub fn new(module: MemoryModule) -> Self {
+        let speed = |speed: Option<u32>| match speed {
+            Some(speed) => format!("{speed} MT/s").into(),
+            None => DIMM_UNKNOWN.as_str().into(),
+        };
+        Self {
+            slot: match &module.bank {
+                Some(bank) => format!("{} ({bank})", module.slot).into(),
+                None => module.slot.clone().into(),
+            },
+            size: helpers::get_bytes(module.size_bytes).into(),
+            speed: speed(module.speed_mts),
+            configured_speed: speed(module.configured_speed_mts),
+            errors: match (module.corrected_errors, module.uncorrected_errors) {
+                (None, None) => DIMM_UNKNOWN.as_str().into(),
+                (corrected, uncorrected) => fl!(
+                    "dimm-error-counts",
+                    corrected = corrected.unwrap_or_default(),
+                    uncorrected = uncorrected.unwrap_or_default()
+                )
+                .into(),
+            },
+            module,
+        }
+    }
+}
+
+fn optional(value: &Option<String>) -> Cow<'static, str> {
+    match value {
+        Some(value) => value.clone().into(),
+        None => DIMM_UNKNOWN.as_str().into(),
+    }
+}
+
+impl widget::table::ItemInterface<MemoryModuleTableCategory> for MemoryModuleTableItem {
+    fn get_icon(&self, _category: MemoryModuleTableCategory) -> Option<widget::Icon> {
+        None
+    }
+
+    fn get_text(&self, category: MemoryModuleTableCategory) -> Cow<'static, str> {
+        match category {
+            MemoryModuleTableCategory::Slot => self.slot.clone(),
+            MemoryModuleTableCategory::Size => self.size.clone(),
+            MemoryModuleTableCategory::Type => optional(&self.module.memory_type),
+            MemoryModuleTableCategory::Speed => self.speed.clone(),
+            MemoryModuleTableCategory::ConfiguredSpeed => self.configured_speed.clone(),
+            MemoryModuleTableCategory::Manufacturer => optional(&self.module.manufacturer),
+            MemoryModuleTableCategory::PartNumber => optional(&self.module.part_number),
+            MemoryModuleTableCategory::Ecc => match self.module.ecc {
+                Some(true) => DIMM_YES.as_str().into(),
+                Some(false) => DIMM_NO.as_str().into(),
+                None => DIMM_UNKNOWN.as_str().into(),
+            },
+            MemoryModuleTableCategory::Errors => self.errors.clone(),
+        }
+    }
+
+    fn compare(&self, other: &Self, category: MemoryModuleTableCategory) -> std::cmp::Ordering {
+        match category {
+            MemoryModuleTableCategory::Slot => other.slot.cmp(&self.slot),
+            MemoryModuleTableCategory::Size => self.module.size_bytes.cmp(&other.module.size_bytes),
+            MemoryModuleTableCategory::Type => {
+                self.module.memory_type.cmp(&other.module.memory_type)
+            }
+            MemoryModuleTableCategory::Speed => self.module.speed_mts.cmp(&other.module.speed_mts),
+            MemoryModuleTableCategory::ConfiguredSpeed => self
+                .module
+                .configured_speed_mts
+                .cmp(&other.module.configured_speed_mts),
+            MemoryModuleTableCategory::Manufacturer => {
+                self.module.manufacturer.cmp(&other.module.manufacturer)
+            }
+            MemoryModuleTableCategory::PartNumber => {
+                self.module.part_number.cmp(&other.module.part_number)
+            }
+            MemoryModuleTableCategory::Ecc => self.module.ecc.cmp(&other.module.ecc),
+            MemoryModuleTableCategory::Errors => {
+                let errors =
+                    |module: &MemoryModule| (module.uncorrected_errors, module.corrected_errors);
+                errors(&self.module).cmp(&errors(&other.module))
+            }
+        }
+    }
+}
+
+#[derive(Default, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum MemoryModuleTableCategory {
+    #[default]
+    Slot,
+    Size,
+    Type,
+    Speed,
+    ConfiguredSpeed,
+    Manufacturer,
+    PartNumber,
+    Ecc,
+    Errors,
+}
+
+impl std::fmt::Display for MemoryModuleTableCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Slot => DIMM_SLOT.as_str(),
+                Self::Size => DIMM_SIZE.as_str(),
+                Self::Type => DIMM_TYPE.as_str(),
+                Self::Speed => DIMM_SPEED.as_str(),
+                Self::ConfiguredSpeed => DIMM_CONFIGURED_SPEED.as_str(),
+                Self::Manufacturer => DIMM_MANUFACTURER.as_str(),
+                Self::PartNumber => DIMM_PART_NUMBER.as_str(),
+                Self::Ecc => DIMM_ECC.as_str(),
+                Self::Errors => DIMM_ERRORS.as_str(),
+            }
+        )
+    }
+}
+
+impl widget::table::ItemCategory for MemoryModuleTableCategory {
+    fn width(&self) -> cosmic::iced::Length {
+        match self {
+            Self::Slot => Length::Fixed(180.0),
+            Self::Size => Length::Fixed(80.0),
+            Self::Type => Length::Fixed(80.0),
+            Self::Speed => Length::Fixed(100.0),
+            Self::ConfiguredSpeed => Length::Fixed(140.0),
+            Self::Manufacturer => Length::Fixed(140.0),
+            Self::PartNumber => Length::Fixed(180.0),
+            Self::Ecc => Length::Fixed(60.0),
+            Self::Errors => Length::Fixed(140.0),
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::Duration,
+};
 
 use cosmic::{
     iced::{self, stream, Subscription},
@@ -11,30 +14,613 @@ use monitord_protocols::{
     protocols::MonitordServiceClient,
 };
 
-use crate::{app::Message, fl};
+use super::devices::DeviceTabs;
+use crate::{
+    app::Message,
+    config::Config,
+    fl,
+    helpers::get_bytes,
+    sys::{
+        net::{self, Addressing, InterfaceKind, WirelessLink},
+        sockets::{self, Socket},
+        traffic::{ProcessTraffic, TrafficSampler},
+    },
+};
+use connection::{ConnectionKey, ConnectionTableCategory, ConnectionTableItem};
+use usage::DataUsage;
+
+mod connection;
+mod usage;
 
 #[derive(Debug, Clone)]
 pub enum NetworkMessage {
     Snapshot(NetworkList),
     SelectTab(widget::segmented_button::Entity),
+    Addressing(HashMap<String, Addressing>),
+    Wireless(HashMap<String, WirelessLink>),
+    Kinds(HashMap<String, InterfaceKind>),
+    SelectView(widget::segmented_button::Entity),
+    Sockets(Vec<Socket>),
+    SelectConnection(widget::table::Entity),
+    SortConnections(ConnectionTableCategory),
+    SearchConnections(String),
+    ListeningOnly(bool),
+}
+
+/// Whether the network page shows interfaces or the sockets using them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NetworkView {
+    Interfaces,
+    Connections,
+    Talkers,
 }
 
+/// How many processes the top talkers list shows
+const TALKER_LIMIT: usize = 10;
+/// Data allowances offered for metered interfaces in GiB, 0 meaning none
+const QUOTA_PRESETS: [u64; 11] = [0, 1, 2, 5, 10, 20, 50, 100, 200, 500, 1000];
+/// How many months of usage history are shown
+const MONTH_LIMIT: usize = 6;
+
 struct NetworkDevice {
     info: NetworkInfo,
     history: VecDeque<f32>,
+    addressing: Option<Addressing>,
+    wireless: Option<WirelessLink>,
+    signal_history: VecDeque<f32>,
+    /// Virtual interfaces grouped under this bridge instead of having their own tab
+    members: Vec<String>,
+}
+
+impl NetworkDevice {
+    fn addressing_section(&self) -> Option<Element<Message>> {
+        let addressing = self.addressing.as_ref()?;
+        let list = |items: Vec<String>| {
+            if items.is_empty() {
+                fl!("unknown")
+            } else {
+                items.join("\n")
+            }
+        };
+
+        widget::settings::section()
+            .title(fl!("net-addressing"))
+            .add(widget::settings::item(
+                fl!("net-addresses"),
+                addressing
+                    .addresses
+                    .iter()
+                    .map(|(address, prefix)| format!("{}/{}", address, prefix))
+                    .collect::<Vec<_>>()
+                    .apply(list)
+                    .apply(widget::text::body),
+            ))
+            .add(widget::settings::item(
+                fl!("net-gateways"),
+                addressing
+                    .gateways
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .apply(list)
+                    .apply(widget::text::body),
+            ))
+            .add(widget::settings::item(
+                fl!("net-dns"),
+                addressing
+                    .dns_servers
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .apply(list)
+                    .apply(widget::text::body),
+            ))
+            .add_maybe(addressing.speed_mbps.map(|speed| {
+                widget::settings::item(
+                    fl!("net-link-speed"),
+                    fl!("net-mbps", value = speed).apply(widget::text::body),
+                )
+            }))
+            .add_maybe(addressing.duplex.as_ref().map(|duplex| {
+                widget::settings::item(fl!("net-duplex"), duplex.clone().apply(widget::text::body))
+            }))
+            .apply(Element::from)
+            .apply(Some)
+    }
+
+    fn wireless_section(&self) -> Option<Element<Message>> {
+        let wireless = self.wireless.as_ref()?;
+        widget::settings::section()
+            .title(fl!("net-wireless"))
+            .add(
+                widget::container(
+                    widget::canvas(crate::widget::graph::LineGraph {
+                        points: self.signal_history.iter().cloned().collect(),
+                    })
+                    .width(iced::Length::Fill)
+                    .height(iced::Length::Fill),
+                )
+                .height(iced::Length::Fixed(120.0)),
+            )
+            .add_maybe(wireless.ssid.as_ref().map(|ssid| {
+                widget::settings::item(fl!("net-ssid"), ssid.clone().apply(widget::text::body))
+            }))
+            .add_maybe(wireless.signal_percent.map(|signal| {
+                widget::settings::item(
+                    fl!("net-signal"),
+                    format!("{}%", signal).apply(widget::text::body),
+                )
+            }))
+            .add_maybe(wireless.bitrate_mbps.map(|bitrate| {
+                widget::settings::item(
+                    fl!("net-bitrate"),
+                    fl!(
+                        "net-mbps",
+                        value = crate::helpers::format_number(bitrate as f64)
+                    )
+                    .apply(widget::text::body),
+                )
+            }))
+            .add_maybe(wireless.band().map(|band| {
+                widget::settings::item(
+                    fl!("net-band"),
+                    format!("{} GHz", band).apply(widget::text::body),
+                )
+            }))
+            .apply(Element::from)
+            .apply(Some)
+    }
 }
 
 pub struct NetworkPage {
-    net_list: widget::segmented_button::SingleSelectModel,
-    name_to_entity: HashMap<String, widget::segmented_button::Entity>,
+    net_list: DeviceTabs<NetworkDevice>,
+    view_list: widget::segmented_button::SingleSelectModel,
+    sockets: Vec<Socket>,
+    connection_model:
+        widget::table::SingleSelectModel<ConnectionTableItem, ConnectionTableCategory>,
+    connection_to_entity: HashMap<ConnectionKey, widget::table::Entity>,
+    connection_search: String,
+    listening_only: bool,
+    talkers: Vec<ProcessTraffic>,
+    kinds: HashMap<String, InterfaceKind>,
+    usage: DataUsage,
+    quota_labels: Vec<String>,
+    billing_day_labels: Vec<String>,
+    /// Addressing, wireless links, connections and top talkers are only polled while the page
+    /// is shown
+    visible: bool,
+    // Configuration data that persists between application runs.
+    config: Config,
 }
 
 impl NetworkPage {
-    pub fn new() -> Self {
+    pub fn new(config: Config) -> Self {
+        let mut view_list = widget::segmented_button::SingleSelectModel::default();
+        view_list
+            .insert()
+            .text(fl!("net-interfaces"))
+            .data(NetworkView::Interfaces)
+            .activate();
+        view_list
+            .insert()
+            .text(fl!("net-connections"))
+            .data(NetworkView::Connections);
+        view_list
+            .insert()
+            .text(fl!("net-talkers"))
+            .data(NetworkView::Talkers);
+
         Self {
-            net_list: widget::segmented_button::SingleSelectModel::default(),
-            name_to_entity: HashMap::new(),
+            net_list: DeviceTabs::new(),
+            view_list,
+            sockets: Vec::new(),
+            connection_model: widget::table::SingleSelectModel::new(vec![
+                ConnectionTableCategory::Protocol,
+                ConnectionTableCategory::Local,
+                ConnectionTableCategory::Remote,
+                ConnectionTableCategory::State,
+                ConnectionTableCategory::Pid,
+                ConnectionTableCategory::Process,
+            ]),
+            connection_to_entity: HashMap::new(),
+            connection_search: String::new(),
+            listening_only: false,
+            talkers: Vec::new(),
+            kinds: HashMap::new(),
+            usage: DataUsage::load(),
+            quota_labels: QUOTA_PRESETS
+                .iter()
+                .map(|quota| match quota {
+                    0 => fl!("net-no-quota"),
+                    quota => format!("{quota} GiB"),
+                })
+                .collect(),
+            billing_day_labels: (1..=28).map(|day| day.to_string()).collect(),
+            visible: false,
+            config,
+        }
+    }
+
+    /// Whether the interface filter rules in the settings keep the interface out of the tabs.
+    /// Favorites are always shown.
+    fn is_hidden(&self, name: &str) -> bool {
+        let Some(kind) = self.kinds.get(name) else {
+            return false;
+        };
+        !self
+            .config
+            .favorite_interfaces
+            .iter()
+            .any(|favorite| favorite == name)
+            && ((self.config.hide_loopback && kind.loopback)
+                || (self.config.hide_virtual && kind.is_virtual)
+                || (self.config.group_virtual && kind.is_virtual && kind.bridge.is_some()))
+    }
+
+    /// Refills the connection table with the sockets matching the search, keeping the sort
+    /// and the selected socket
+    /// Applies the sockets and filters to the table, updating rows in place keyed by socket
+    fn filter_connections(&mut self) {
+        let mut shown = HashSet::new();
+        for socket in self.sockets.iter().filter(|socket| {
+            (!self.listening_only || socket.is_listening())
+                && connection::matches_search(socket, &self.connection_search)
+        }) {
+            let key = ConnectionTableItem::key(socket);
+            shown.insert(key);
+            if let Some(item) = self
+                .connection_to_entity
+                .get(&key)
+                .and_then(|entity| self.connection_model.item_mut(*entity))
+            {
+                item.update(socket);
+            } else {
+                let entity = self
+                    .connection_model
+                    .insert(ConnectionTableItem::new(socket.clone()))
+                    .id();
+                self.connection_to_entity.insert(key, entity);
+            }
         }
+        self.connection_to_entity.retain(|key, entity| {
+            if shown.contains(key) {
+                true
+            } else {
+                self.connection_model.remove(*entity);
+                false
+            }
+        });
+        if let Some(sort) = self.connection_model.get_sort() {
+            self.connection_model.sort(sort.0, sort.1);
+        }
+    }
+
+    fn interfaces_view(&self) -> Element<Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        widget::column()
+            .spacing(cosmic.space_xs())
+            .push(
+                widget::tab_bar::horizontal(&self.net_list.model)
+                    .on_activate(|entity| Message::NetworkPage(NetworkMessage::SelectTab(entity))),
+            )
+            .push_maybe(
+                self.net_list
+                    .model
+                    .active_data::<NetworkDevice>()
+                    .map(|net| {
+                        widget::row()
+                            .spacing(cosmic.space_xxs())
+                            .push(
+                                widget::canvas(crate::widget::graph::LineGraph {
+                                    points: {
+                                        let max = net
+                                            .history
+                                            .iter()
+                                            .max_by(|a, b| a.partial_cmp(b).unwrap())
+                                            .unwrap()
+                                            .max(1.0);
+                                        net.history.iter().cloned().map(|val| val / max).collect()
+                                    },
+                                })
+                                .width(iced::Length::Fill)
+                                .height(iced::Length::Fill),
+                            )
+                            .push(
+                                widget::settings::view_column(vec![
+                                    widget::settings::section()
+                                        .title(fl!("net-info"))
+                                        .add(widget::settings::item(
+                                            fl!("interface-name"),
+                                            net.info
+                                                .interface_name
+                                                .clone()
+                                                .apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("net-driver"),
+                                            net.info.driver.clone().apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("mac-address"),
+                                            net.info.mac_address.clone().apply(widget::text::body),
+                                        ))
+                                        .add_maybe((!net.members.is_empty()).then(|| {
+                                            widget::settings::item(
+                                                fl!("net-bridge-members"),
+                                                net.members.join("\n").apply(widget::text::body),
+                                            )
+                                        }))
+                                        .add(widget::settings::item(
+                                            fl!("net-favorite"),
+                                            widget::toggler(
+                                                self.config
+                                                    .favorite_interfaces
+                                                    .contains(&net.info.interface_name),
+                                            )
+                                            .on_toggle({
+                                                let name = net.info.interface_name.clone();
+                                                move |favorite| {
+                                                    Message::SetFavoriteInterface(
+                                                        name.clone(),
+                                                        favorite,
+                                                    )
+                                                }
+                                            }),
+                                        ))
+                                        .apply(Element::from),
+                                    widget::settings::section()
+                                        .title(fl!("net-stats"))
+                                        .add(widget::settings::item(
+                                            fl!("bandwidth"),
+                                            format!(
+                                                "{}/s",
+                                                net.info
+                                                    .max_bandwidth_bytes_per_sec
+                                                    .apply(crate::helpers::get_bytes)
+                                            )
+                                            .apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("rx-bytes"),
+                                            format!(
+                                                "{}/s",
+                                                net.info
+                                                    .rx_bytes_per_sec
+                                                    .apply(crate::helpers::get_bytes)
+                                            )
+                                            .apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("rx-packets"),
+                                            format!("{}/s", net.info.rx_packets_per_sec)
+                                                .apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("rx-total"),
+                                            net.info
+                                                .rx_bytes_total
+                                                .apply(crate::helpers::get_bytes)
+                                                .apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("rx-errors"),
+                                            format!("{}/s", net.info.rx_errors)
+                                                .apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("tx-bytes"),
+                                            format!(
+                                                "{}/s",
+                                                net.info
+                                                    .tx_bytes_per_sec
+                                                    .apply(crate::helpers::get_bytes)
+                                            )
+                                            .apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("tx-packets"),
+                                            format!("{}/s", net.info.tx_packets_per_sec)
+                                                .apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("tx-total"),
+                                            net.info
+                                                .tx_bytes_total
+                                                .apply(crate::helpers::get_bytes)
+                                                .apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("tx-errors"),
+                                            format!("{}/s", net.info.tx_errors)
+                                                .apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("is-up"),
+                                            net.info.is_up.to_string().apply(widget::text::body),
+                                        ))
+                                        .add(widget::settings::item(
+                                            fl!("mtu"),
+                                            net.info.mtu.to_string().apply(widget::text::body),
+                                        ))
+                                        .apply(Element::from),
+                                    net.addressing_section().unwrap_or_else(|| {
+                                        widget::horizontal_space().apply(Element::from)
+                                    }),
+                                    net.wireless_section().unwrap_or_else(|| {
+                                        widget::horizontal_space().apply(Element::from)
+                                    }),
+                                    self.usage_section(&net.info.interface_name),
+                                ])
+                                .apply(widget::scrollable),
+                            )
+                            .apply(Element::from)
+                    }),
+            )
+            .apply(Element::from)
+    }
+
+    fn usage_section(&self, interface: &str) -> Element<Message> {
+        let amounts = |(rx, tx): (u64, u64)| {
+            fl!("net-usage-amounts", rx = get_bytes(rx), tx = get_bytes(tx))
+                .apply(widget::text::body)
+        };
+        let cycle = self.usage.cycle(interface, self.config.billing_day);
+        let quota = self
+            .config
+            .data_quotas
+            .get(interface)
+            .copied()
+            .filter(|quota| *quota > 0);
+        let quota_position = QUOTA_PRESETS
+            .iter()
+            .position(|preset| Some(preset * 1024 * 1024 * 1024) == quota)
+            .or(quota.is_none().then_some(0));
+
+        let set_quota = interface.to_owned();
+        widget::settings::section()
+            .title(fl!("net-usage"))
+            .add(widget::settings::item(
+                fl!("net-usage-today"),
+                amounts(self.usage.today(interface)),
+            ))
+            .add(widget::settings::item(
+                fl!("net-usage-cycle", start = cycle.start.to_string()),
+                get_bytes(cycle.used).apply(widget::text::body),
+            ))
+            .add(widget::settings::item(
+                fl!("net-billing-day"),
+                widget::dropdown(
+                    &self.billing_day_labels,
+                    Some(usize::from(self.config.billing_day) - 1),
+                    |index| Message::SetBillingDay(index as u8 + 1),
+                ),
+            ))
+            .add(widget::settings::item(
+                fl!("net-quota"),
+                widget::dropdown(&self.quota_labels, quota_position, move |index| {
+                    Message::SetDataQuota(
+                        set_quota.clone(),
+                        QUOTA_PRESETS[index] * 1024 * 1024 * 1024,
+                    )
+                }),
+            ))
+            .add_maybe(quota.map(|quota| {
+                widget::settings::item(
+                    fl!("net-projected"),
+                    fl!(
+                        "net-projected-of",
+                        projected = get_bytes(cycle.projected),
+                        quota = get_bytes(quota)
+                    )
+                    .apply(widget::text::body),
+                )
+            }))
+            .add_maybe(quota.filter(|quota| cycle.projected > *quota).map(|_| {
+                widget::row()
+                    .spacing(cosmic::theme::active().cosmic().space_xxs())
+                    .align_y(iced::Alignment::Center)
+                    .push(widget::icon::from_name("dialog-warning-symbolic").size(16))
+                    .push(widget::text::body(fl!("net-quota-warning")))
+            }))
+            .extend(
+                self.usage
+                    .months(interface)
+                    .into_iter()
+                    .take(MONTH_LIMIT)
+                    .map(|(month, usage)| {
+                        widget::settings::item(month.format("%Y-%m").to_string(), amounts(usage))
+                    }),
+            )
+            .apply(Element::from)
+    }
+
+    fn talkers_view(&self) -> Element<Message> {
+        let talkers = self.talkers.iter().map(|talker| {
+            widget::settings::item(
+                format!("{} ({})", talker.name, talker.pid),
+                widget::row()
+                    .align_y(iced::Alignment::Center)
+                    .push(
+                        fl!(
+                            "net-talker-rates",
+                            rx = crate::helpers::get_bytes(talker.rx_bytes_per_sec),
+                            tx = crate::helpers::get_bytes(talker.tx_bytes_per_sec)
+                        )
+                        .apply(widget::text::body),
+                    )
+                    .push(
+                        widget::button::icon(widget::icon::from_name("go-next-symbolic"))
+                            .on_press(Message::ShowProcess(talker.pid)),
+                    ),
+            )
+        });
+
+        widget::settings::view_column(vec![widget::settings::section()
+            .title(fl!("net-talkers"))
+            .add(widget::text::caption(fl!("net-talkers-note")))
+            .add_maybe(
+                self.talkers
+                    .is_empty()
+                    .then(|| widget::text::body(fl!("net-talkers-idle"))),
+            )
+            .extend(talkers)
+            .apply(Element::from)])
+        .apply(widget::scrollable)
+        .apply(Element::from)
+    }
+
+    fn connections_view(&self) -> Element<Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+        let selected_pid = self
+            .connection_model
+            .item(self.connection_model.active())
+            .and_then(|item| item.socket.pid);
+
+        widget::column()
+            .spacing(cosmic.space_xs())
+            .push(
+                widget::row()
+                    .spacing(cosmic.space_s())
+                    .align_y(iced::Alignment::Center)
+                    .push(
+                        widget::search_input(fl!("conn-search"), &self.connection_search)
+                            .on_input(|search| {
+                                Message::NetworkPage(NetworkMessage::SearchConnections(search))
+                            })
+                            .on_clear(Message::NetworkPage(NetworkMessage::SearchConnections(
+                                String::new(),
+                            ))),
+                    )
+                    .push(
+                        widget::toggler(self.listening_only)
+                            .label(fl!("conn-listening-only"))
+                            .on_toggle(|listening| {
+                                Message::NetworkPage(NetworkMessage::ListeningOnly(listening))
+                            }),
+                    ),
+            )
+            .push(
+                widget::table(&self.connection_model)
+                    .on_item_left_click(|entity| {
+                        Message::NetworkPage(NetworkMessage::SelectConnection(entity))
+                    })
+                    .on_category_left_click(|category| {
+                        Message::NetworkPage(NetworkMessage::SortConnections(category))
+                    })
+                    .apply(widget::scrollable)
+                    .height(iced::Length::Fill),
+            )
+            .push(
+                widget::row().push(widget::horizontal_space()).push(
+                    fl!("show-in-processes")
+                        .apply(widget::button::text)
+                        .on_press_maybe(selected_pid.map(Message::ShowProcess)),
+                ),
+            )
+            .apply(Element::from)
     }
 }
 
@@ -43,34 +629,139 @@ impl super::Page for NetworkPage {
         let tasks = Vec::new();
 
         match msg {
+            Message::UpdateConfig(config) => self.config = config,
             Message::NetworkPage(NetworkMessage::Snapshot(snapshot)) => {
+                let mut present = HashSet::new();
                 for net in snapshot.nets.iter() {
-                    let entity = if let Some(entity) = self.name_to_entity.get(&net.interface_name)
-                    {
-                        entity.clone()
+                    self.usage
+                        .record(&net.interface_name, net.rx_bytes_total, net.tx_bytes_total);
+
+                    let id = interface_id(net, &snapshot.nets);
+                    // Tabs wait until the kind of the interface is known, so interfaces the
+                    // filters hide do not show up for a moment first. An existing tab is kept.
+                    if !self.kinds.contains_key(&net.interface_name) {
+                        present.insert(id);
+                        continue;
+                    }
+                    if self.is_hidden(&net.interface_name) {
+                        self.net_list.remove(&id);
+                        continue;
+                    }
+                    present.insert(id.clone());
+                    let members = if self.config.group_virtual {
+                        let mut members = self
+                            .kinds
+                            .iter()
+                            .filter(|(_, kind)| {
+                                kind.is_virtual && kind.bridge.as_ref() == Some(&net.interface_name)
+                            })
+                            .map(|(name, _)| name.clone())
+                            .collect::<Vec<_>>();
+                        members.sort();
+                        members
                     } else {
-                        let entity = self
-                            .net_list
-                            .insert()
-                            .text(net.interface_name.clone())
-                            .data(NetworkDevice {
+                        Vec::new()
+                    };
+                    let device =
+                        self.net_list
+                            .upsert(&id, net.interface_name.clone(), || NetworkDevice {
                                 info: net.clone(),
                                 history: VecDeque::from(vec![0.0; 30]),
-                            })
-                            .id();
-                        self.name_to_entity
-                            .insert(net.interface_name.clone(), entity.clone());
-                        entity
-                    };
-                    let device = self.net_list.data_mut::<NetworkDevice>(entity).unwrap();
+                                addressing: None,
+                                wireless: None,
+                                signal_history: VecDeque::from(vec![0.0; 30]),
+                                members: Vec::new(),
+                            });
                     device.info = net.clone();
+                    device.members = members;
                     device
                         .history
                         .push_back(net.rx_bytes_per_sec as f32 + net.tx_bytes_per_sec as f32);
                     device.history.pop_front();
                 }
+                self.net_list.retain_present(&present);
+                self.usage.save_if_due();
+
+                // Favorites come first, in the order they were pinned
+                let favorites = self.config.favorite_interfaces.iter().filter_map(|name| {
+                    snapshot
+                        .nets
+                        .iter()
+                        .find(|net| net.interface_name == *name)
+                        .filter(|net| present.contains(&interface_id(net, &snapshot.nets)))
+                });
+                for (position, net) in favorites.enumerate() {
+                    self.net_list
+                        .move_to(&interface_id(net, &snapshot.nets), position as u16);
+                }
+            }
+            Message::NetworkPage(NetworkMessage::SelectTab(tab)) => {
+                self.net_list.model.activate(tab)
+            }
+            Message::NetworkPage(NetworkMessage::Kinds(kinds)) => self.kinds = kinds,
+            Message::NetworkPage(NetworkMessage::Addressing(mut addressing)) => {
+                for entity in self.net_list.model.iter().collect::<Vec<_>>() {
+                    if let Some(device) = self.net_list.model.data_mut::<NetworkDevice>(entity) {
+                        device.addressing = addressing.remove(&device.info.interface_name);
+                    }
+                }
+            }
+            Message::NetworkPage(NetworkMessage::Wireless(mut wireless)) => {
+                for entity in self.net_list.model.iter().collect::<Vec<_>>() {
+                    let Some(device) = self.net_list.model.data_mut::<NetworkDevice>(entity) else {
+                        continue;
+                    };
+                    device.wireless = wireless.remove(&device.info.interface_name);
+                    if let Some(wireless) = &device.wireless {
+                        device.signal_history.push_back(
+                            wireless
+                                .signal_percent
+                                .map(|signal| signal as f32 / 100.0)
+                                .unwrap_or_default(),
+                        );
+                        device.signal_history.pop_front();
+                    }
+                }
+            }
+            Message::ProcessTraffic(traffic) => {
+                let mut talkers = traffic
+                    .into_values()
+                    .filter(|talker| talker.rx_bytes_per_sec + talker.tx_bytes_per_sec > 0)
+                    .collect::<Vec<_>>();
+                talkers.sort_by_key(|talker| {
+                    std::cmp::Reverse(talker.rx_bytes_per_sec + talker.tx_bytes_per_sec)
+                });
+                talkers.truncate(TALKER_LIMIT);
+                self.talkers = talkers;
+            }
+            Message::NetworkPage(NetworkMessage::SelectView(view)) => self.view_list.activate(view),
+            // Sockets come and go constantly, so the table is only updated when they change
+            Message::NetworkPage(NetworkMessage::Sockets(sockets)) if sockets != self.sockets => {
+                self.sockets = sockets;
+                self.filter_connections();
+            }
+            Message::NetworkPage(NetworkMessage::SelectConnection(connection)) => {
+                self.connection_model.activate(connection)
+            }
+            Message::NetworkPage(NetworkMessage::SortConnections(category)) => {
+                if let Some(sort) = self.connection_model.get_sort() {
+                    if sort.0 == category {
+                        self.connection_model.sort(category, !sort.1);
+                    } else {
+                        self.connection_model.sort(category, false)
+                    }
+                } else {
+                    self.connection_model.sort(category, false)
+                }
+            }
+            Message::NetworkPage(NetworkMessage::SearchConnections(search)) => {
+                self.connection_search = search;
+                self.filter_connections();
+            }
+            Message::NetworkPage(NetworkMessage::ListeningOnly(listening_only)) => {
+                self.listening_only = listening_only;
+                self.filter_connections();
             }
-            Message::NetworkPage(NetworkMessage::SelectTab(tab)) => self.net_list.activate(tab),
             _ => {}
         }
 
@@ -84,147 +775,200 @@ impl super::Page for NetworkPage {
         widget::column()
             .spacing(cosmic.space_xs())
             .push(
-                widget::tab_bar::horizontal(&self.net_list)
-                    .on_activate(|entity| Message::NetworkPage(NetworkMessage::SelectTab(entity))),
+                widget::segmented_control::horizontal(&self.view_list)
+                    .on_activate(|entity| Message::NetworkPage(NetworkMessage::SelectView(entity))),
             )
-            .push_maybe(self.net_list.active_data::<NetworkDevice>().map(|net| {
-                widget::row()
-                    .spacing(cosmic.space_xxs())
-                    .push(
-                        widget::canvas(crate::widget::graph::LineGraph {
-                            points: {
-                                let max = net
-                                    .history
-                                    .iter()
-                                    .max_by(|a, b| a.partial_cmp(b).unwrap())
-                                    .unwrap()
-                                    .max(1.0);
-                                net.history.iter().cloned().map(|val| val / max).collect()
-                            },
-                        })
-                        .width(iced::Length::Fill)
-                        .height(iced::Length::Fill),
-                    )
-                    .push(
-                        widget::settings::view_column(vec![
-                            widget::settings::section()
-                                .title(fl!("net-info"))
-                                .add(widget::settings::item(
-                                    fl!("interface-name"),
-                                    net.info.interface_name.clone().apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("net-driver"),
-                                    net.info.driver.clone().apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("mac-address"),
-                                    net.info.mac_address.clone().apply(widget::text::body),
-                                ))
-                                .apply(Element::from),
-                            widget::settings::section()
-                                .title(fl!("net-stats"))
-                                .add(widget::settings::item(
-                                    fl!("bandwidth"),
-                                    format!(
-                                        "{}/s",
-                                        net.info
-                                            .max_bandwidth_bytes_per_sec
-                                            .apply(crate::helpers::get_bytes)
-                                    )
-                                    .apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("rx-bytes"),
-                                    format!(
-                                        "{}/s",
-                                        net.info.rx_bytes_per_sec.apply(crate::helpers::get_bytes)
-                                    )
-                                    .apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("rx-packets"),
-                                    format!("{}/s", net.info.rx_packets_per_sec)
-                                        .apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("rx-total"),
-                                    net.info
-                                        .rx_bytes_total
-                                        .apply(crate::helpers::get_bytes)
-                                        .apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("rx-errors"),
-                                    format!("{}/s", net.info.rx_errors).apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("tx-bytes"),
-                                    format!(
-                                        "{}/s",
-                                        net.info.tx_bytes_per_sec.apply(crate::helpers::get_bytes)
-                                    )
-                                    .apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("tx-packets"),
-                                    format!("{}/s", net.info.tx_packets_per_sec)
-                                        .apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("tx-total"),
-                                    net.info
-                                        .tx_bytes_total
-                                        .apply(crate::helpers::get_bytes)
-                                        .apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("tx-errors"),
-                                    format!("{}/s", net.info.tx_errors).apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("is-up"),
-                                    net.info.is_up.to_string().apply(widget::text::body),
-                                ))
-                                .add(widget::settings::item(
-                                    fl!("mtu"),
-                                    net.info.mtu.to_string().apply(widget::text::body),
-                                ))
-                                .apply(Element::from),
-                        ])
-                        .apply(widget::scrollable),
-                    )
-                    .apply(Element::from)
-            }))
+            .push(match self.view_list.active_data::<NetworkView>() {
+                Some(NetworkView::Connections) => self.connections_view(),
+                Some(NetworkView::Talkers) => self.talkers_view(),
+                _ => self.interfaces_view(),
+            })
             .apply(Element::from)
     }
 
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
     fn subscription(&self) -> Vec<Subscription<Message>> {
-        vec![Subscription::run(|| {
-            stream::channel(1, |mut sender| async move {
-                let mut service = MonitordServiceClient::connect("http://127.0.0.1:50051")
-                    .await
-                    .unwrap();
+        let mut subscriptions = vec![
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let mut service = MonitordServiceClient::connect("http://127.0.0.1:50051")
+                        .await
+                        .unwrap();
 
-                let request = tonic::Request::new(SnapshotRequest { interval_ms: 1000 });
+                    let request = tonic::Request::new(SnapshotRequest { interval_ms: 1000 });
 
-                let mut stream = service
-                    .stream_network_info(request)
-                    .await
-                    .unwrap()
-                    .into_inner();
+                    let mut stream = service
+                        .stream_network_info(request)
+                        .await
+                        .unwrap()
+                        .into_inner();
 
-                loop {
-                    let message = stream.message().await.unwrap();
+                    loop {
+                        let message = stream.message().await.unwrap();
 
-                    if let Some(message) = message {
+                        if let Some(message) = message {
+                            sender
+                                .send(Message::NetworkPage(NetworkMessage::Snapshot(message)))
+                                .await
+                                .unwrap();
+                        }
+                    }
+                })
+            }),
+            // The daemon does not report interface kinds, they come from sysfs
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    loop {
+                        let kinds = tokio::task::spawn_blocking(net::read_kinds).await.unwrap();
                         sender
-                            .send(Message::NetworkPage(NetworkMessage::Snapshot(message)))
+                            .send(Message::NetworkPage(NetworkMessage::Kinds(kinds)))
                             .await
                             .unwrap();
+
+                        tokio::time::sleep(Duration::from_millis(5000)).await;
+                    }
+                })
+            }),
+        ];
+
+        // The daemon does not report addressing or wireless links either. They come from
+        // procfs, sysfs, iproute2, systemd-resolved and NetworkManager, and are only shown on
+        // this page.
+        if self.visible {
+            subscriptions.push(Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let connection = zbus::Connection::system().await.ok();
+
+                    loop {
+                        let link_dns = match &connection {
+                            Some(connection) => net::resolved_link_servers(connection).await,
+                            None => HashMap::new(),
+                        };
+                        let addressing =
+                            tokio::task::spawn_blocking(move || net::read_addressing(link_dns))
+                                .await
+                                .unwrap();
+                        sender
+                            .send(Message::NetworkPage(NetworkMessage::Addressing(addressing)))
+                            .await
+                            .unwrap();
+
+                        tokio::time::sleep(Duration::from_millis(5000)).await;
+                    }
+                })
+            }));
+            subscriptions.push(Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let Ok(connection) = zbus::Connection::system().await else {
+                        return;
+                    };
+
+                    loop {
+                        let names = tokio::task::spawn_blocking(|| {
+                            std::fs::read_dir("/sys/class/net")
+                                .into_iter()
+                                .flatten()
+                                .flatten()
+                                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                                .filter(|name| net::is_wireless(name))
+                                .collect::<Vec<_>>()
+                        })
+                        .await
+                        .unwrap();
+
+                        let mut wireless = HashMap::new();
+                        for name in names {
+                            if let Some(link) = net::read_wireless(&connection, &name).await {
+                                wireless.insert(name, link);
+                            }
+                        }
+                        sender
+                            .send(Message::NetworkPage(NetworkMessage::Wireless(wireless)))
+                            .await
+                            .unwrap();
+
+                        tokio::time::sleep(Duration::from_millis(1000)).await;
                     }
+                })
+            }));
+        }
+
+        if self.visible
+            && self.view_list.active_data::<NetworkView>() == Some(&NetworkView::Talkers)
+        {
+            subscriptions.push(traffic_subscription());
+        }
+
+        // Finding the owner of every socket walks the descriptors of all processes, so it is
+        // only done while the connections are shown
+        if self.visible
+            && self.view_list.active_data::<NetworkView>() == Some(&NetworkView::Connections)
+        {
+            subscriptions.push(Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    loop {
+                        let sockets = tokio::task::spawn_blocking(sockets::read).await.unwrap();
+                        sender
+                            .send(Message::NetworkPage(NetworkMessage::Sockets(sockets)))
+                            .await
+                            .unwrap();
+
+                        tokio::time::sleep(Duration::from_millis(2000)).await;
+                    }
+                })
+            }));
+        }
+
+        subscriptions
+    }
+}
+
+/// Per-process throughput for the top talkers and the Network column of the processes page,
+/// only subscribed to while one of them is shown. The daemon has no per-process network
+/// accounting, so it is estimated from the TCP counters `ss` reports for each connection.
+pub(super) fn traffic_subscription() -> Subscription<Message> {
+    Subscription::run(|| {
+        stream::channel(1, |mut sender| async move {
+            let mut sampler = TrafficSampler::default();
+
+            loop {
+                let (returned, traffic) = tokio::task::spawn_blocking(move || {
+                    let traffic = sampler.sample();
+                    (sampler, traffic)
+                })
+                .await
+                .unwrap();
+                sampler = returned;
+
+                if let Some(traffic) = traffic {
+                    sender.send(Message::ProcessTraffic(traffic)).await.unwrap();
                 }
-            })
-        })]
+
+                tokio::time::sleep(Duration::from_millis(1000)).await;
+            }
+        })
+    })
+}
+
+/// Keys an interface by its MAC address so it keeps its tab when renamed. Interfaces
+/// without a MAC, or sharing one with another interface such as bridges, bonds and VLANs,
+/// fall back to their name.
+fn interface_id(net: &NetworkInfo, nets: &[NetworkInfo]) -> String {
+    let mac = net.mac_address.to_ascii_lowercase();
+    let usable = !mac.is_empty()
+        && mac.chars().any(|c| c != '0' && c != ':')
+        && nets
+            .iter()
+            .filter(|other| other.mac_address.eq_ignore_ascii_case(&mac))
+            .count()
+            == 1;
+
+    if usable {
+        mac
+    } else {
+        net.interface_name.clone()
     }
 }
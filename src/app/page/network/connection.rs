@@ -0,0 +1,179 @@
+use crate::{
+    fl,
+    sys::sockets::{Protocol, Socket, SocketState},
+};
+use cosmic::{iced::Length, widget};
+use lazy_static::lazy_static;
+use std::{borrow::Cow, net::SocketAddr};
+
+lazy_static! {
+    static ref CONN_PROTOCOL: String = fl!("conn-protocol");
+    static ref CONN_LOCAL: String = fl!("conn-local");
+    static ref CONN_REMOTE: String = fl!("conn-remote");
+    static ref CONN_STATE: String = fl!("conn-state");
+    static ref CONN_PID: String = fl!("conn-pid");
+    static ref CONN_PROCESS: String = fl!("conn-process");
+    static ref CONN_LISTENING: String = fl!("conn-listening");
+    static ref CONN_ESTABLISHED: String = fl!("conn-established");
+    static ref CONN_SYN_SENT: String = fl!("conn-syn-sent");
+    static ref CONN_SYN_RECEIVED: String = fl!("conn-syn-received");
+    static ref CONN_CLOSING: String = fl!("conn-closing");
+    static ref CONN_TIME_WAIT: String = fl!("conn-time-wait");
+    static ref CONN_CLOSE_WAIT: String = fl!("conn-close-wait");
+    static ref CONN_CLOSED: String = fl!("conn-closed");
+    static ref CONN_UNKNOWN: String = fl!("unknown");
+}
+
+/// Identifies a socket across samples. Sockets in TIME_WAIT have no inode, and sockets sharing a
+/// port through `SO_REUSEPORT` have the same addresses, so both are needed.
+pub type ConnectionKey = (Protocol, SocketAddr, SocketAddr, u64);
+
+pub struct ConnectionTableItem {
+    pub socket: Socket,
+    local: Cow<'static, str>,
+    remote: Cow<'static, str>,
+    pid: Cow<'static, str>,
+}
+
+impl ConnectionTableItem {
+    pub fn new(socket: Socket) -> Self {
+        Self {
+            local: socket.local.to_string().into(),
+            remote: if socket.remote.port() == 0 && socket.remote.ip().is_unspecified() {
+                "*".into()
+            } else {
+                socket.remote.to_string().into()
+            },
+            pid: match socket.pid {
+                Some(pid) => pid.to_string().into(),
+                None => "-".into(),
+            },
+            socket,
+        }
+    }
+
+    pub fn key(socket: &Socket) -> ConnectionKey {
+        (socket.protocol, socket.local, socket.remote, socket.inode)
+    }
+
+    /// Refreshes the row in place, only reformatting it when the socket changed
+    pub fn update(&mut self, socket: &Socket) {
+        if socket != &self.socket {
+            *self = Self::new(socket.clone());
+        }
+    }
+}
+
+/// Whether the socket matches a search, where a number matches local or remote ports and
+/// anything else matches addresses and process names
+pub fn matches_search(socket: &Socket, search: &str) -> bool {
+    let search = search.trim();
+    if search.is_empty() {
+        return true;
+    }
+    if let Ok(port) = search.trim_start_matches(':').parse::<u16>() {
+        return socket.local.port() == port || socket.remote.port() == port;
+    }
+
+    let search = search.to_lowercase();
+    socket.local.to_string().contains(&search)
+        || socket.remote.to_string().contains(&search)
+        || socket
+            .process_name
+            .as_ref()
+            .is_some_and(|name| name.to_lowercase().contains(&search))
+}
+
+impl widget::table::ItemInterface<ConnectionTableCategory> for ConnectionTableItem {
+    fn get_icon(&self, _category: ConnectionTableCategory) -> Option<widget::Icon> {
+        None
+    }
+
+    fn get_text(&self, category: ConnectionTableCategory) -> Cow<'static, str> {
+        match category {
+            ConnectionTableCategory::Protocol => match self.socket.protocol {
+                Protocol::Tcp => "TCP".into(),
+                Protocol::Udp => "UDP".into(),
+            },
+            ConnectionTableCategory::Local => self.local.clone(),
+            ConnectionTableCategory::Remote => self.remote.clone(),
+            ConnectionTableCategory::State => {
+                if self.socket.is_listening() {
+                    return CONN_LISTENING.as_str().into();
+                }
+                match self.socket.state {
+                    SocketState::Established => CONN_ESTABLISHED.as_str().into(),
+                    SocketState::SynSent => CONN_SYN_SENT.as_str().into(),
+                    SocketState::SynReceived => CONN_SYN_RECEIVED.as_str().into(),
+                    SocketState::FinWait1
+                    | SocketState::FinWait2
+                    | SocketState::LastAck
+                    | SocketState::Closing => CONN_CLOSING.as_str().into(),
+                    SocketState::TimeWait => CONN_TIME_WAIT.as_str().into(),
+                    SocketState::CloseWait => CONN_CLOSE_WAIT.as_str().into(),
+                    SocketState::Close => CONN_CLOSED.as_str().into(),
+                    SocketState::Listen | SocketState::Unknown => CONN_UNKNOWN.as_str().into(),
+                }
+            }
+            ConnectionTableCategory::Pid => self.pid.clone(),
+            ConnectionTableCategory::Process => match &self.socket.process_name {
+                Some(name) => name.clone().into(),
+                None => "-".into(),
+            },
+        }
+    }
+
+    fn compare(&self, other: &Self, category: ConnectionTableCategory) -> std::cmp::Ordering {
+        match category {
+            ConnectionTableCategory::Protocol => self.socket.protocol.cmp(&other.socket.protocol),
+            ConnectionTableCategory::Local => self.socket.local.cmp(&other.socket.local),
+            ConnectionTableCategory::Remote => self.socket.remote.cmp(&other.socket.remote),
+            ConnectionTableCategory::State => self.socket.state.cmp(&other.socket.state),
+            ConnectionTableCategory::Pid => self.socket.pid.cmp(&other.socket.pid),
+            ConnectionTableCategory::Process => {
+                self.socket.process_name.cmp(&other.socket.process_name)
+            }
+        }
+    }
+}
+
+#[derive(Default, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum ConnectionTableCategory {
+    #[default]
+    Protocol,
+    Local,
+    Remote,
+    State,
+    Pid,
+    Process,
+}
+
+impl std::fmt::Display for ConnectionTableCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Protocol => CONN_PROTOCOL.as_str(),
+                Self::Local => CONN_LOCAL.as_str(),
+                Self::Remote => CONN_REMOTE.as_str(),
+                Self::State => CONN_STATE.as_str(),
+                Self::Pid => CONN_PID.as_str(),
+                Self::Process => CONN_PROCESS.as_str(),
+            }
+        )
+    }
+}
+
+impl widget::table::ItemCategory for ConnectionTableCategory {
+    fn width(&self) -> cosmic::iced::Length {
+        match self {
+            Self::Protocol => Length::Fixed(80.0),
+            Self::Local => Length::Fixed(280.0),
+            Self::Remote => Length::Fixed(280.0),
+            Self::State => Length::Fixed(120.0),
+            Self::Pid => Length::Fixed(80.0),
+            Self::Process => Length::Fixed(200.0),
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+use chrono::{Datelike, Local, Months, NaiveDate, NaiveTime};
+use cosmic::{
+    cosmic_config::{self, CosmicConfigEntry},
+    Application,
+};
+
+use crate::{app::AppModel, config::UsageState};
+
+/// Daily counters are kept long enough to cover the current and the previous billing cycle
+const DAILY_RETENTION: i64 = 62;
+/// Counters are written to disk at most this often
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Usage of one interface over its current billing cycle
+pub struct CycleUsage {
+    pub start: NaiveDate,
+    pub used: u64,
+    /// Usage by the end of the cycle if the rate so far continues
+    pub projected: u64,
+}
+
+/// Per-interface traffic counters that survive restarts of the app and of the machine
+pub struct DataUsage {
+    state: UsageState,
+    context: Option<cosmic_config::Config>,
+    boot_id: String,
+    last_save: Instant,
+    dirty: bool,
+}
+
+impl DataUsage {
+    pub fn load() -> Self {
+        let context = cosmic_config::Config::new_state(AppModel::APP_ID, UsageState::VERSION).ok();
+        let state = context
+            .as_ref()
+            .map(|context| match UsageState::get_entry(context) {
+                Ok(state) => state,
+                Err((errors, state)) => {
+                    for why in errors {
+                        tracing::error!(%why, "error loading network usage");
+                    }
+
+                    state
+                }
+            })
+            .unwrap_or_default();
+
+        Self {
+            state,
+            context,
+            boot_id: fs::read_to_string("/proc/sys/kernel/random/boot_id")
+                .map(|id| id.trim().to_owned())
+                .unwrap_or_default(),
+            last_save: Instant::now(),
+            dirty: false,
+        }
+    }
+
+    /// Adds the traffic since the previous sample, given the interface totals since boot
+    pub fn record(&mut self, interface: &str, rx_total: u64, tx_total: u64) {
+        let since = |total: u64, previous: u64| {
+            // Counters restart when an interface is recreated
+            if total >= previous {
+                total - previous
+            } else {
+                total
+            }
+        };
+        let (rx, tx) = match self.state.baseline.get(interface) {
+            Some((boot_id, rx_previous, tx_previous)) if *boot_id == self.boot_id => {
+                (since(rx_total, *rx_previous), since(tx_total, *tx_previous))
+            }
+            // Traffic between the last run and a reboot is lost, everything since boot is new
+            Some(_) => (rx_total, tx_total),
+            // Interfaces seen for the first time start counting now
+            None => (0, 0),
+        };
+        let baseline = (self.boot_id.clone(), rx_total, tx_total);
+        if self.state.baseline.get(interface) == Some(&baseline) {
+            return;
+        }
+        self.state.baseline.insert(interface.to_owned(), baseline);
+        self.dirty = true;
+
+        if rx == 0 && tx == 0 {
+            return;
+        }
+        let today = Local::now().date_naive();
+        for (counters, key) in [
+            (&mut self.state.daily, day_key(today)),
+            (&mut self.state.monthly, month_key(today)),
+        ] {
+            let counter = counters
+                .entry(interface.to_owned())
+                .or_default()
+                .entry(key)
+                .or_default();
+            counter.0 += rx;
+            counter.1 += tx;
+        }
+    }
+
+    /// Writes the counters to disk if they changed and were not saved recently
+    pub fn save_if_due(&mut self) {
+        if self.last_save.elapsed() >= SAVE_INTERVAL {
+            self.save();
+        }
+    }
+
+    /// Writes the counters to disk if they changed since they were last saved
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+
+        let oldest = day_key(Local::now().date_naive()) - DAILY_RETENTION;
+        for days in self.state.daily.values_mut() {
+            days.retain(|day, _| *day >= oldest);
+        }
+
+        if let Some(context) = &self.context {
+            if let Err(why) = self.state.write_entry(context) {
+                tracing::error!(%why, "error saving network usage");
+            }
+        }
+        self.last_save = Instant::now();
+        self.dirty = false;
+    }
+
+    /// Received and sent bytes today
+    pub fn today(&self, interface: &str) -> (u64, u64) {
+        self.state
+            .daily
+            .get(interface)
+            .and_then(|days| days.get(&day_key(Local::now().date_naive())))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Usage since the billing cycle started on `billing_day` of this or the previous month
+    pub fn cycle(&self, interface: &str, billing_day: u8) -> CycleUsage {
+        let now = Local::now().naive_local();
+        let (start, end) = cycle_bounds(now.date(), billing_day);
+
+        let used = self
+            .state
+            .daily
+            .get(interface)
+            .map(|days| {
+                days.range(day_key(start)..day_key(end))
+                    .map(|(_, (rx, tx))| rx + tx)
+                    .sum()
+            })
+            .unwrap_or_default();
+        // Projecting from the first hours of a cycle is mostly noise
+        let elapsed = (now - start.and_time(NaiveTime::MIN)).num_seconds() as f64 / 86_400.0;
+        let length = (end - start).num_days() as f64;
+
+        CycleUsage {
+            start,
+            used,
+            projected: (used as f64 * length / elapsed.max(1.0)) as u64,
+        }
+    }
+
+    /// Received and sent bytes per calendar month, latest first
+    pub fn months(&self, interface: &str) -> Vec<(NaiveDate, (u64, u64))> {
+        self.state
+            .monthly
+            .get(interface)
+            .map(|months| {
+                months
+                    .iter()
+                    .rev()
+                    .filter_map(|(month, usage)| Some((month_from_key(*month)?, *usage)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Traffic since the last save would otherwise be lost when the app quits
+impl Drop for DataUsage {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+/// The billing cycle `today` falls in, from its first day up to the first day of the next.
+/// Billing days are at most 28, so every month has them.
+fn cycle_bounds(today: NaiveDate, billing_day: u8) -> (NaiveDate, NaiveDate) {
+    let start = today.with_day(u32::from(billing_day)).unwrap_or(today);
+    let start = if today.day() < u32::from(billing_day) {
+        start - Months::new(1)
+    } else {
+        start
+    };
+    (start, start + Months::new(1))
+}
+
+/// Days are counted from the Unix epoch, which is the default date, in local time so they turn
+/// over at local midnight
+fn day_key(date: NaiveDate) -> i64 {
+    date.signed_duration_since(NaiveDate::default()).num_days()
+}
+
+/// Months are counted from January 1970
+fn month_key(date: NaiveDate) -> i64 {
+    i64::from(date.year() - 1970) * 12 + i64::from(date.month0())
+}
+
+fn month_from_key(key: i64) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(
+        1970 + i32::try_from(key.div_euclid(12)).ok()?,
+        key.rem_euclid(12) as u32 + 1,
+        1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn cycles_start_on_the_billing_day() {
+        assert_eq!(
+            cycle_bounds(date(2026, 3, 20), 15),
+            (date(2026, 3, 15), date(2026, 4, 15))
+        );
+        assert_eq!(
+            cycle_bounds(date(2026, 3, 10), 15),
+            (date(2026, 2, 15), date(2026, 3, 15))
+        );
+        assert_eq!(
+            cycle_bounds(date(2026, 1, 5), 28),
+            (date(2025, 12, 28), date(2026, 1, 28))
+        );
+    }
+
+    #[test]
+    fn keys_count_from_the_epoch() {
+        assert_eq!(day_key(date(1970, 1, 2)), 1);
+        assert_eq!(day_key(date(2026, 10, 19)), 20_745);
+        assert_eq!(month_key(date(2026, 10, 19)), 56 * 12 + 9);
+        assert_eq!(month_from_key(56 * 12 + 9), Some(date(2026, 10, 1)));
+    }
+}
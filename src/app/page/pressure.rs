@@ -0,0 +1,111 @@
+use std::collections::VecDeque;
+
+use cosmic::{iced, prelude::*, widget};
+
+use crate::{
+    app::Message,
+    fl,
+    helpers::format_number,
+    sys::pressure::{Pressure, PressureAverages},
+};
+
+/// Recent pressure of one resource, shown both on its own page and in the system overview
+pub struct PressureHistory {
+    some: VecDeque<f32>,
+    full: VecDeque<f32>,
+    latest: Option<Pressure>,
+}
+
+impl PressureHistory {
+    pub fn new() -> Self {
+        Self {
+            some: VecDeque::from(vec![0.0; 30]),
+            full: VecDeque::from(vec![0.0; 30]),
+            latest: None,
+        }
+    }
+
+    pub fn push(&mut self, pressure: Option<Pressure>) {
+        let some = pressure.map(|pressure| pressure.some.avg10);
+        let full = pressure
+            .and_then(|pressure| pressure.full)
+            .map(|full| full.avg10);
+        self.some.push_back(some.unwrap_or_default() / 100.0);
+        self.some.pop_front();
+        self.full.push_back(full.unwrap_or_default() / 100.0);
+        self.full.pop_front();
+
+        self.latest = pressure;
+    }
+
+    /// Full stalls at the bottom with the rest of the partial stalls on top, so the top edge is
+    /// the share of time at least some tasks were stalled
+    pub fn graph(&self) -> Element<'static, Message> {
+        widget::canvas(crate::widget::graph::StackedGraph {
+            series: vec![
+                self.full.iter().cloned().collect(),
+                self.some
+                    .iter()
+                    .zip(&self.full)
+                    .map(|(some, full)| (some - full).max(0.0))
+                    .collect(),
+            ],
+            markers: Vec::new(),
+        })
+        .width(iced::Length::Fill)
+        .height(iced::Length::Fill)
+        .apply(Element::from)
+    }
+
+    /// Graph and averages of one resource, `None` when the kernel does not track its pressure
+    pub fn section(&self, title: String) -> Option<Element<Message>> {
+        let pressure = self.latest?;
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        let legend = [fl!("pressure-full"), fl!("pressure-some")]
+            .into_iter()
+            .enumerate()
+            .map(|(index, label)| {
+                widget::row()
+                    .spacing(cosmic.space_xxxs())
+                    .align_y(iced::Alignment::Center)
+                    .push(
+                        widget::canvas(crate::widget::graph::LegendSwatch { index })
+                            .width(iced::Length::Fixed(10.0))
+                            .height(iced::Length::Fixed(10.0)),
+                    )
+                    .push(widget::text::caption(label))
+                    .apply(Element::from)
+            })
+            .collect();
+
+        widget::settings::section()
+            .title(title)
+            .add(
+                widget::column()
+                    .spacing(cosmic.space_xxs())
+                    .push(widget::container(self.graph()).height(iced::Length::Fixed(120.0)))
+                    .push(widget::row::with_children(legend).spacing(cosmic.space_xs())),
+            )
+            .add(widget::settings::item(
+                fl!("pressure-some"),
+                format_averages(&pressure.some).apply(widget::text::body),
+            ))
+            .add_maybe(pressure.full.map(|full| {
+                widget::settings::item(
+                    fl!("pressure-full"),
+                    format_averages(&full).apply(widget::text::body),
+                )
+            }))
+            .apply(Element::from)
+            .apply(Some)
+    }
+}
+
+/// Formats the 10 second, 1 minute and 5 minute averages in that order
+pub fn format_averages(averages: &PressureAverages) -> String {
+    [averages.avg10, averages.avg60, averages.avg300]
+        .map(|average| format!("{}%", format_number(average as f64)))
+        .join(" / ")
+}
@@ -1,6 +1,8 @@
 mod process;
 use futures_util::SinkExt;
 use process::{ProcessTableCategory, ProcessTableItem};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 use cosmic::{
     app::{context_drawer, Task},
@@ -14,7 +16,12 @@ use crate::{
     app::{ContextPage, Message},
     config::Config,
     fl,
+    sys::traffic::ProcessTraffic,
 };
+
+/// Updating the table should never cost more than a frame at 60 Hz
+const FRAME_BUDGET: Duration = Duration::from_millis(16);
+
 #[derive(Clone, Debug)]
 pub enum ProcessMessage {
     ProcessList(monitord_protocols::monitord::ProcessList),
@@ -26,7 +33,11 @@ pub enum ProcessMessage {
 
 pub struct ProcessPage {
     process_model: widget::table::SingleSelectModel<ProcessTableItem, ProcessTableCategory>,
+    pid_to_entity: HashMap<u32, widget::table::Entity>,
+    traffic: HashMap<u32, ProcessTraffic>,
     show_info: bool,
+    /// Network traffic is only sampled while the page is shown
+    visible: bool,
     // Configuration data that persists between application runs.
     config: Config,
 }
@@ -40,11 +51,60 @@ impl ProcessPage {
                 ProcessTableCategory::Gpu,
                 ProcessTableCategory::Mem,
                 ProcessTableCategory::Disk,
+                ProcessTableCategory::Network,
             ]),
+            pid_to_entity: HashMap::new(),
+            traffic: HashMap::new(),
             show_info: false,
+            visible: false,
             config,
         }
     }
+
+    /// Applies a new process list to the table, updating rows in place keyed by PID
+    fn apply_process_list(&mut self, processes: monitord_protocols::monitord::ProcessList) {
+        let start = Instant::now();
+        let mut alive = HashSet::with_capacity(processes.processes.len());
+        for process in processes.processes {
+            let pid = process.pid;
+            alive.insert(pid);
+            if let Some(item) = self
+                .pid_to_entity
+                .get(&pid)
+                .and_then(|entity| self.process_model.item_mut(*entity))
+            {
+                item.update(process);
+            } else {
+                let mut item = ProcessTableItem::new(process);
+                if let Some(traffic) = self.traffic.get(&pid) {
+                    item.set_traffic((traffic.rx_bytes_per_sec, traffic.tx_bytes_per_sec));
+                }
+                let entity = self.process_model.insert(item).id();
+                self.pid_to_entity.insert(pid, entity);
+            }
+        }
+        self.pid_to_entity.retain(|pid, entity| {
+            if alive.contains(pid) {
+                true
+            } else {
+                self.process_model.remove(*entity);
+                false
+            }
+        });
+        if let Some(sort) = self.process_model.get_sort() {
+            self.process_model.sort(sort.0, sort.1);
+        } else {
+            self.process_model.sort(ProcessTableCategory::Name, false)
+        }
+        let elapsed = start.elapsed();
+        if elapsed > FRAME_BUDGET {
+            tracing::warn!(
+                "process table update took {:?} for {} processes",
+                elapsed,
+                alive.len()
+            );
+        }
+    }
 }
 
 impl super::Page for ProcessPage {
@@ -53,30 +113,7 @@ impl super::Page for ProcessPage {
         match msg {
             Message::UpdateConfig(config) => self.config = config,
             Message::ProcessPage(msg) => match msg {
-                ProcessMessage::ProcessList(processes) => {
-                    let old_sort = self.process_model.get_sort();
-                    let active_process = self
-                        .process_model
-                        .item(self.process_model.active())
-                        .map(|process| process.process.pid);
-                    self.process_model.clear();
-                    for process in processes.processes.iter().cloned() {
-                        let pid = process.pid;
-                        let item = ProcessTableItem::new(process);
-                        self.process_model.insert(item).apply(|entity| {
-                            if let Some(active_pid) = active_process {
-                                if pid == active_pid {
-                                    entity.activate();
-                                }
-                            }
-                        });
-                    }
-                    if let Some(sort) = old_sort {
-                        self.process_model.sort(sort.0, sort.1);
-                    } else {
-                        self.process_model.sort(ProcessTableCategory::Name, false)
-                    }
-                }
+                ProcessMessage::ProcessList(processes) => self.apply_process_list(processes),
                 ProcessMessage::SelectProcess(process) => self.process_model.activate(process),
                 ProcessMessage::SortCategory(category) => {
                     if let Some(sort) = self.process_model.get_sort() {
@@ -132,6 +169,21 @@ impl super::Page for ProcessPage {
                     }));
                 }
             },
+            Message::ProcessTraffic(traffic) => {
+                for (pid, entity) in &self.pid_to_entity {
+                    if let Some(item) = self.process_model.item_mut(*entity) {
+                        item.set_traffic(traffic.get(pid).map_or((0, 0), |traffic| {
+                            (traffic.rx_bytes_per_sec, traffic.tx_bytes_per_sec)
+                        }));
+                    }
+                }
+                self.traffic = traffic;
+            }
+            Message::ShowProcess(pid) => {
+                if let Some(entity) = self.pid_to_entity.get(&pid) {
+                    self.process_model.activate(*entity);
+                }
+            }
             Message::ToggleContextPage(ContextPage::PageAbout) => {
                 self.show_info = true;
             }
@@ -222,6 +274,20 @@ impl super::Page for ProcessPage {
                         fl!("status"),
                         widget::text::caption(process.state.clone()),
                     ))
+                    .add(widget::settings::item(
+                        fl!("proc-net-rx"),
+                        widget::text::caption(format!(
+                            "{}/s",
+                            crate::helpers::get_bytes(selected.traffic.0)
+                        )),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("proc-net-tx"),
+                        widget::text::caption(format!(
+                            "{}/s",
+                            crate::helpers::get_bytes(selected.traffic.1)
+                        )),
+                    ))
                     .apply(Element::from),
                 Message::ToggleContextPage(ContextPage::PageAbout),
             ))
@@ -230,8 +296,12 @@ impl super::Page for ProcessPage {
         }
     }
 
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
     fn subscription(&self) -> Vec<Subscription<Message>> {
-        vec![Subscription::run(|| {
+        let mut subscriptions = vec![Subscription::run(|| {
             stream::channel(1, |mut sender| async move {
                 use monitord_protocols::protocols::MonitordServiceClient;
                 let mut client = MonitordServiceClient::connect("http://127.0.0.1:50051")
@@ -266,6 +336,70 @@ impl super::Page for ProcessPage {
                     }
                 }
             })
-        })]
+        })];
+
+        if self.visible {
+            subscriptions.push(super::network::traffic_subscription());
+        }
+
+        subscriptions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use monitord_protocols::monitord::{ProcessInfo, ProcessList};
+
+    const PROCESS_COUNT: u32 = 20_000;
+    /// Processes replaced by new ones between two lists
+    const CHURN: u32 = 200;
+
+    /// A list in which every usage value differs from the previous tick's, and the oldest
+    /// processes have exited to make room for new ones
+    fn process_list(tick: u32) -> ProcessList {
+        ProcessList {
+            processes: (tick * CHURN..tick * CHURN + PROCESS_COUNT)
+                .map(|pid| ProcessInfo {
+                    pid,
+                    name: format!("process-{pid}"),
+                    cpu_usage_percent: ((pid + tick) % 100) as _,
+                    physical_memory_bytes: u64::from(pid + tick) * 4096,
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture process_table_update`
+    #[test]
+    #[ignore = "benchmark"]
+    fn process_table_update() {
+        let mut page = ProcessPage::new(Config::default());
+        page.apply_process_list(process_list(0));
+
+        let mut samples = (1..=30)
+            .map(|tick| {
+                let processes = process_list(tick);
+                let start = Instant::now();
+                page.apply_process_list(processes);
+                start.elapsed()
+            })
+            .collect::<Vec<_>>();
+        samples.sort();
+        let median = samples[samples.len() / 2];
+        println!(
+            "applied {PROCESS_COUNT} processes: median {median:?}, worst {:?}",
+            samples[samples.len() - 1]
+        );
+
+        assert_eq!(page.pid_to_entity.len(), PROCESS_COUNT as usize);
+        // Unoptimized builds are far slower, so only optimized ones are held to the budget
+        if !cfg!(debug_assertions) {
+            assert!(
+                median < FRAME_BUDGET,
+                "median {median:?} over {FRAME_BUDGET:?}"
+            );
+        }
     }
 }
@@ -10,6 +10,7 @@ lazy_static! {
     static ref PROC_GPU: String = fl!("gpu");
     static ref PROC_MEM: String = fl!("mem");
     static ref PROC_DISK: String = fl!("disk");
+    static ref PROC_NETWORK: String = fl!("proc-net");
 }
 
 pub struct ProcessTableItem {
@@ -19,34 +20,86 @@ pub struct ProcessTableItem {
     gpu: Cow<'static, str>,
     mem: Cow<'static, str>,
     disk: Cow<'static, str>,
+    network: Cow<'static, str>,
+    /// Received and sent bytes per second, estimated locally since the daemon does not track them
+    pub traffic: (u64, u64),
 }
 
 impl ProcessTableItem {
     pub fn new(process: ProcessInfo) -> Self {
         Self {
             name: process.name.clone().into(),
-            cpu: format!("{}%", process.cpu_usage_percent.round()).into(),
-            gpu: format!(
-                "{}%",
-                process
-                    .gpu_usage
-                    .as_ref()
-                    .map(|gpu| gpu.gpu_utilization_percent)
-                    .unwrap_or_default()
-                    .round()
-            )
-            .into(),
-            mem: helpers::get_bytes(process.physical_memory_bytes).into(),
-            disk: format!(
-                "{}/s",
-                helpers::get_bytes(
-                    process.disk_read_bytes_per_sec + process.disk_write_bytes_per_sec
-                )
-            )
-            .into(),
+            cpu: Self::cpu_text(&process),
+            gpu: Self::gpu_text(&process),
+            mem: Self::mem_text(&process),
+            disk: Self::disk_text(&process),
+            network: Self::network_text((0, 0)),
+            traffic: (0, 0),
             process,
         }
     }
+
+    /// Refreshes the row in place, only reformatting the columns whose values changed
+    pub fn update(&mut self, process: ProcessInfo) {
+        if process.name != self.process.name {
+            self.name = process.name.clone().into();
+        }
+        if process.cpu_usage_percent != self.process.cpu_usage_percent {
+            self.cpu = Self::cpu_text(&process);
+        }
+        if process.gpu_usage != self.process.gpu_usage {
+            self.gpu = Self::gpu_text(&process);
+        }
+        if process.physical_memory_bytes != self.process.physical_memory_bytes {
+            self.mem = Self::mem_text(&process);
+        }
+        if process.disk_read_bytes_per_sec != self.process.disk_read_bytes_per_sec
+            || process.disk_write_bytes_per_sec != self.process.disk_write_bytes_per_sec
+        {
+            self.disk = Self::disk_text(&process);
+        }
+        self.process = process;
+    }
+
+    pub fn set_traffic(&mut self, traffic: (u64, u64)) {
+        if traffic != self.traffic {
+            self.network = Self::network_text(traffic);
+            self.traffic = traffic;
+        }
+    }
+
+    fn cpu_text(process: &ProcessInfo) -> Cow<'static, str> {
+        format!("{}%", process.cpu_usage_percent.round()).into()
+    }
+
+    fn gpu_text(process: &ProcessInfo) -> Cow<'static, str> {
+        format!(
+            "{}%",
+            process
+                .gpu_usage
+                .as_ref()
+                .map(|gpu| gpu.gpu_utilization_percent)
+                .unwrap_or_default()
+                .round()
+        )
+        .into()
+    }
+
+    fn mem_text(process: &ProcessInfo) -> Cow<'static, str> {
+        helpers::get_bytes(process.physical_memory_bytes).into()
+    }
+
+    fn disk_text(process: &ProcessInfo) -> Cow<'static, str> {
+        format!(
+            "{}/s",
+            helpers::get_bytes(process.disk_read_bytes_per_sec + process.disk_write_bytes_per_sec)
+        )
+        .into()
+    }
+
+    fn network_text((rx, tx): (u64, u64)) -> Cow<'static, str> {
+        format!("{}/s", helpers::get_bytes(rx + tx)).into()
+    }
 }
 
 impl widget::table::ItemInterface<ProcessTableCategory> for ProcessTableItem {
@@ -66,6 +119,7 @@ impl widget::table::ItemInterface<ProcessTableCategory> for ProcessTableItem {
             ProcessTableCategory::Gpu => self.gpu.clone(),
             ProcessTableCategory::Mem => self.mem.clone(),
             ProcessTableCategory::Disk => self.disk.clone(),
+            ProcessTableCategory::Network => self.network.clone(),
         }
     }
 
@@ -104,6 +158,9 @@ impl widget::table::ItemInterface<ProcessTableCategory> for ProcessTableItem {
                 .physical_memory_bytes
                 .cmp(&other.process.physical_memory_bytes),
             ProcessTableCategory::Disk => self_disk.cmp(&other_disk),
+            ProcessTableCategory::Network => {
+                (self.traffic.0 + self.traffic.1).cmp(&(other.traffic.0 + other.traffic.1))
+            }
         }
     }
 }
@@ -116,6 +173,7 @@ pub enum ProcessTableCategory {
     Gpu,
     Mem,
     Disk,
+    Network,
 }
 
 impl std::fmt::Display for ProcessTableCategory {
@@ -129,6 +187,7 @@ impl std::fmt::Display for ProcessTableCategory {
                 Self::Gpu => PROC_GPU.as_str(),
                 Self::Mem => PROC_MEM.as_str(),
                 Self::Disk => PROC_DISK.as_str(),
+                Self::Network => PROC_NETWORK.as_str(),
             }
         )
     }
@@ -142,6 +201,7 @@ impl widget::table::ItemCategory for ProcessTableCategory {
             Self::Gpu => Length::Fixed(100.0),
             Self::Mem => Length::Fixed(120.0),
             Self::Disk => Length::Fixed(150.0),
+            Self::Network => Length::Fixed(150.0),
         }
     }
 }
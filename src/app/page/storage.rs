@@ -1,4 +1,9 @@
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::Path,
+    sync::atomic::Ordering,
+    time::Duration,
+};
 
 use cosmic::{
     iced::{self, stream, Subscription},
@@ -11,66 +16,1008 @@ use monitord_protocols::{
     protocols::MonitordServiceClient,
 };
 
-use crate::{app::Message, fl};
+use super::{devices::DeviceTabs, pressure::PressureHistory, processes::ProcessMessage};
+use crate::{
+    app::Message,
+    fl,
+    helpers::get_bytes,
+    sys::{
+        self,
+        block::{BlockKind, BlockNode, Layout, PoolKind},
+        diskstats::{DiskStatsSampler, IoStats},
+        diskusage::{self, UsageNode},
+        mounts::{self, Holder, UnmountError},
+        smart::{self, RunningTest, Severity, SmartReport},
+    },
+};
+use analysis::Analysis;
+use attribute::{AttributeTableCategory, AttributeTableItem};
+use metric::StorageMetric;
+
+mod analysis;
+mod attribute;
+mod metric;
 
 #[derive(Debug, Clone)]
 pub enum StorageMessage {
     Snapshot(StorageList),
     SelectTab(widget::segmented_button::Entity),
+    SelectView(widget::segmented_button::Entity),
+    SelectMetric(widget::segmented_button::Entity),
+    /// Extended statistics keyed by kernel device name
+    IoStats(HashMap<String, IoStats>),
+    ReadSmart,
+    /// A SMART report for the disk with the given device path
+    Smart(String, SmartReport),
+    SelectAttribute(widget::table::Entity),
+    SortAttributes(AttributeTableCategory),
+    Layout(Layout),
+    /// Scans the filesystem mounted from the selected device
+    Analyze,
+    CancelAnalysis,
+    /// A finished scan, missing when it was cancelled
+    Analyzed(u64, Option<UsageNode>),
+    DrillDown(usize),
+    DrillUp(usize),
+    Mount,
+    Unmount,
+    /// Unmounts every filesystem on the selected device's disk and powers it off
+    PowerOff,
+    /// Processes keeping the filesystem at a mount point from being unmounted
+    Busy(String, Vec<Holder>),
+    DismissBusy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StorageView {
+    Activity,
+    Health,
+    Layout,
+    Usage,
 }
 
 struct StorageDevice {
     info: StorageInfo,
-    history: VecDeque<f32>,
+    /// Kernel name the extended statistics are reported under
+    kernel_name: String,
+    io: Option<IoStats>,
+    history: HashMap<StorageMetric, VecDeque<f32>>,
+    /// Whole disk holding the device, which SMART data is read from
+    disk: String,
+    removable: bool,
+    smart: Option<SmartReport>,
+    attribute_model: widget::table::SingleSelectModel<AttributeTableItem, AttributeTableCategory>,
+}
+
+impl StorageDevice {
+    fn set_smart(&mut self, report: SmartReport) {
+        let old_sort = self.attribute_model.get_sort();
+        self.attribute_model.clear();
+        for attribute in report.attributes.iter() {
+            self.attribute_model
+                .insert(AttributeTableItem::new(attribute.clone()));
+        }
+        if let Some(sort) = old_sort {
+            self.attribute_model.sort(sort.0, sort.1);
+        }
+        self.smart = Some(report);
+    }
 }
 
 pub struct StoragePage {
-    storage_list: widget::segmented_button::SingleSelectModel,
-    name_to_entity: HashMap<String, widget::segmented_button::Entity>,
+    storage_list: DeviceTabs<StorageDevice>,
+    view_list: widget::segmented_button::SingleSelectModel,
+    metric_list: widget::segmented_button::SingleSelectModel,
+    layout: Layout,
+    analysis: Option<Analysis>,
+    // Identifies scans, so a cancelled one finishing late is ignored
+    scan_count: u64,
+    busy: Option<(String, Vec<Holder>)>,
+    // IO pressure is system wide, so it is shown for every device
+    pressure: PressureHistory,
 }
 
 impl StoragePage {
     pub fn new() -> Self {
+        let mut view_list = widget::segmented_button::SingleSelectModel::default();
+        view_list
+            .insert()
+            .text(fl!("storage-activity"))
+            .data(StorageView::Activity)
+            .activate();
+        view_list
+            .insert()
+            .text(fl!("storage-health"))
+            .data(StorageView::Health);
+        view_list
+            .insert()
+            .text(fl!("storage-layout"))
+            .data(StorageView::Layout);
+        view_list
+            .insert()
+            .text(fl!("storage-usage"))
+            .data(StorageView::Usage);
+
+        let mut metric_list = widget::segmented_button::SingleSelectModel::default();
+        metric_list
+            .insert()
+            .text(StorageMetric::Throughput.label())
+            .data(StorageMetric::Throughput)
+            .activate();
+        for metric in &StorageMetric::ALL[1..] {
+            metric_list.insert().text(metric.label()).data(*metric);
+        }
+
         Self {
-            storage_list: widget::segmented_button::SingleSelectModel::default(),
-            name_to_entity: HashMap::new(),
+            storage_list: DeviceTabs::new(),
+            view_list,
+            metric_list,
+            layout: Layout::default(),
+            analysis: None,
+            scan_count: 0,
+            busy: None,
+            pressure: PressureHistory::new(),
+        }
+    }
+
+    fn activity_view(&self, storage: &StorageDevice) -> Element<Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        let metric = self
+            .metric_list
+            .active_data::<StorageMetric>()
+            .copied()
+            .unwrap_or(StorageMetric::Throughput);
+
+        widget::row()
+            .spacing(cosmic.space_xxs())
+            .push(
+                widget::column()
+                    .spacing(cosmic.space_xxs())
+                    .width(iced::Length::Fill)
+                    .push(
+                        widget::segmented_control::horizontal(&self.metric_list).on_activate(
+                            |entity| Message::StoragePage(StorageMessage::SelectMetric(entity)),
+                        ),
+                    )
+                    .push(match storage.history.get(&metric) {
+                        Some(history) => widget::canvas(crate::widget::graph::LineGraph {
+                            points: metric.normalize(history),
+                        })
+                        .width(iced::Length::Fill)
+                        .height(iced::Length::Fill)
+                        .apply(Element::from),
+                        None => widget::text::body(fl!("not-supported")).apply(Element::from),
+                    }),
+            )
+            .push(
+                widget::settings::view_column(vec![
+                    widget::settings::section()
+                        .title(fl!("storage-info"))
+                        .add(widget::settings::item(
+                            fl!("storage-device"),
+                            storage.info.device_name.clone().apply(widget::text::body),
+                        ))
+                        .add(widget::settings::item(
+                            fl!("storage-type"),
+                            storage.info.device_type.clone().apply(widget::text::body),
+                        ))
+                        .add(widget::settings::item(
+                            fl!("model"),
+                            storage.info.model.clone().apply(widget::text::body),
+                        ))
+                        .add_maybe(storage.info.serial_number.as_ref().map(|serial| {
+                            widget::settings::item(
+                                fl!("disk-serial"),
+                                serial.clone().apply(widget::text::body),
+                            )
+                        }))
+                        .add_maybe(storage.info.partition_label.as_ref().map(|plabel| {
+                            widget::settings::item(
+                                fl!("part-label"),
+                                plabel.clone().apply(widget::text::body),
+                            )
+                        }))
+                        .add(widget::settings::item(
+                            fl!("fs-type"),
+                            storage
+                                .info
+                                .filesystem_type
+                                .clone()
+                                .apply(widget::text::body),
+                        ))
+                        .add(widget::settings::item(
+                            fl!("mount-point"),
+                            storage.info.mount_point.clone().apply(widget::text::body),
+                        ))
+                        .add(widget::settings::item(
+                            fl!("disk-space"),
+                            storage
+                                .info
+                                .total_space_bytes
+                                .apply(crate::helpers::get_bytes)
+                                .apply(widget::text::body),
+                        ))
+                        .apply(Element::from),
+                    widget::settings::section()
+                        .title(fl!("storage-stats"))
+                        .add(widget::settings::item(
+                            fl!("available-space"),
+                            storage
+                                .info
+                                .available_space_bytes
+                                .apply(crate::helpers::get_bytes)
+                                .apply(widget::text::body),
+                        ))
+                        .add(widget::settings::item(
+                            fl!("disk-read"),
+                            format!(
+                                "{}/s",
+                                storage
+                                    .info
+                                    .read_bytes_per_sec
+                                    .apply(crate::helpers::get_bytes)
+                            )
+                            .apply(widget::text::body),
+                        ))
+                        .add(widget::settings::item(
+                            fl!("disk-write"),
+                            format!(
+                                "{}/s",
+                                storage
+                                    .info
+                                    .write_bytes_per_sec
+                                    .apply(crate::helpers::get_bytes)
+                            )
+                            .apply(widget::text::body),
+                        ))
+                        .add(widget::settings::item(
+                            fl!("io-time"),
+                            format!("{} ms", storage.info.io_time_ms).apply(widget::text::body),
+                        ))
+                        .add_maybe(storage.info.temperature_celsius.map(|temp| {
+                            widget::settings::item(
+                                fl!("disk-temp"),
+                                format!("{}°C", temp.apply(crate::helpers::format_number))
+                                    .apply(widget::text::body),
+                            )
+                        }))
+                        .add_maybe(storage.info.smart_data.as_ref().map(|smart| {
+                            widget::settings::item(
+                                fl!("smart-status"),
+                                smart.health_status.clone().apply(widget::text::body),
+                            )
+                        }))
+                        .apply(Element::from),
+                    storage
+                        .io
+                        .map(|io| {
+                            widget::settings::section()
+                                .title(fl!("disk-performance"))
+                                .add(widget::settings::item(
+                                    fl!("disk-iops"),
+                                    fl!(
+                                        "disk-read-write",
+                                        read = format!("{:.0}", io.reads_per_sec),
+                                        write = format!("{:.0}", io.writes_per_sec)
+                                    )
+                                    .apply(widget::text::body),
+                                ))
+                                .add(widget::settings::item(
+                                    fl!("disk-latency"),
+                                    fl!(
+                                        "disk-read-write",
+                                        read = format!("{:.2} ms", io.read_latency_ms),
+                                        write = format!("{:.2} ms", io.write_latency_ms)
+                                    )
+                                    .apply(widget::text::body),
+                                ))
+                                .add(widget::settings::item(
+                                    fl!("disk-queue-depth"),
+                                    format!("{:.2}", io.queue_depth).apply(widget::text::body),
+                                ))
+                                .add(widget::settings::item(
+                                    fl!("disk-in-flight"),
+                                    io.in_flight.to_string().apply(widget::text::body),
+                                ))
+                                .add(widget::settings::item(
+                                    fl!("disk-utilization"),
+                                    format!("{:.1}%", io.utilization).apply(widget::text::body),
+                                ))
+                                .apply(Element::from)
+                        })
+                        .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
+                    self.pressure
+                        .section(fl!("io-pressure"))
+                        .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
+                    storage
+                        .removable
+                        .then(|| self.removable_section(storage))
+                        .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
+                ])
+                .apply(widget::scrollable),
+            )
+            .apply(Element::from)
+    }
+
+    fn removable_section(&self, storage: &StorageDevice) -> Element<Message> {
+        let mounted = !storage.info.mount_point.is_empty();
+        let holders = self
+            .busy
+            .as_ref()
+            .filter(|(mount_point, _)| *mount_point == storage.info.mount_point)
+            .map(|(_, holders)| holders);
+
+        widget::settings::section()
+            .title(fl!("storage-removable"))
+            .add(
+                widget::row()
+                    .spacing(cosmic::theme::active().cosmic().space_xs())
+                    .push(widget::horizontal_space())
+                    .push(if mounted {
+                        fl!("storage-unmount")
+                            .apply(widget::button::standard)
+                            .on_press(Message::StoragePage(StorageMessage::Unmount))
+                    } else {
+                        fl!("storage-mount")
+                            .apply(widget::button::standard)
+                            .on_press(Message::StoragePage(StorageMessage::Mount))
+                    })
+                    .push(
+                        fl!("storage-power-off")
+                            .apply(widget::button::standard)
+                            .on_press(Message::StoragePage(StorageMessage::PowerOff)),
+                    ),
+            )
+            .add_maybe(holders.map(|holders| {
+                widget::settings::item_row(vec![
+                    widget::text::body(if holders.is_empty() {
+                        fl!("storage-busy-unknown")
+                    } else {
+                        fl!("storage-busy")
+                    })
+                    .apply(Element::from),
+                    widget::horizontal_space().apply(Element::from),
+                    fl!("storage-retry")
+                        .apply(widget::button::standard)
+                        .on_press(Message::StoragePage(StorageMessage::Unmount))
+                        .apply(Element::from),
+                ])
+            }))
+            .extend(holders.into_iter().flatten().map(|holder| {
+                widget::settings::item::builder(format!("{} ({})", holder.name, holder.pid))
+                    .description(holder.path.clone())
+                    .control(
+                        widget::row()
+                            .spacing(cosmic::theme::active().cosmic().space_xxs())
+                            .push(fl!("term").apply(widget::button::standard).on_press(
+                                Message::ProcessPage(ProcessMessage::TermProcess(holder.pid)),
+                            ))
+                            .push(fl!("kill").apply(widget::button::destructive).on_press(
+                                Message::ProcessPage(ProcessMessage::KillProcess(holder.pid)),
+                            )),
+                    )
+            }))
+            .apply(Element::from)
+    }
+
+    fn health_view(&self, storage: &StorageDevice) -> Element<Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        let summary = storage.smart.as_ref().map(|report| {
+            widget::settings::section()
+                .title(fl!("smart-health"))
+                .add_maybe(report.passed.map(|passed| {
+                    widget::settings::item(
+                        fl!("smart-assessment"),
+                        if passed {
+                            severity_text(fl!("smart-passed"), Severity::Ok)
+                        } else {
+                            severity_text(fl!("smart-failed"), Severity::Failing)
+                        },
+                    )
+                }))
+                .add_maybe(report.power_on_hours.map(|hours| {
+                    widget::settings::item(
+                        fl!("smart-power-on"),
+                        widget::text::body(fl!("smart-hours", hours = hours)),
+                    )
+                }))
+                .add_maybe(report.wear_percent.map(|wear| {
+                    widget::settings::item(
+                        fl!("smart-wear"),
+                        severity_text(
+                            format!("{wear}%"),
+                            match wear {
+                                100.. => Severity::Failing,
+                                90.. => Severity::Warning,
+                                _ => Severity::Ok,
+                            },
+                        ),
+                    )
+                }))
+                .add_maybe(report.media_errors.map(|errors| {
+                    widget::settings::item(
+                        fl!("smart-media-errors"),
+                        severity_text(
+                            errors.to_string(),
+                            if errors > 0 {
+                                Severity::Warning
+                            } else {
+                                Severity::Ok
+                            },
+                        ),
+                    )
+                }))
+                .add_maybe(report.running_test.as_ref().map(|test| {
+                    widget::settings::item(
+                        fl!("smart-running"),
+                        widget::text::body(match test {
+                            RunningTest::Remaining(percent) => {
+                                fl!("smart-test-remaining", percent = *percent)
+                            }
+                            RunningTest::Completed { operation, percent } => fl!(
+                                "smart-test-progress",
+                                operation = operation.as_str(),
+                                percent = *percent
+                            ),
+                        }),
+                    )
+                }))
+                .apply(Element::from)
+        });
+
+        let self_tests = storage.smart.as_ref().map(|report| {
+            widget::settings::section()
+                .title(fl!("smart-self-tests"))
+                .add_maybe(
+                    report
+                        .self_tests
+                        .is_empty()
+                        .then(|| widget::text::body(fl!("smart-no-self-tests"))),
+                )
+                .extend(report.self_tests.iter().map(|test| {
+                    // Covers read, electrical and servo failures as well as failed segments
+                    let failed = test.status.to_lowercase().contains("fail");
+                    widget::settings::item(
+                        match test.lifetime_hours {
+                            Some(hours) => format!(
+                                "{} ({})",
+                                test.description,
+                                fl!("smart-hours", hours = hours)
+                            ),
+                            None => test.description.clone(),
+                        },
+                        severity_text(
+                            test.status.clone(),
+                            if failed {
+                                Severity::Failing
+                            } else {
+                                Severity::Ok
+                            },
+                        ),
+                    )
+                }))
+                .apply(Element::from)
+        });
+
+        widget::column()
+            .spacing(cosmic.space_xs())
+            .push(
+                widget::row()
+                    .spacing(cosmic.space_xs())
+                    .push(widget::horizontal_space())
+                    .push(
+                        if storage.smart.is_some() {
+                            fl!("smart-reload")
+                        } else {
+                            fl!("smart-read")
+                        }
+                        .apply(widget::button::suggested)
+                        .on_press(Message::StoragePage(StorageMessage::ReadSmart)),
+                    ),
+            )
+            .push(
+                widget::column()
+                    .spacing(cosmic.space_xs())
+                    .push_maybe(
+                        storage
+                            .smart
+                            .is_none()
+                            .then(|| widget::text::body(fl!("smart-unread"))),
+                    )
+                    .push_maybe(summary)
+                    .push_maybe(storage.smart.as_ref().map(|_| {
+                        widget::table(&storage.attribute_model)
+                            .on_item_left_click(|entity| {
+                                Message::StoragePage(StorageMessage::SelectAttribute(entity))
+                            })
+                            .on_category_left_click(|category| {
+                                Message::StoragePage(StorageMessage::SortAttributes(category))
+                            })
+                    }))
+                    .push_maybe(self_tests)
+                    .apply(widget::scrollable)
+                    .height(iced::Length::Fill),
+            )
+            .apply(Element::from)
+    }
+
+    fn usage_view(&self, storage: &StorageDevice) -> Element<Message> {
+        if storage.info.mount_point.is_empty() {
+            return widget::text::body(fl!("analysis-unmounted")).apply(Element::from);
+        }
+        if let Some(analysis) = self
+            .analysis
+            .as_ref()
+            .filter(|analysis| analysis.mount_point == storage.info.mount_point)
+        {
+            return analysis.view();
+        }
+
+        widget::column()
+            .spacing(cosmic::theme::active().cosmic().space_xs())
+            .push(widget::text::body(fl!(
+                "analysis-intro",
+                mount_point = storage.info.mount_point.clone()
+            )))
+            .push(
+                fl!("analysis-start")
+                    .apply(widget::button::suggested)
+                    .on_press(Message::StoragePage(StorageMessage::Analyze)),
+            )
+            .apply(Element::from)
+    }
+
+    /// The device in the latest snapshot matching a node, by kernel or device-mapper name
+    fn storage_of(&self, node: &BlockNode) -> Option<&StorageInfo> {
+        self.storage_list
+            .model
+            .iter()
+            .filter_map(|entity| self.storage_list.model.data::<StorageDevice>(entity))
+            .map(|storage| &storage.info)
+            .find(|info| {
+                info.device_name == node.label
+                    || info.device_name.trim_start_matches("/dev/") == node.name
+            })
+    }
+
+    /// A value of the node's own device, or the sum over the devices built on it.
+    /// Logical volumes spanning several disks are counted under each of them.
+    fn aggregate(&self, node: &BlockNode, value: &impl Fn(&StorageInfo) -> u64) -> Option<u64> {
+        self.storage_of(node).map(value).or_else(|| {
+            node.children
+                .iter()
+                .filter_map(|child| self.aggregate(child, value))
+                .reduce(|a, b| a + b)
+        })
+    }
+
+    fn layout_rows<'a>(
+        &'a self,
+        node: &'a BlockNode,
+        depth: u16,
+        rows: &mut Vec<Element<'a, Message>>,
+    ) {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        let kind = match &node.kind {
+            BlockKind::Disk => fl!("block-disk"),
+            BlockKind::Partition => fl!("block-partition"),
+            BlockKind::Crypt { luks: true } => fl!("block-luks"),
+            BlockKind::Crypt { luks: false } => fl!("block-crypt"),
+            BlockKind::VolumeGroup => fl!("block-vg"),
+            BlockKind::LogicalVolume => fl!("block-lv"),
+            BlockKind::Mapper => fl!("block-mapper"),
+            BlockKind::Raid(status) => {
+                let mut kind = fl!("block-raid", level = status.level.clone());
+                if let Some((action, progress)) = &status.sync {
+                    kind.push_str(&format!(", {action} {progress:.1}%"));
+                }
+                if status.degraded > 0 {
+                    kind.push_str(&format!(
+                        ", {}",
+                        fl!("block-degraded", missing = status.degraded)
+                    ));
+                }
+                kind
+            }
+        };
+
+        let used = self.aggregate(node, &|info| {
+            info.total_space_bytes
+                .saturating_sub(info.available_space_bytes)
+        });
+        let throughput = self.aggregate(node, &|info| {
+            info.read_bytes_per_sec + info.write_bytes_per_sec
+        });
+        let mount_point = self
+            .storage_of(node)
+            .map(|info| info.mount_point.clone())
+            .filter(|mount_point| !mount_point.is_empty());
+
+        rows.push(
+            widget::settings::item_row(vec![
+                widget::horizontal_space()
+                    .width(iced::Length::Fixed(f32::from(depth * cosmic.space_m())))
+                    .apply(Element::from),
+                widget::column()
+                    .push(widget::text::body(node.label.clone()))
+                    .push(widget::text::caption(match mount_point {
+                        Some(mount_point) => format!("{kind} · {mount_point}"),
+                        None => kind,
+                    }))
+                    .apply(Element::from),
+                widget::horizontal_space().apply(Element::from),
+                widget::column()
+                    .align_x(iced::Alignment::End)
+                    .push(widget::text::body(match used {
+                        Some(used) => fl!(
+                            "block-used",
+                            used = get_bytes(used),
+                            size = get_bytes(node.size_bytes)
+                        ),
+                        None => get_bytes(node.size_bytes),
+                    }))
+                    .push_maybe(
+                        throughput
+                            .filter(|throughput| *throughput > 0)
+                            .map(|throughput| {
+                                widget::text::caption(format!("{}/s", get_bytes(throughput)))
+                            }),
+                    )
+                    .apply(Element::from),
+            ])
+            .apply(Element::from),
+        );
+
+        for child in node.children.iter() {
+            self.layout_rows(child, depth + 1, rows);
         }
     }
+
+    fn layout_view(&self) -> Element<Message> {
+        let disks = self.layout.disks.iter().map(|disk| {
+            let mut rows = Vec::new();
+            self.layout_rows(disk, 0, &mut rows);
+            widget::settings::section()
+                .title(
+                    self.storage_of(disk)
+                        .map(|info| info.model.clone())
+                        .filter(|model| !model.is_empty())
+                        .unwrap_or_else(|| disk.label.clone()),
+                )
+                .extend(rows)
+                .apply(Element::from)
+        });
+
+        let pools = (!self.layout.pools.is_empty()).then(|| {
+            widget::settings::section()
+                .title(fl!("block-pools"))
+                .extend(self.layout.pools.iter().map(|pool| {
+                    let kind = match pool.kind {
+                        PoolKind::Btrfs => "Btrfs".to_owned(),
+                        PoolKind::Zfs => "ZFS".to_owned(),
+                    };
+                    widget::settings::item::builder(pool.name.clone())
+                        .description(match &pool.health {
+                            Some(health) => format!("{kind} · {health}"),
+                            None => kind,
+                        })
+                        .control(
+                            widget::column()
+                                .align_x(iced::Alignment::End)
+                                .push(widget::text::body(fl!(
+                                    "block-used",
+                                    used = get_bytes(pool.used_bytes),
+                                    size = get_bytes(pool.size_bytes)
+                                )))
+                                .push(widget::text::caption(
+                                    pool.members
+                                        .iter()
+                                        .map(|member| format!("/dev/{member}"))
+                                        .collect::<Vec<_>>()
+                                        .join(", "),
+                                )),
+                        )
+                }))
+                .apply(Element::from)
+        });
+
+        widget::settings::view_column(disks.chain(pools).collect())
+            .apply(widget::scrollable)
+            .height(iced::Length::Fill)
+            .apply(Element::from)
+    }
+}
+
+/// Unmounts filesystems in turn and then powers off their disk if one is given. Stops at the
+/// first filesystem still in use and finds the processes holding it.
+async fn eject(
+    filesystems: Vec<(String, String)>,
+    disk: Option<String>,
+) -> cosmic::Action<Message> {
+    for (device, mount_point) in filesystems {
+        match mounts::unmount(device).await {
+            Ok(()) => {}
+            Err(UnmountError::Busy) => {
+                let holders = tokio::task::spawn_blocking({
+                    let mount_point = mount_point.clone();
+                    move || mounts::holders(&mount_point)
+                })
+                .await
+                .unwrap_or_default();
+                return cosmic::Action::App(Message::StoragePage(StorageMessage::Busy(
+                    mount_point,
+                    holders,
+                )));
+            }
+            Err(UnmountError::Failed(err)) => {
+                return cosmic::Action::App(Message::Error(format!(
+                    "Failed to unmount {mount_point}: {err}"
+                )))
+            }
+        }
+    }
+
+    if let Some(disk) = disk {
+        if let Err(err) = mounts::power_off(disk).await {
+            return cosmic::Action::App(Message::Error(format!("Failed to power off: {err}")));
+        }
+    }
+    cosmic::Action::App(Message::StoragePage(StorageMessage::DismissBusy))
+}
+
+/// Body text in the theme's warning or destructive color for worrying values
+fn severity_text(text: String, severity: Severity) -> Element<'static, Message> {
+    let theme = cosmic::theme::active();
+    let color = match severity {
+        Severity::Ok => return widget::text::body(text).into(),
+        Severity::Warning => theme.cosmic().warning_color(),
+        Severity::Failing => theme.cosmic().destructive_color(),
+    };
+    widget::text::body(text)
+        .class(cosmic::theme::Text::Color(color.into()))
+        .into()
 }
 
 impl super::Page for StoragePage {
     fn update(&mut self, msg: Message) -> cosmic::app::Task<Message> {
-        let tasks = Vec::new();
+        let mut tasks = Vec::new();
 
         match msg {
+            Message::Pressure(pressure) => self.pressure.push(pressure.io),
             Message::StoragePage(StorageMessage::Snapshot(snapshot)) => {
+                let mut present = HashSet::new();
                 for storage in snapshot.storages.iter() {
-                    let entity = if let Some(entity) = self.name_to_entity.get(&storage.device_name)
+                    // Prefer the disk's WWN or serial so a replugged disk keeps its tab
+                    let id = sys::block::stable_id(
+                        &storage.device_name,
+                        storage.serial_number.as_deref(),
+                    )
+                    .unwrap_or_else(|| storage.device_name.clone());
+                    present.insert(id.clone());
+                    let device = self
+                        .storage_list
+                        .upsert(&id, storage.device_name.clone(), || StorageDevice {
+                            info: storage.clone(),
+                            kernel_name: sys::block::kernel_name(&storage.device_name),
+                            io: None,
+                            history: HashMap::from([(
+                                StorageMetric::Throughput,
+                                VecDeque::from(vec![0.0; 30]),
+                            )]),
+                            disk: sys::block::disk_of(&storage.device_name),
+                            removable: sys::block::is_removable(&storage.device_name),
+                            smart: None,
+                            attribute_model: widget::table::SingleSelectModel::new(vec![
+                                AttributeTableCategory::Id,
+                                AttributeTableCategory::Attribute,
+                                AttributeTableCategory::Value,
+                                AttributeTableCategory::Worst,
+                                AttributeTableCategory::Threshold,
+                                AttributeTableCategory::Raw,
+                                AttributeTableCategory::State,
+                            ]),
+                        });
+                    device.info = storage.clone();
+                    if let Some(history) = device.history.get_mut(&StorageMetric::Throughput) {
+                        history.push_back(
+                            storage.write_bytes_per_sec as f32 + storage.read_bytes_per_sec as f32,
+                        );
+                        history.pop_front();
+                    }
+                }
+                self.storage_list.retain_present(&present);
+            }
+            Message::StoragePage(StorageMessage::SelectTab(tab)) => {
+                self.storage_list.model.activate(tab)
+            }
+            Message::StoragePage(StorageMessage::SelectView(view)) => self.view_list.activate(view),
+            Message::StoragePage(StorageMessage::Layout(layout)) => self.layout = layout,
+            Message::StoragePage(StorageMessage::Analyze) => {
+                let Some(mount_point) = self
+                    .storage_list
+                    .model
+                    .active_data::<StorageDevice>()
+                    .map(|storage| storage.info.mount_point.clone())
+                    .filter(|mount_point| !mount_point.is_empty())
+                else {
+                    return cosmic::app::Task::none();
+                };
+                // Only one filesystem is scanned at a time
+                if let Some(analysis) = self.analysis.take() {
+                    analysis.progress.cancel.store(true, Ordering::Relaxed);
+                }
+                self.scan_count += 1;
+                let analysis = Analysis::new(self.scan_count, mount_point.clone());
+                let (id, progress) = (analysis.id, analysis.progress.clone());
+                self.analysis = Some(analysis);
+
+                tasks.push(cosmic::app::Task::future(async move {
+                    let root = tokio::task::spawn_blocking(move || {
+                        diskusage::scan(Path::new(&mount_point), &progress)
+                    })
+                    .await
+                    .ok()
+                    .flatten();
+                    cosmic::Action::App(Message::StoragePage(StorageMessage::Analyzed(id, root)))
+                }));
+            }
+            Message::StoragePage(StorageMessage::CancelAnalysis) => {
+                if let Some(analysis) = self.analysis.take() {
+                    analysis.progress.cancel.store(true, Ordering::Relaxed);
+                }
+            }
+            Message::StoragePage(StorageMessage::Analyzed(id, root)) => {
+                if self
+                    .analysis
+                    .as_ref()
+                    .is_some_and(|analysis| analysis.id == id)
+                {
+                    // Scans only come back empty when the mount point could not be read
+                    match (root, self.analysis.as_mut()) {
+                        (Some(root), Some(analysis)) => analysis.root = Some(root),
+                        _ => self.analysis = None,
+                    }
+                }
+            }
+            Message::StoragePage(StorageMessage::DrillDown(index)) => {
+                if let Some(analysis) = self.analysis.as_mut() {
+                    analysis.drill_down(index);
+                }
+            }
+            Message::StoragePage(StorageMessage::DrillUp(depth)) => {
+                if let Some(analysis) = self.analysis.as_mut() {
+                    analysis.drill_up(depth);
+                }
+            }
+            Message::StoragePage(StorageMessage::Mount) => {
+                if let Some(storage) = self.storage_list.model.active_data::<StorageDevice>() {
+                    let device = storage.info.device_name.clone();
+                    tasks.push(cosmic::app::Task::future(async move {
+                        match mounts::mount(device).await {
+                            Ok(()) => cosmic::Action::App(Message::NoOp),
+                            Err(err) => cosmic::Action::App(Message::Error(format!(
+                                "Failed to mount: {err}"
+                            ))),
+                        }
+                    }));
+                }
+            }
+            Message::StoragePage(StorageMessage::Unmount) => {
+                if let Some(storage) = self.storage_list.model.active_data::<StorageDevice>() {
+                    let filesystems = vec![(
+                        storage.info.device_name.clone(),
+                        storage.info.mount_point.clone(),
+                    )];
+                    tasks.push(cosmic::app::Task::future(eject(filesystems, None)));
+                }
+            }
+            Message::StoragePage(StorageMessage::PowerOff) => {
+                if let Some(disk) = self
+                    .storage_list
+                    .model
+                    .active_data::<StorageDevice>()
+                    .map(|storage| storage.disk.clone())
+                {
+                    let filesystems = self
+                        .storage_list
+                        .model
+                        .iter()
+                        .filter_map(|entity| self.storage_list.model.data::<StorageDevice>(entity))
+                        .filter(|storage| {
+                            storage.disk == disk && !storage.info.mount_point.is_empty()
+                        })
+                        .map(|storage| {
+                            (
+                                storage.info.device_name.clone(),
+                                storage.info.mount_point.clone(),
+                            )
+                        })
+                        .collect();
+                    tasks.push(cosmic::app::Task::future(eject(filesystems, Some(disk))));
+                }
+            }
+            Message::StoragePage(StorageMessage::Busy(mount_point, holders)) => {
+                self.busy = Some((mount_point, holders))
+            }
+            Message::StoragePage(StorageMessage::DismissBusy) => self.busy = None,
+            Message::StoragePage(StorageMessage::SelectMetric(metric)) => {
+                self.metric_list.activate(metric)
+            }
+            Message::StoragePage(StorageMessage::IoStats(mut stats)) => {
+                for entity in self.storage_list.model.iter().collect::<Vec<_>>() {
+                    let Some(device) = self.storage_list.model.data_mut::<StorageDevice>(entity)
+                    else {
+                        continue;
+                    };
+                    device.io = stats.remove(&device.kernel_name);
+                    let Some(io) = device.io else {
+                        continue;
+                    };
+                    for metric in StorageMetric::ALL {
+                        if let Some(value) = metric.sample(&io) {
+                            let history = device
+                                .history
+                                .entry(metric)
+                                .or_insert_with(|| VecDeque::from(vec![0.0; 30]));
+                            history.push_back(value);
+                            history.pop_front();
+                        }
+                    }
+                }
+            }
+            Message::StoragePage(StorageMessage::ReadSmart) => {
+                if let Some(storage) = self.storage_list.model.active_data::<StorageDevice>() {
+                    let disk = storage.disk.clone();
+                    tasks.push(cosmic::app::Task::future(async move {
+                        match smart::read(disk.clone()).await {
+                            Ok(report) => cosmic::Action::App(Message::StoragePage(
+                                StorageMessage::Smart(disk, report),
+                            )),
+                            Err(err) => cosmic::Action::App(Message::Error(format!(
+                                "Failed to read SMART data: {err}"
+                            ))),
+                        }
+                    }));
+                }
+            }
+            Message::StoragePage(StorageMessage::Smart(disk, report)) => {
+                // Partitions of the same disk share its report
+                for entity in self.storage_list.model.iter().collect::<Vec<_>>() {
+                    if let Some(storage) = self.storage_list.model.data_mut::<StorageDevice>(entity)
                     {
-                        entity.clone()
+                        if storage.disk == disk {
+                            storage.set_smart(report.clone());
+                        }
+                    }
+                }
+            }
+            Message::StoragePage(StorageMessage::SelectAttribute(attribute)) => {
+                let active = self.storage_list.model.active();
+                if let Some(storage) = self.storage_list.model.data_mut::<StorageDevice>(active) {
+                    storage.attribute_model.activate(attribute);
+                }
+            }
+            Message::StoragePage(StorageMessage::SortAttributes(category)) => {
+                let active = self.storage_list.model.active();
+                if let Some(storage) = self.storage_list.model.data_mut::<StorageDevice>(active) {
+                    let model = &mut storage.attribute_model;
+                    if let Some(sort) = model.get_sort() {
+                        if sort.0 == category {
+                            model.sort(category, !sort.1);
+                        } else {
+                            model.sort(category, false)
+                        }
                     } else {
-                        let entity = self
-                            .storage_list
-                            .insert()
-                            .text(storage.device_name.clone())
-                            .data(StorageDevice {
-                                info: storage.clone(),
-                                history: VecDeque::from(vec![0.0; 30]),
-                            })
-                            .id();
-                        self.name_to_entity
-                            .insert(storage.device_name.clone(), entity.clone());
-                        entity
-                    };
-                    let device = self.storage_list.data_mut::<StorageDevice>(entity).unwrap();
-                    device.info = storage.clone();
-                    device.history.push_back(
-                        storage.write_bytes_per_sec as f32 + storage.read_bytes_per_sec as f32,
-                    );
-                    device.history.pop_front();
+                        model.sort(category, false)
+                    }
                 }
             }
-            Message::StoragePage(StorageMessage::SelectTab(tab)) => self.storage_list.activate(tab),
             _ => {}
         }
 
@@ -81,196 +1028,114 @@ impl super::Page for StoragePage {
         let theme = cosmic::theme::active();
         let cosmic = theme.cosmic();
 
+        let view = self.view_list.active_data::<StorageView>().copied();
+
         widget::column()
             .spacing(cosmic.space_xs())
             .push(
-                widget::tab_bar::horizontal(&self.storage_list)
-                    .on_activate(|entity| Message::StoragePage(StorageMessage::SelectTab(entity))),
+                widget::segmented_control::horizontal(&self.view_list)
+                    .on_activate(|entity| Message::StoragePage(StorageMessage::SelectView(entity))),
             )
-            .push_maybe(
-                self.storage_list
+            // The layout covers every disk at once
+            .push_maybe((view != Some(StorageView::Layout)).then(|| {
+                widget::tab_bar::horizontal(&self.storage_list.model)
+                    .on_activate(|entity| Message::StoragePage(StorageMessage::SelectTab(entity)))
+            }))
+            .push_maybe(match view {
+                Some(StorageView::Layout) => Some(self.layout_view()),
+                Some(StorageView::Usage) => self
+                    .storage_list
+                    .model
                     .active_data::<StorageDevice>()
-                    .map(|storage| {
-                        widget::row()
-                            .spacing(cosmic.space_xxs())
-                            .push(
-                                widget::canvas(crate::widget::graph::LineGraph {
-                                    points: {
-                                        let max = storage
-                                            .history
-                                            .iter()
-                                            .max_by(|a, b| a.partial_cmp(b).unwrap())
-                                            .unwrap()
-                                            .max(1.0);
-                                        tracing::info!("{}", max);
-                                        storage
-                                            .history
-                                            .iter()
-                                            .cloned()
-                                            .map(|val| val / max)
-                                            .collect()
-                                    },
-                                })
-                                .width(iced::Length::Fill)
-                                .height(iced::Length::Fill),
-                            )
-                            .push(
-                                widget::settings::view_column(vec![
-                                    widget::settings::section()
-                                        .title(fl!("storage-info"))
-                                        .add(widget::settings::item(
-                                            fl!("storage-device"),
-                                            storage
-                                                .info
-                                                .device_name
-                                                .clone()
-                                                .apply(widget::text::body),
-                                        ))
-                                        .add(widget::settings::item(
-                                            fl!("storage-type"),
-                                            storage
-                                                .info
-                                                .device_type
-                                                .clone()
-                                                .apply(widget::text::body),
-                                        ))
-                                        .add(widget::settings::item(
-                                            fl!("model"),
-                                            storage.info.model.clone().apply(widget::text::body),
-                                        ))
-                                        .add_maybe(storage.info.serial_number.as_ref().map(
-                                            |serial| {
-                                                widget::settings::item(
-                                                    fl!("disk-serial"),
-                                                    serial.clone().apply(widget::text::body),
-                                                )
-                                            },
-                                        ))
-                                        .add_maybe(storage.info.partition_label.as_ref().map(
-                                            |plabel| {
-                                                widget::settings::item(
-                                                    fl!("part-label"),
-                                                    plabel.clone().apply(widget::text::body),
-                                                )
-                                            },
-                                        ))
-                                        .add(widget::settings::item(
-                                            fl!("fs-type"),
-                                            storage
-                                                .info
-                                                .filesystem_type
-                                                .clone()
-                                                .apply(widget::text::body),
-                                        ))
-                                        .add(widget::settings::item(
-                                            fl!("mount-point"),
-                                            storage
-                                                .info
-                                                .mount_point
-                                                .clone()
-                                                .apply(widget::text::body),
-                                        ))
-                                        .add(widget::settings::item(
-                                            fl!("disk-space"),
-                                            storage
-                                                .info
-                                                .total_space_bytes
-                                                .apply(crate::helpers::get_bytes)
-                                                .apply(widget::text::body),
-                                        ))
-                                        .apply(Element::from),
-                                    widget::settings::section()
-                                        .title(fl!("storage-stats"))
-                                        .add(widget::settings::item(
-                                            fl!("available-space"),
-                                            storage
-                                                .info
-                                                .available_space_bytes
-                                                .apply(crate::helpers::get_bytes)
-                                                .apply(widget::text::body),
-                                        ))
-                                        .add(widget::settings::item(
-                                            fl!("disk-read"),
-                                            format!(
-                                                "{}/s",
-                                                storage
-                                                    .info
-                                                    .read_bytes_per_sec
-                                                    .apply(crate::helpers::get_bytes)
-                                            )
-                                            .apply(widget::text::body),
-                                        ))
-                                        .add(widget::settings::item(
-                                            fl!("disk-write"),
-                                            format!(
-                                                "{}/s",
-                                                storage
-                                                    .info
-                                                    .write_bytes_per_sec
-                                                    .apply(crate::helpers::get_bytes)
-                                            )
-                                            .apply(widget::text::body),
-                                        ))
-                                        .add(widget::settings::item(
-                                            fl!("io-time"),
-                                            format!("{} ms", storage.info.io_time_ms)
-                                                .apply(widget::text::body),
-                                        ))
-                                        .add_maybe(storage.info.temperature_celsius.map(|temp| {
-                                            widget::settings::item(
-                                                fl!("disk-temp"),
-                                                format!(
-                                                    "{}°C",
-                                                    temp.apply(crate::helpers::format_number)
-                                                )
-                                                .apply(widget::text::body),
-                                            )
-                                        }))
-                                        .add_maybe(storage.info.smart_data.as_ref().map(|smart| {
-                                            widget::settings::item(
-                                                fl!("smart-status"),
-                                                smart
-                                                    .health_status
-                                                    .clone()
-                                                    .apply(widget::text::body),
-                                            )
-                                        }))
-                                        .apply(Element::from),
-                                ])
-                                .apply(widget::scrollable),
-                            )
-                            .apply(Element::from)
-                    }),
-            )
+                    .map(|storage| self.usage_view(storage)),
+                Some(StorageView::Health) => self
+                    .storage_list
+                    .model
+                    .active_data::<StorageDevice>()
+                    .map(|storage| self.health_view(storage)),
+                _ => self
+                    .storage_list
+                    .model
+                    .active_data::<StorageDevice>()
+                    .map(|storage| self.activity_view(storage)),
+            })
             .apply(Element::from)
     }
 
     fn subscription(&self) -> Vec<Subscription<Message>> {
-        vec![Subscription::run(|| {
-            stream::channel(1, |mut sender| async move {
-                let mut service = MonitordServiceClient::connect("http://127.0.0.1:50051")
-                    .await
-                    .unwrap();
+        let mut subscriptions = vec![
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let mut service = MonitordServiceClient::connect("http://127.0.0.1:50051")
+                        .await
+                        .unwrap();
 
-                let request = tonic::Request::new(SnapshotRequest { interval_ms: 1000 });
+                    let request = tonic::Request::new(SnapshotRequest { interval_ms: 1000 });
 
-                let mut stream = service
-                    .stream_storage_info(request)
-                    .await
-                    .unwrap()
-                    .into_inner();
+                    let mut stream = service
+                        .stream_storage_info(request)
+                        .await
+                        .unwrap()
+                        .into_inner();
+
+                    loop {
+                        let message = stream.message().await.unwrap();
+
+                        if let Some(message) = message {
+                            sender
+                                .send(Message::StoragePage(StorageMessage::Snapshot(message)))
+                                .await
+                                .unwrap();
+                        }
+                    }
+                })
+            }),
+            // The daemon only reports throughput and total IO time, so request rates, latency and
+            // queue depth are derived from the kernel's counters
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let mut sampler = DiskStatsSampler::default();
 
-                loop {
-                    let message = stream.message().await.unwrap();
+                    loop {
+                        let (returned, stats) = tokio::task::spawn_blocking(move || {
+                            let stats = sampler.sample();
+                            (sampler, stats)
+                        })
+                        .await
+                        .unwrap();
+                        sampler = returned;
 
-                    if let Some(message) = message {
+                        if let Some(stats) = stats {
+                            sender
+                                .send(Message::StoragePage(StorageMessage::IoStats(stats)))
+                                .await
+                                .unwrap();
+                        }
+
+                        tokio::time::sleep(Duration::from_millis(1000)).await;
+                    }
+                })
+            }),
+        ];
+
+        if self.view_list.active_data::<StorageView>() == Some(&StorageView::Layout) {
+            subscriptions.push(Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    loop {
+                        let layout = tokio::task::spawn_blocking(sys::block::read_layout)
+                            .await
+                            .unwrap();
                         sender
-                            .send(Message::StoragePage(StorageMessage::Snapshot(message)))
+                            .send(Message::StoragePage(StorageMessage::Layout(layout)))
                             .await
                             .unwrap();
+
+                        tokio::time::sleep(Duration::from_millis(5000)).await;
                     }
-                }
-            })
-        })]
+                })
+            }));
+        }
+
+        subscriptions
     }
 }
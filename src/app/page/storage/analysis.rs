@@ -0,0 +1,203 @@
+use std::{
+    path::PathBuf,
+    sync::{atomic::Ordering, Arc},
+};
+
+use cosmic::{iced, prelude::*, widget};
+
+use super::StorageMessage;
+use crate::{
+    app::Message,
+    fl,
+    helpers::get_bytes,
+    sys::diskusage::{ScanProgress, UsageNode},
+    widget::treemap::{self, TreemapTile},
+};
+
+/// Entries drawn in the treemap, the rest are too small to make out
+const TILE_LIMIT: usize = 24;
+
+/// A running or finished scan of one mounted filesystem
+pub struct Analysis {
+    /// Tells a scan apart from earlier, cancelled ones of the same filesystem
+    pub id: u64,
+    pub mount_point: String,
+    pub progress: Arc<ScanProgress>,
+    /// Missing while the scan runs
+    pub root: Option<UsageNode>,
+    /// Positions of the directories drilled into, starting from the root
+    pub path: Vec<usize>,
+}
+
+impl Analysis {
+    pub fn new(id: u64, mount_point: String) -> Self {
+        Self {
+            id,
+            mount_point,
+            progress: Arc::new(ScanProgress::default()),
+            root: None,
+            path: Vec::new(),
+        }
+    }
+
+    /// The directory drilled into with its location on disk
+    fn current(&self) -> Option<(&UsageNode, PathBuf)> {
+        let mut node = self.root.as_ref()?;
+        let mut location = PathBuf::from(&self.mount_point);
+        for index in self.path.iter() {
+            node = node.children.get(*index)?;
+            location.push(&node.name);
+        }
+        Some((node, location))
+    }
+
+    /// Names of the root and the directories drilled into
+    fn breadcrumbs(&self) -> Vec<String> {
+        let mut crumbs = vec![self.mount_point.clone()];
+        let mut node = self.root.as_ref();
+        for index in self.path.iter() {
+            node = node.and_then(|node| node.children.get(*index));
+            crumbs.extend(node.map(|node| node.name.clone()));
+        }
+        crumbs
+    }
+
+    pub fn drill_down(&mut self, index: usize) {
+        if self
+            .current()
+            .and_then(|(node, _)| node.children.get(index))
+            .is_some_and(|child| child.is_dir)
+        {
+            self.path.push(index);
+        }
+    }
+
+    /// Goes back up to a level of the breadcrumbs, 0 being the root
+    pub fn drill_up(&mut self, depth: usize) {
+        self.path.truncate(depth);
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        let Some((node, location)) = self.current() else {
+            return widget::row()
+                .spacing(cosmic.space_s())
+                .align_y(iced::Alignment::Center)
+                .push(widget::text::body(fl!(
+                    "analysis-scanning",
+                    entries = self.progress.entries.load(Ordering::Relaxed),
+                    size = get_bytes(self.progress.bytes.load(Ordering::Relaxed))
+                )))
+                .push(widget::horizontal_space())
+                .push(
+                    fl!("analysis-cancel")
+                        .apply(widget::button::standard)
+                        .on_press(Message::StoragePage(StorageMessage::CancelAnalysis)),
+                )
+                .apply(Element::from);
+        };
+
+        let mut crumbs = widget::row()
+            .spacing(cosmic.space_xxxs())
+            .align_y(iced::Alignment::Center);
+        for (depth, crumb) in self.breadcrumbs().into_iter().enumerate() {
+            if depth > 0 {
+                crumbs = crumbs.push(widget::icon::from_name("go-next-symbolic").size(16));
+            }
+            crumbs = crumbs.push(
+                widget::button::text(crumb)
+                    .on_press(Message::StoragePage(StorageMessage::DrillUp(depth))),
+            );
+        }
+
+        let tiles = &node.children[..node.children.len().min(TILE_LIMIT)];
+        let total = tiles.iter().map(|tile| tile.size).sum::<u64>().max(1);
+        // Weights are rounded up so the smallest tiles stay visible
+        let weight = |size: u64| ((size as f64 / total as f64) * 1000.0).ceil().max(1.0) as u16;
+        let sizes = tiles.iter().map(|tile| tile.size).collect::<Vec<_>>();
+        let treemap = treemap::strips(&sizes, 3.0).into_iter().fold(
+            widget::column().spacing(cosmic.space_xxxs()),
+            |rows, strip| {
+                let height = weight(sizes[strip.clone()].iter().sum());
+                let row = strip.fold(widget::row().spacing(cosmic.space_xxxs()), |row, index| {
+                    let tile = &tiles[index];
+                    row.push(
+                        widget::mouse_area(
+                            widget::canvas(TreemapTile {
+                                label: format!("{} ({})", tile.name, get_bytes(tile.size)),
+                                index,
+                            })
+                            .width(iced::Length::FillPortion(weight(tile.size)))
+                            .height(iced::Length::Fill),
+                        )
+                        .on_press(Message::StoragePage(StorageMessage::DrillDown(index))),
+                    )
+                });
+                rows.push(row.height(iced::Length::FillPortion(height)))
+            },
+        );
+
+        let entries = node.children.iter().enumerate().map(|(index, child)| {
+            widget::settings::item(
+                child.name.clone(),
+                widget::row()
+                    .spacing(cosmic.space_xxs())
+                    .align_y(iced::Alignment::Center)
+                    .push(widget::text::body(get_bytes(child.size)))
+                    .push_maybe(child.is_dir.then(|| {
+                        widget::button::icon(widget::icon::from_name("go-next-symbolic"))
+                            .on_press(Message::StoragePage(StorageMessage::DrillDown(index)))
+                    })),
+            )
+        });
+        let (other_count, other_size) = node.other;
+        let denied = self.progress.denied.load(Ordering::Relaxed);
+
+        widget::column()
+            .spacing(cosmic.space_xs())
+            .push(
+                widget::row()
+                    .spacing(cosmic.space_xs())
+                    .align_y(iced::Alignment::Center)
+                    .push(crumbs)
+                    .push(widget::horizontal_space())
+                    .push(widget::text::body(get_bytes(node.size)))
+                    .push(
+                        fl!("analysis-open")
+                            .apply(widget::button::standard)
+                            .on_press(Message::LaunchUrl(location.to_string_lossy().into_owned())),
+                    )
+                    .push(
+                        fl!("analysis-rescan")
+                            .apply(widget::button::standard)
+                            .on_press(Message::StoragePage(StorageMessage::Analyze)),
+                    ),
+            )
+            .push(
+                widget::container(treemap)
+                    .width(iced::Length::Fill)
+                    .height(iced::Length::Fixed(280.0)),
+            )
+            .push(
+                widget::settings::view_column(vec![widget::settings::section()
+                    .title(fl!("analysis-largest"))
+                    .add_maybe(
+                        (denied > 0)
+                            .then(|| widget::text::caption(fl!("analysis-denied", count = denied))),
+                    )
+                    .extend(entries)
+                    .add_maybe((other_count > 0).then(|| {
+                        widget::settings::item(
+                            fl!("analysis-other", count = other_count),
+                            widget::text::body(get_bytes(other_size)),
+                        )
+                    }))
+                    .apply(Element::from)])
+                .apply(widget::scrollable)
+                .height(iced::Length::Fill),
+            )
+            .apply(Element::from)
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    fl,
+    sys::smart::{Severity, SmartAttribute},
+};
+use cosmic::{iced::Length, widget};
+use lazy_static::lazy_static;
+use std::borrow::Cow;
+
+lazy_static! {
+    static ref SMART_ID: String = fl!("smart-id");
+    static ref SMART_ATTRIBUTE: String = fl!("smart-attribute");
+    static ref SMART_VALUE: String = fl!("smart-value");
+    static ref SMART_WORST: String = fl!("smart-worst");
+    static ref SMART_THRESHOLD: String = fl!("smart-threshold");
+    static ref SMART_RAW: String = fl!("smart-raw");
+    static ref SMART_STATE: String = fl!("smart-state");
+    static ref SMART_OK: String = fl!("smart-ok");
+    static ref SMART_WARNING: String = fl!("smart-warning");
+    static ref SMART_FAILING: String = fl!("smart-failing");
+}
+
+pub struct AttributeTableItem {
+    pub attribute: SmartAttribute,
+    id: Cow<'static, str>,
+    value: Cow<'static, str>,
+    worst: Cow<'static, str>,
+    threshold: Cow<'static, str>,
+}
+
+impl AttributeTableItem {
+    pub fn new(attribute: SmartAttribute) -> Self {
+        // NVMe health entries have no normalized values, only raw ones
+        let optional = |value: Option<u8>| -> Cow<'static, str> {
+            match value {
+                Some(value) => value.to_string().into(),
+                None => "-".into(),
+            }
+        };
+
+        Self {
+            id: optional(attribute.id),
+            value: optional(attribute.value),
+            worst: optional(attribute.worst),
+            threshold: optional(attribute.threshold),
+            attribute,
+        }
+    }
+}
+
+impl widget::table::ItemInterface<AttributeTableCategory> for AttributeTableItem {
+    fn get_icon(&self, category: AttributeTableCategory) -> Option<widget::Icon> {
+        match (category, self.attribute.severity) {
+            (AttributeTableCategory::State, Severity::Warning) => {
+                Some(widget::icon::from_name("dialog-warning-symbolic").icon())
+            }
+            (AttributeTableCategory::State, Severity::Failing) => {
+                Some(widget::icon::from_name("dialog-error-symbolic").icon())
+            }
+            _ => None,
+        }
+    }
+
+    fn get_text(&self, category: AttributeTableCategory) -> Cow<'static, str> {
+        match category {
+            AttributeTableCategory::Id => self.id.clone(),
+            AttributeTableCategory::Attribute => self.attribute.name.clone().into(),
+            AttributeTableCategory::Value => self.value.clone(),
+            AttributeTableCategory::Worst => self.worst.clone(),
+            AttributeTableCategory::Threshold => self.threshold.clone(),
+            AttributeTableCategory::Raw => self.attribute.raw.clone().into(),
+            AttributeTableCategory::State => match self.attribute.severity {
+                Severity::Ok => SMART_OK.as_str().into(),
+                Severity::Warning => SMART_WARNING.as_str().into(),
+                Severity::Failing => SMART_FAILING.as_str().into(),
+            },
+        }
+    }
+
+    fn compare(&self, other: &Self, category: AttributeTableCategory) -> std::cmp::Ordering {
+        match category {
+            AttributeTableCategory::Id => self.attribute.id.cmp(&other.attribute.id),
+            AttributeTableCategory::Attribute => self.attribute.name.cmp(&other.attribute.name),
+            AttributeTableCategory::Value => self.attribute.value.cmp(&other.attribute.value),
+            AttributeTableCategory::Worst => self.attribute.worst.cmp(&other.attribute.worst),
+            AttributeTableCategory::Threshold => {
+                self.attribute.threshold.cmp(&other.attribute.threshold)
+            }
+            AttributeTableCategory::Raw => self.attribute.raw.cmp(&other.attribute.raw),
+            AttributeTableCategory::State => self.attribute.severity.cmp(&other.attribute.severity),
+        }
+    }
+}
+
+#[derive(Default, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum AttributeTableCategory {
+    #[default]
+    Id,
+    Attribute,
+    Value,
+    Worst,
+    Threshold,
+    Raw,
+    State,
+}
+
+impl std::fmt::Display for AttributeTableCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Id => SMART_ID.as_str(),
+                Self::Attribute => SMART_ATTRIBUTE.as_str(),
+                Self::Value => SMART_VALUE.as_str(),
+                Self::Worst => SMART_WORST.as_str(),
+                Self::Threshold => SMART_THRESHOLD.as_str(),
+                Self::Raw => SMART_RAW.as_str(),
+                Self::State => SMART_STATE.as_str(),
+            }
+        )
+    }
+}
+
+impl widget::table::ItemCategory for AttributeTableCategory {
+    fn width(&self) -> cosmic::iced::Length {
+        match self {
+            Self::Id => Length::Fixed(60.0),
+            Self::Attribute => Length::Fixed(280.0),
+            Self::Value => Length::Fixed(80.0),
+            Self::Worst => Length::Fixed(80.0),
+            Self::Threshold => Length::Fixed(100.0),
+            Self::Raw => Length::Fixed(200.0),
+            Self::State => Length::Fixed(120.0),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::VecDeque;
+
+use crate::{fl, sys::diskstats::IoStats};
+
+/// A storage statistic that is kept as a history and can be graphed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageMetric {
+    Throughput,
+    Iops,
+    Latency,
+    QueueDepth,
+    Utilization,
+}
+
+impl StorageMetric {
+    pub const ALL: [StorageMetric; 5] = [
+        Self::Throughput,
+        Self::Iops,
+        Self::Latency,
+        Self::QueueDepth,
+        Self::Utilization,
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Throughput => fl!("disk-throughput"),
+            Self::Iops => fl!("disk-iops"),
+            Self::Latency => fl!("disk-latency"),
+            Self::QueueDepth => fl!("disk-queue-depth"),
+            Self::Utilization => fl!("disk-utilization"),
+        }
+    }
+
+    /// Reads this metric from the extended statistics, throughput comes from the daemon instead
+    pub fn sample(self, stats: &IoStats) -> Option<f32> {
+        match self {
+            Self::Throughput => None,
+            Self::Iops => Some(stats.iops()),
+            Self::Latency => Some(stats.latency_ms()),
+            Self::QueueDepth => Some(stats.queue_depth),
+            Self::Utilization => Some(stats.utilization),
+        }
+    }
+
+    /// Scales a history to the graph's range of 0 to 1. Utilization uses a fixed scale, and
+    /// everything else is relative to the highest value seen.
+    pub fn normalize(self, history: &VecDeque<f32>) -> Vec<f32> {
+        let max = history
+            .iter()
+            .cloned()
+            .max_by(|a, b| a.partial_cmp(b).unwrap())
+            .unwrap_or_default();
+        let scale = match self {
+            Self::Utilization => 100.0,
+            Self::Throughput | Self::Iops | Self::Latency | Self::QueueDepth => max.max(1.0),
+        };
+        history.iter().map(|value| value / scale).collect()
+    }
+}
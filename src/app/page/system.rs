@@ -1,6 +1,19 @@
-use crate::{app::Message, fl};
+use std::time::Duration;
+
+use super::pressure::{format_averages, PressureHistory};
+use crate::{
+    app::Message,
+    config::Config,
+    fl,
+    helpers::format_number,
+    sys::{
+        boot::{self, BootTimes},
+        pressure::{self, CgroupPressure, Pressure},
+        sessions::{self, Session},
+    },
+};
 use cosmic::{
-    iced::{stream, Subscription},
+    iced::{self, stream, Subscription},
     prelude::*,
     widget,
 };
@@ -14,15 +27,191 @@ use monitord_protocols::{
 #[derive(Debug, Clone)]
 pub enum SystemMessage {
     Snapshot(SystemInfo),
+    Cgroups(Vec<CgroupPressure>),
+    Uptime(Option<Duration>),
+    /// Missing when the service manager does not record boot timestamps
+    Boot(Option<BootTimes>),
+    Sessions(Vec<Session>),
 }
 
 pub struct SystemPage {
     system_info: Option<SystemInfo>,
+    cpu_pressure: PressureHistory,
+    memory_pressure: PressureHistory,
+    io_pressure: PressureHistory,
+    cgroups: Vec<CgroupPressure>,
+    uptime: Option<Duration>,
+    boot: Option<BootTimes>,
+    sessions: Vec<Session>,
+    cmdline: Option<String>,
+    // Configuration data that persists between application runs.
+    config: Config,
 }
 
+/// Cgroups beyond this many are left out of the overview
+const CGROUP_LIMIT: usize = 10;
+
 impl SystemPage {
-    pub fn new() -> Self {
-        Self { system_info: None }
+    pub fn new(config: Config) -> Self {
+        Self {
+            system_info: None,
+            cpu_pressure: PressureHistory::new(),
+            memory_pressure: PressureHistory::new(),
+            io_pressure: PressureHistory::new(),
+            cgroups: Vec::new(),
+            uptime: None,
+            boot: None,
+            sessions: Vec::new(),
+            // The command line cannot change until the next boot
+            cmdline: boot::cmdline(),
+            config,
+        }
+    }
+
+    /// Uptime, and how long each phase of the last boot took
+    fn boot_section(&self) -> Element<Message> {
+        let phases = self
+            .boot
+            .iter()
+            .flat_map(|boot| {
+                [
+                    (fl!("boot-firmware"), boot.firmware),
+                    (fl!("boot-loader"), boot.loader),
+                    (fl!("boot-kernel"), boot.kernel),
+                    (fl!("boot-initrd"), boot.initrd),
+                    (fl!("boot-userspace"), boot.userspace),
+                ]
+            })
+            .filter_map(|(label, phase)| {
+                Some(widget::settings::item(
+                    label,
+                    widget::text::body(format_seconds(phase?)),
+                ))
+            });
+
+        widget::settings::section()
+            .title(fl!("boot-info"))
+            .add_maybe(self.uptime.map(|uptime| {
+                widget::settings::item(fl!("uptime"), widget::text::body(format_duration(uptime)))
+            }))
+            .add_maybe(
+                self.boot
+                    .as_ref()
+                    .and_then(|boot| boot.booted_at.clone())
+                    .map(|booted_at| {
+                        widget::settings::item(fl!("boot-time"), widget::text::body(booted_at))
+                    }),
+            )
+            .add_maybe(self.boot.as_ref().map(|boot| {
+                widget::settings::item(
+                    fl!("boot-duration"),
+                    widget::text::body(match boot.userspace {
+                        Some(_) => format_seconds(boot.total()),
+                        None => fl!("boot-unfinished"),
+                    }),
+                )
+            }))
+            .extend(phases)
+            .apply(Element::from)
+    }
+
+    /// Logged in users with where they are sitting and how long they have been away
+    fn session_section(&self) -> Option<Element<Message>> {
+        if self.sessions.is_empty() {
+            return None;
+        }
+
+        self.sessions
+            .iter()
+            .fold(
+                widget::settings::section().title(fl!("sessions")),
+                |section, session| {
+                    let mut place = [session.seat.clone(), session.tty.clone()]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>();
+                    place.extend((!session.kind.is_empty()).then(|| session.kind.clone()));
+                    place.extend(
+                        session
+                            .remote_host
+                            .clone()
+                            .map(|host| fl!("session-from", host = host)),
+                    );
+
+                    section.add(
+                        widget::settings::item::builder(session.user.clone())
+                            .description(place.join(" · "))
+                            .control(widget::text::body(match session.idle {
+                                Some(idle) => {
+                                    fl!("session-idle", duration = format_duration(idle))
+                                }
+                                None => session.state.clone(),
+                            })),
+                    )
+                },
+            )
+            .apply(Element::from)
+            .apply(Some)
+    }
+
+    /// CPU, memory and IO pressure side by side
+    fn pressure_section(&self) -> Element<Message> {
+        let theme = cosmic::theme::active();
+        let cosmic = theme.cosmic();
+
+        let graphs = [
+            (fl!("cpu"), &self.cpu_pressure),
+            (fl!("memory"), &self.memory_pressure),
+            (fl!("io"), &self.io_pressure),
+        ]
+        .into_iter()
+        .map(|(label, history)| {
+            widget::column()
+                .spacing(cosmic.space_xxxs())
+                .width(iced::Length::Fill)
+                .push(widget::text::caption(label))
+                .push(widget::container(history.graph()).height(iced::Length::Fixed(100.0)))
+                .apply(Element::from)
+        })
+        .collect();
+
+        widget::settings::section()
+            .title(fl!("pressure"))
+            .add(widget::row::with_children(graphs).spacing(cosmic.space_xs()))
+            .apply(Element::from)
+    }
+
+    /// The cgroups under the most pressure, by their worst resource over the last 10 seconds
+    fn cgroup_section(&self) -> Option<Element<Message>> {
+        if !self.config.cgroup_pressure || self.cgroups.is_empty() {
+            return None;
+        }
+
+        let some = |pressure: Option<Pressure>| {
+            pressure
+                .map(|pressure| format_averages(&pressure.some))
+                .unwrap_or_else(|| fl!("not-supported"))
+        };
+        self.cgroups
+            .iter()
+            .take(CGROUP_LIMIT)
+            .fold(
+                widget::settings::section().title(fl!("cgroup-pressure")),
+                |section, cgroup| {
+                    section.add(
+                        widget::settings::item::builder(cgroup.path.clone())
+                            .description(fl!(
+                                "cgroup-pressure-detail",
+                                cpu = some(cgroup.pressure.cpu),
+                                memory = some(cgroup.pressure.memory),
+                                io = some(cgroup.pressure.io)
+                            ))
+                            .control(widget::horizontal_space()),
+                    )
+                },
+            )
+            .apply(Element::from)
+            .apply(Some)
     }
 }
 
@@ -30,9 +219,33 @@ impl super::Page for SystemPage {
     fn update(&mut self, msg: crate::app::Message) -> cosmic::app::Task<crate::app::Message> {
         let tasks = Vec::new();
         match msg {
+            Message::UpdateConfig(config) => self.config = config,
             Message::SystemPage(SystemMessage::Snapshot(snapshot)) => {
                 self.system_info = Some(snapshot);
             }
+            Message::Pressure(pressure) => {
+                self.cpu_pressure.push(pressure.cpu);
+                self.memory_pressure.push(pressure.memory);
+                self.io_pressure.push(pressure.io);
+            }
+            Message::SystemPage(SystemMessage::Cgroups(mut cgroups)) => {
+                let worst = |cgroup: &CgroupPressure| {
+                    [
+                        cgroup.pressure.cpu,
+                        cgroup.pressure.memory,
+                        cgroup.pressure.io,
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .map(|pressure| pressure.some.avg10)
+                    .fold(0.0, f32::max)
+                };
+                cgroups.sort_by(|a, b| worst(b).total_cmp(&worst(a)));
+                self.cgroups = cgroups;
+            }
+            Message::SystemPage(SystemMessage::Uptime(uptime)) => self.uptime = uptime,
+            Message::SystemPage(SystemMessage::Boot(boot)) => self.boot = boot,
+            Message::SystemPage(SystemMessage::Sessions(sessions)) => self.sessions = sessions,
             _ => {}
         }
 
@@ -88,6 +301,21 @@ impl super::Page for SystemPage {
                             .apply(widget::text::body),
                     ))
                     .apply(Element::from),
+                self.boot_section(),
+                self.session_section()
+                    .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
+                self.cmdline
+                    .clone()
+                    .map(|cmdline| {
+                        widget::settings::section()
+                            .title(fl!("kernel-cmdline"))
+                            .add(widget::text::body(cmdline))
+                            .apply(Element::from)
+                    })
+                    .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
+                self.pressure_section(),
+                self.cgroup_section()
+                    .unwrap_or_else(|| widget::horizontal_space().apply(Element::from)),
             ])
             .apply(widget::scrollable)
             .apply(Element::from)
@@ -97,31 +325,138 @@ impl super::Page for SystemPage {
     }
 
     fn subscription(&self) -> Vec<Subscription<Message>> {
-        vec![Subscription::run(|| {
-            stream::channel(1, |mut sender| async move {
-                let mut service = MonitordServiceClient::connect("http://127.0.0.1:50051")
-                    .await
-                    .unwrap();
+        let mut subscriptions = vec![
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let mut service = MonitordServiceClient::connect("http://127.0.0.1:50051")
+                        .await
+                        .unwrap();
 
-                let request = tonic::Request::new(SnapshotRequest { interval_ms: 1000 });
+                    let request = tonic::Request::new(SnapshotRequest { interval_ms: 1000 });
 
-                let mut stream = service
-                    .stream_system_info(request)
-                    .await
-                    .unwrap()
-                    .into_inner();
+                    let mut stream = service
+                        .stream_system_info(request)
+                        .await
+                        .unwrap()
+                        .into_inner();
 
-                loop {
-                    let message = stream.message().await.unwrap();
+                    loop {
+                        let message = stream.message().await.unwrap();
 
-                    if let Some(message) = message {
+                        if let Some(message) = message {
+                            sender
+                                .send(Message::SystemPage(SystemMessage::Snapshot(message)))
+                                .await
+                                .unwrap();
+                        }
+                    }
+                })
+            }),
+            // PSI is not reported by the daemon, every page showing pressure shares this sample
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    loop {
                         sender
-                            .send(Message::SystemPage(SystemMessage::Snapshot(message)))
+                            .send(Message::Pressure(pressure::read()))
                             .await
                             .unwrap();
+
+                        tokio::time::sleep(Duration::from_millis(1000)).await;
                     }
-                }
-            })
-        })]
+                })
+            }),
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    loop {
+                        sender
+                            .send(Message::SystemPage(SystemMessage::Uptime(boot::uptime())))
+                            .await
+                            .unwrap();
+
+                        tokio::time::sleep(Duration::from_millis(1000)).await;
+                    }
+                })
+            }),
+            Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    let connection = zbus::Connection::system().await.ok();
+
+                    // Boot timestamps are fixed once userspace has finished starting up, and
+                    // without systemd they will not turn up later either
+                    let mut pending = true;
+                    loop {
+                        if pending {
+                            let times = match &connection {
+                                Some(connection) => boot::boot_times(connection).await.ok(),
+                                None => None,
+                            };
+                            pending = times
+                                .as_ref()
+                                .is_some_and(|times| times.userspace.is_none());
+                            sender
+                                .send(Message::SystemPage(SystemMessage::Boot(times)))
+                                .await
+                                .unwrap();
+                        }
+
+                        // Without logind there are no sessions to show
+                        let sessions = match &connection {
+                            Some(connection) => {
+                                sessions::sessions(connection).await.unwrap_or_default()
+                            }
+                            None => Vec::new(),
+                        };
+                        sender
+                            .send(Message::SystemPage(SystemMessage::Sessions(sessions)))
+                            .await
+                            .unwrap();
+
+                        tokio::time::sleep(Duration::from_millis(5000)).await;
+                    }
+                })
+            }),
+        ];
+
+        // Walking the cgroup tree is only worth it while the cgroups are shown
+        if self.config.cgroup_pressure {
+            subscriptions.push(Subscription::run(|| {
+                stream::channel(1, |mut sender| async move {
+                    loop {
+                        let cgroups = tokio::task::spawn_blocking(pressure::read_cgroups)
+                            .await
+                            .unwrap();
+                        sender
+                            .send(Message::SystemPage(SystemMessage::Cgroups(cgroups)))
+                            .await
+                            .unwrap();
+
+                        tokio::time::sleep(Duration::from_millis(1000)).await;
+                    }
+                })
+            }));
+        }
+
+        subscriptions
     }
 }
+
+/// Durations long enough for days, e.g. uptime or idle time
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (days, hours, minutes) = (seconds / 86_400, seconds / 3600 % 24, seconds / 60 % 60);
+    if days > 0 {
+        format!("{days}d {hours}h {minutes}m")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m {}s", seconds % 60)
+    }
+}
+
+/// Boot phases, which take seconds rather than hours
+fn format_seconds(duration: Duration) -> String {
+    format!(
+        "{} s",
+        format_number((duration.as_secs_f64() * 100.0).round() / 100.0)
+    )
+}
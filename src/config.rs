@@ -1,10 +1,61 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use std::collections::{BTreeMap, HashMap};
+
 use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
 
-#[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
+#[derive(Debug, Clone, CosmicConfigEntry, Eq, PartialEq)]
 #[version = 1]
 pub struct Config {
     pub scale_by_core: bool,
     pub multicore_view: bool,
+    pub cgroup_pressure: bool,
+    /// Day of the month metered plans restart counting on, from 1 to 28
+    pub billing_day: u8,
+    /// Data allowance per billing cycle in bytes, keyed by interface name
+    pub data_quotas: HashMap<String, u64>,
+    pub hide_loopback: bool,
+    /// Hides bridges, veth pairs, tunnels and other software interfaces
+    pub hide_virtual: bool,
+    /// Lists virtual interfaces attached to a bridge with the bridge instead of in their own tab
+    pub group_virtual: bool,
+    /// Interface names pinned to the front of the network page, in order
+    pub favorite_interfaces: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            scale_by_core: false,
+            multicore_view: false,
+            cgroup_pressure: false,
+            billing_day: 1,
+            data_quotas: HashMap::new(),
+            hide_loopback: false,
+            hide_virtual: false,
+            group_virtual: false,
+            favorite_interfaces: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Brings values edited by hand back into range, so they are only checked when loaded
+    pub fn sanitized(mut self) -> Self {
+        self.billing_day = self.billing_day.clamp(1, 28);
+        self
+    }
+}
+
+/// Network usage counted across runs, kept in the state directory rather than with the settings
+#[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
+#[version = 1]
+pub struct UsageState {
+    /// Received and sent bytes per interface and day, counted in days since the Unix epoch
+    pub daily: HashMap<String, BTreeMap<i64, (u64, u64)>>,
+    /// Received and sent bytes per interface and month, counted in months since January 1970
+    pub monthly: HashMap<String, BTreeMap<i64, (u64, u64)>>,
+    /// Interface totals at the last sample together with the boot they were read in, so traffic
+    /// while the app was closed is still counted
+    pub baseline: HashMap<String, (String, u64, u64)>,
 }
@@ -0,0 +1,439 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use super::read_trimmed;
+
+/// Builds an identifier for a block device from its disk's WWN or serial number and its
+/// partition number, so it survives the kernel handing out a different device name
+pub fn stable_id(device_name: &str, serial: Option<&str>) -> Option<String> {
+    let name = device_name.trim_start_matches("/dev/");
+    let path = fs::canonicalize(Path::new("/sys/class/block").join(name)).ok()?;
+    let partition = read_trimmed(path.join("partition"));
+    let disk = if partition.is_some() {
+        path.parent()?.to_path_buf()
+    } else {
+        path
+    };
+
+    let disk_id = read_trimmed(disk.join("wwid"))
+        .or_else(|| read_trimmed(disk.join("device/wwid")))
+        .or_else(|| {
+            serial
+                .filter(|serial| !serial.is_empty())
+                .map(str::to_owned)
+        })?;
+
+    Some(match partition {
+        Some(partition) => format!("{disk_id}-part{partition}"),
+        None => disk_id,
+    })
+}
+
+/// The whole disk a partition belongs to, e.g. `/dev/nvme0n1` for `/dev/nvme0n1p2`. Other
+/// devices are returned as they are.
+pub fn disk_of(device_name: &str) -> String {
+    let name = device_name.trim_start_matches("/dev/");
+    fs::canonicalize(Path::new("/sys/class/block").join(name))
+        .ok()
+        .filter(|path| path.join("partition").exists())
+        .and_then(|path| {
+            path.parent()?
+                .file_name()?
+                .to_str()
+                .map(|disk| format!("/dev/{disk}"))
+        })
+        .unwrap_or_else(|| format!("/dev/{name}"))
+}
+
+/// Whether the disk holding a device can be unplugged, like USB drives and SD cards
+pub fn is_removable(device_name: &str) -> bool {
+    let disk = disk_of(device_name);
+    let Some(path) = sysfs_path(disk.trim_start_matches("/dev/")) else {
+        return false;
+    };
+    read_trimmed(path.join("removable")).as_deref() == Some("1")
+        || path.to_string_lossy().contains("/usb")
+}
+
+/// The kernel name of a device, e.g. `dm-0` for `/dev/mapper/root`
+pub fn kernel_name(device_name: &str) -> String {
+    fs::canonicalize(Path::new("/dev").join(device_name.trim_start_matches("/dev/")))
+        .ok()
+        .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| device_name.trim_start_matches("/dev/").to_owned())
+}
+
+/// The role a block device plays in the storage stack
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockKind {
+    Disk,
+    Partition,
+    /// A dm-crypt mapping, `luks` tells LUKS apart from plain dm-crypt
+    Crypt {
+        luks: bool,
+    },
+    /// Groups the logical volumes a physical volume holds
+    VolumeGroup,
+    LogicalVolume,
+    Raid(RaidStatus),
+    /// Other device-mapper targets such as multipath or snapshots
+    Mapper,
+}
+
+/// State of an mdraid array
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaidStatus {
+    /// RAID level, e.g. `raid1`
+    pub level: String,
+    /// Running resync, recovery, check or reshape with its progress in percent
+    pub sync: Option<(String, f32)>,
+    /// Number of members missing from the array
+    pub degraded: u32,
+}
+
+/// A block device together with the devices built on top of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockNode {
+    /// Kernel name such as `sda1` or `dm-0`, or the volume group name
+    pub name: String,
+    /// Name users know the device by, e.g. `/dev/mapper/root` for `dm-0`
+    pub label: String,
+    pub kind: BlockKind,
+    pub size_bytes: u64,
+    pub children: Vec<BlockNode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolKind {
+    Btrfs,
+    Zfs,
+}
+
+/// A filesystem that manages its own devices
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pool {
+    pub name: String,
+    pub kind: PoolKind,
+    pub size_bytes: u64,
+    pub used_bytes: u64,
+    /// Health as reported by ZFS, e.g. `ONLINE` or `DEGRADED`
+    pub health: Option<String>,
+    /// Kernel names of the member devices
+    pub members: Vec<String>,
+}
+
+/// How physical disks are divided and combined
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Layout {
+    pub disks: Vec<BlockNode>,
+    pub pools: Vec<Pool>,
+}
+
+/// Devices stacked deeper than this are not followed, which guards against cycles
+const MAX_DEPTH: usize = 8;
+
+/// Reads the device tree from sysfs in the way `lsblk` shows it. Devices built from several
+/// others, like RAID arrays or logical volumes spanning disks, appear under each of them.
+pub fn read_layout() -> Layout {
+    let mut disks = fs::read_dir("/sys/block")
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                // Loop devices and RAM disks hold files rather than storage
+                .filter(|name| !["loop", "ram", "zram"].iter().any(|p| name.starts_with(p)))
+                // Devices built on others are shown under them
+                .filter(|name| is_empty_dir(&Path::new("/sys/block").join(name).join("slaves")))
+                .filter_map(|name| read_node(&name, 0))
+                .filter(|node| node.size_bytes > 0)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    disks.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut pools = read_btrfs_pools();
+    pools.extend(read_zfs_pools());
+
+    Layout { disks, pools }
+}
+
+fn read_node(name: &str, depth: usize) -> Option<BlockNode> {
+    let path = sysfs_path(name)?;
+    let size_bytes = read_trimmed(path.join("size"))?.parse::<u64>().ok()? * 512;
+    let dm_name = read_trimmed(path.join("dm/name"));
+    let dm_uuid = read_trimmed(path.join("dm/uuid")).unwrap_or_default();
+
+    let kind = if path.join("partition").exists() {
+        BlockKind::Partition
+    } else if let Some(level) = read_trimmed(path.join("md/level")) {
+        BlockKind::Raid(read_raid_status(&path, level))
+    } else if dm_uuid.starts_with("CRYPT-") {
+        BlockKind::Crypt {
+            luks: dm_uuid.starts_with("CRYPT-LUKS"),
+        }
+    } else if dm_uuid.starts_with("LVM-") {
+        BlockKind::LogicalVolume
+    } else if dm_name.is_some() {
+        BlockKind::Mapper
+    } else {
+        BlockKind::Disk
+    };
+    let label = match &dm_name {
+        Some(dm_name) => format!("/dev/mapper/{dm_name}"),
+        None => format!("/dev/{name}"),
+    };
+
+    let mut children = Vec::new();
+    if depth < MAX_DEPTH {
+        // Partitions are subdirectories of their disk
+        let mut partitions = list_dir(&path)
+            .into_iter()
+            .filter(|child| path.join(child).join("partition").exists())
+            .filter_map(|child| read_node(&child, depth + 1))
+            .collect::<Vec<_>>();
+        partitions.sort_by_key(|partition| partition_number(&partition.name));
+        children.extend(partitions);
+
+        let mut volume_groups: Vec<BlockNode> = Vec::new();
+        for holder in list_dir(&path.join("holders")) {
+            let Some(node) = read_node(&holder, depth + 1) else {
+                continue;
+            };
+            if node.kind != BlockKind::LogicalVolume {
+                children.push(node);
+                continue;
+            }
+            // Logical volumes are listed under the volume group they belong to
+            let group = volume_group(&node.label);
+            match volume_groups.iter_mut().find(|vg| vg.name == group) {
+                Some(vg) => vg.children.push(node),
+                None => volume_groups.push(BlockNode {
+                    label: group.clone(),
+                    name: group,
+                    kind: BlockKind::VolumeGroup,
+                    // Only the share of the group on this physical volume
+                    size_bytes,
+                    children: vec![node],
+                }),
+            }
+        }
+        children.extend(volume_groups);
+    }
+
+    Some(BlockNode {
+        name: name.to_owned(),
+        label,
+        kind,
+        size_bytes,
+        children,
+    })
+}
+
+fn read_raid_status(path: &Path, level: String) -> RaidStatus {
+    let md = path.join("md");
+    // `sync_completed` reads `none` when idle and `done / total` in sectors otherwise
+    let sync = read_trimmed(md.join("sync_action"))
+        .filter(|action| action != "idle")
+        .and_then(|action| {
+            let completed = read_trimmed(md.join("sync_completed"))?;
+            let (done, total) = completed.split_once('/')?;
+            let done = done.trim().parse::<f32>().ok()?;
+            let total = total.trim().parse::<f32>().ok()?.max(1.0);
+            Some((action, done / total * 100.0))
+        });
+
+    RaidStatus {
+        level,
+        sync,
+        degraded: read_trimmed(md.join("degraded"))
+            .and_then(|degraded| degraded.parse().ok())
+            .unwrap_or_default(),
+    }
+}
+
+/// Logical volumes are mapped as `vg-lv`, with dashes inside either name doubled
+fn volume_group(mapper_path: &str) -> String {
+    let name = mapper_path.trim_start_matches("/dev/mapper/");
+    let bytes = name.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'-' {
+            if bytes.get(index + 1) == Some(&b'-') {
+                index += 2;
+                continue;
+            }
+            break;
+        }
+        index += 1;
+    }
+    name[..index].replace("--", "-")
+}
+
+/// Btrfs filesystems, which list their member devices under `/sys/fs/btrfs`
+fn read_btrfs_pools() -> Vec<Pool> {
+    list_dir(Path::new("/sys/fs/btrfs"))
+        .into_iter()
+        .filter(|fsid| fsid != "features")
+        .filter_map(|fsid| {
+            let path = Path::new("/sys/fs/btrfs").join(&fsid);
+            let members = list_dir(&path.join("devices"));
+            if members.is_empty() {
+                return None;
+            }
+            let size_bytes = members
+                .iter()
+                .filter_map(|member| read_trimmed(sysfs_path(member)?.join("size")))
+                .filter_map(|size| size.parse::<u64>().ok())
+                .sum::<u64>()
+                * 512;
+            // Space taken on the devices, counting every copy kept by the RAID profile
+            let used_bytes = ["data", "metadata", "system"]
+                .iter()
+                .filter_map(|group| {
+                    read_trimmed(path.join("allocation").join(group).join("disk_used"))
+                })
+                .filter_map(|used| used.parse::<u64>().ok())
+                .sum();
+
+            Some(Pool {
+                name: read_trimmed(path.join("label")).unwrap_or(fsid),
+                kind: PoolKind::Btrfs,
+                size_bytes,
+                used_bytes,
+                health: None,
+                members,
+            })
+        })
+        .collect()
+}
+
+/// ZFS pools as listed by `zpool`, which keeps its layout out of sysfs
+fn read_zfs_pools() -> Vec<Pool> {
+    let Ok(output) = Command::new("zpool")
+        .args(["list", "-Hpv", "-P", "-o", "name,size,allocated,health"])
+        .output()
+    else {
+        return Vec::new();
+    };
+
+    parse_zpool_list(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Pools are followed by their vdevs, indented with a tab, e.g.
+/// `tank\t1992864825344\t1073741824\tONLINE` then `\t/dev/sda1\t...`. Cache, log and spare
+/// devices follow an unindented header such as `cache\t-\t-\t-`, which has no size.
+fn parse_zpool_list(output: &str) -> Vec<Pool> {
+    let mut pools: Vec<Pool> = Vec::new();
+    for line in output.lines().filter(|line| !line.trim().is_empty()) {
+        let fields = line.split('\t').map(str::trim).collect::<Vec<_>>();
+        let pool_size = (!line.starts_with('\t'))
+            .then(|| fields.get(1)?.parse::<u64>().ok())
+            .flatten();
+        if let Some(size_bytes) = pool_size {
+            pools.push(Pool {
+                name: fields[0].to_owned(),
+                kind: PoolKind::Zfs,
+                size_bytes,
+                used_bytes: fields
+                    .get(2)
+                    .and_then(|used| used.parse().ok())
+                    .unwrap_or_default(),
+                health: fields.get(3).map(|health| health.to_string()),
+                members: Vec::new(),
+            });
+        } else if let (Some(pool), Some(device)) = (
+            pools.last_mut(),
+            fields.iter().find(|field| field.starts_with("/dev/")),
+        ) {
+            // Devices are often given by ID, resolve them to the kernel name
+            let device = fs::canonicalize(device).unwrap_or_else(|_| PathBuf::from(device));
+            if let Some(name) = device.file_name() {
+                pool.members.push(name.to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    pools
+}
+
+fn sysfs_path(name: &str) -> Option<PathBuf> {
+    fs::canonicalize(Path::new("/sys/class/block").join(name)).ok()
+}
+
+fn partition_number(name: &str) -> u32 {
+    read_trimmed(Path::new("/sys/class/block").join(name).join("partition"))
+        .and_then(|partition| partition.parse().ok())
+        .unwrap_or_default()
+}
+
+fn list_dir(path: &Path) -> Vec<String> {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_empty_dir(path: &Path) -> bool {
+    fs::read_dir(path).map_or(true, |mut entries| entries.next().is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zpool_list() {
+        // `zpool list -Hpv -P -o name,size,allocated,health` with a mirrored pool that has a
+        // cache device, and a degraded stripe
+        let output = "\
+tank\t3985729650688\t1099511627776\tONLINE
+\tmirror-0\t1992864825344\t549755813888\tONLINE
+\t/dev/sda1\t-\t-\tONLINE
+\t/dev/sdb1\t-\t-\tONLINE
+\tmirror-1\t1992864825344\t549755813888\tONLINE
+\t/dev/sdc1\t-\t-\tONLINE
+\t/dev/sdd1\t-\t-\tONLINE
+cache\t-\t-\t-
+\t/dev/nvme0n1p4\t-\t-\tONLINE
+scratch\t498216206336\t0\tDEGRADED
+\t/dev/nvme0n1p3\t-\t-\tONLINE
+\t/dev/nvme1n1p3\t-\t-\tFAULTED
+
+";
+
+        assert_eq!(
+            parse_zpool_list(output),
+            vec![
+                Pool {
+                    name: "tank".to_owned(),
+                    kind: PoolKind::Zfs,
+                    size_bytes: 3985729650688,
+                    used_bytes: 1099511627776,
+                    health: Some("ONLINE".to_owned()),
+                    members: vec![
+                        "sda1".to_owned(),
+                        "sdb1".to_owned(),
+                        "sdc1".to_owned(),
+                        "sdd1".to_owned(),
+                        "nvme0n1p4".to_owned(),
+                    ],
+                },
+                Pool {
+                    name: "scratch".to_owned(),
+                    kind: PoolKind::Zfs,
+                    size_bytes: 498216206336,
+                    used_bytes: 0,
+                    health: Some("DEGRADED".to_owned()),
+                    members: vec!["nvme0n1p3".to_owned(), "nvme1n1p3".to_owned()],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use std::{fs, time::Duration};
+
+use chrono::{DateTime, Local};
+
+use super::read_trimmed;
+
+/// How long the last boot spent in each phase, as `systemd-analyze time` reports it. Phases
+/// the firmware or boot loader did not record are missing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BootTimes {
+    /// When the kernel started, in local time
+    pub booted_at: Option<String>,
+    pub firmware: Option<Duration>,
+    pub loader: Option<Duration>,
+    pub kernel: Option<Duration>,
+    pub initrd: Option<Duration>,
+    /// Missing while the boot has not finished
+    pub userspace: Option<Duration>,
+}
+
+impl BootTimes {
+    pub fn total(&self) -> Duration {
+        [
+            self.firmware,
+            self.loader,
+            self.kernel,
+            self.initrd,
+            self.userspace,
+        ]
+        .into_iter()
+        .flatten()
+        .sum()
+    }
+}
+
+/// Time since boot, including time spent suspended
+pub fn uptime() -> Option<Duration> {
+    let uptime = fs::read_to_string("/proc/uptime").ok()?;
+    let seconds = uptime.split_whitespace().next()?.parse::<f64>().ok()?;
+    Some(Duration::from_secs_f64(seconds))
+}
+
+pub fn cmdline() -> Option<String> {
+    read_trimmed("/proc/cmdline")
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1"
+)]
+trait SystemdManager {
+    /// Wall clock time the kernel started, in microseconds since the epoch
+    #[zbus(property)]
+    fn kernel_timestamp(&self) -> zbus::Result<u64>;
+
+    #[zbus(property)]
+    fn firmware_timestamp_monotonic(&self) -> zbus::Result<u64>;
+
+    #[zbus(property)]
+    fn loader_timestamp_monotonic(&self) -> zbus::Result<u64>;
+
+    #[zbus(property, name = "InitRDTimestampMonotonic")]
+    fn initrd_timestamp_monotonic(&self) -> zbus::Result<u64>;
+
+    #[zbus(property)]
+    fn userspace_timestamp_monotonic(&self) -> zbus::Result<u64>;
+
+    #[zbus(property)]
+    fn finish_timestamp_monotonic(&self) -> zbus::Result<u64>;
+}
+
+/// Boot timestamps kept by the service manager, in microseconds. Zero means the phase was not
+/// recorded.
+#[derive(Debug, Clone, Copy, Default)]
+struct Timestamps {
+    kernel: u64,
+    firmware: u64,
+    loader: u64,
+    initrd: u64,
+    userspace: u64,
+    finish: u64,
+}
+
+/// Reads the boot timestamps the service manager keeps, which are what `systemd-analyze` works
+/// from, so they do not have to be scraped from its human readable output
+pub async fn boot_times(connection: &zbus::Connection) -> zbus::Result<BootTimes> {
+    // Properties are polled until the boot has finished, so there is no point in caching them
+    let manager = SystemdManagerProxy::builder(connection)
+        .cache_properties(zbus::proxy::CacheProperties::No)
+        .build()
+        .await?;
+
+    let timestamps = Timestamps {
+        kernel: manager.kernel_timestamp().await?,
+        firmware: manager.firmware_timestamp_monotonic().await?,
+        loader: manager.loader_timestamp_monotonic().await?,
+        initrd: manager.initrd_timestamp_monotonic().await?,
+        userspace: manager.userspace_timestamp_monotonic().await?,
+        finish: manager.finish_timestamp_monotonic().await?,
+    };
+    Ok(phases(timestamps))
+}
+
+/// Firmware and loader timestamps count backwards from the kernel starting, the others forwards
+fn phases(timestamps: Timestamps) -> BootTimes {
+    let recorded = |microseconds: u64| (microseconds > 0).then_some(microseconds);
+    let between = |start: Option<u64>, end: Option<u64>| {
+        Some(Duration::from_micros(end?.checked_sub(start?)?))
+    };
+
+    let firmware = recorded(timestamps.firmware);
+    let loader = recorded(timestamps.loader);
+    let initrd = recorded(timestamps.initrd);
+    let userspace = recorded(timestamps.userspace);
+    let finish = recorded(timestamps.finish);
+
+    BootTimes {
+        booted_at: recorded(timestamps.kernel)
+            .and_then(|microseconds| DateTime::from_timestamp_micros(microseconds as i64))
+            .map(|booted_at| {
+                booted_at
+                    .with_timezone(&Local)
+                    .format("%a %Y-%m-%d %H:%M:%S")
+                    .to_string()
+            }),
+        firmware: between(loader, firmware),
+        loader: loader.map(Duration::from_micros),
+        kernel: initrd.or(userspace).map(Duration::from_micros),
+        initrd: between(initrd, userspace),
+        userspace: between(userspace, finish),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_boot_into_phases() {
+        let times = phases(Timestamps {
+            // Noon UTC, so the date is the same in nearly every time zone
+            kernel: 1_792_238_403_000_000,
+            firmware: 9_876_000,
+            loader: 2_543_000,
+            initrd: 1_204_000,
+            userspace: 4_312_000,
+            finish: 11_907_000,
+        });
+
+        assert!(times
+            .booted_at
+            .as_deref()
+            .is_some_and(|booted_at| booted_at.contains("2026-10-17")));
+        assert_eq!(times.firmware, Some(Duration::from_micros(7_333_000)));
+        assert_eq!(times.loader, Some(Duration::from_micros(2_543_000)));
+        assert_eq!(times.kernel, Some(Duration::from_micros(1_204_000)));
+        assert_eq!(times.initrd, Some(Duration::from_micros(3_108_000)));
+        assert_eq!(times.userspace, Some(Duration::from_micros(7_595_000)));
+        assert_eq!(times.total(), Duration::from_micros(21_783_000));
+    }
+
+    #[test]
+    fn skips_unrecorded_phases() {
+        // Booted without an initrd by a loader that does not report its timestamps, and
+        // still starting up
+        let times = phases(Timestamps {
+            userspace: 1_830_000,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            times,
+            BootTimes {
+                kernel: Some(Duration::from_micros(1_830_000)),
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use std::{collections::HashMap, fs, time::Instant};
+
+/// Share of CPU time spent in each state between two samples, in percent
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CpuStates {
+    pub user: f32,
+    pub nice: f32,
+    pub system: f32,
+    pub iowait: f32,
+    pub irq: f32,
+    pub softirq: f32,
+    pub steal: f32,
+    pub guest: f32,
+}
+
+impl CpuStates {
+    /// Values in stacking order, from user to guest
+    pub fn values(&self) -> [f32; 8] {
+        [
+            self.user,
+            self.nice,
+            self.system,
+            self.iowait,
+            self.irq,
+            self.softirq,
+            self.steal,
+            self.guest,
+        ]
+    }
+
+    fn between(previous: &[u64; 10], current: &[u64; 10]) -> Self {
+        let delta = |i: usize| current[i].saturating_sub(previous[i]) as f32;
+        // The kernel also accounts guest time as user and nice time, so it is split out here
+        let guest = delta(8);
+        let guest_nice = delta(9);
+        let total = (0..8).map(delta).sum::<f32>().max(1.0);
+        let percent = |value: f32| value.max(0.0) / total * 100.0;
+
+        Self {
+            user: percent(delta(0) - guest),
+            nice: percent(delta(1) - guest_nice),
+            system: percent(delta(2)),
+            iowait: percent(delta(4)),
+            irq: percent(delta(5)),
+            softirq: percent(delta(6)),
+            steal: percent(delta(7)),
+            guest: percent(guest + guest_nice),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuActivity {
+    pub global: CpuStates,
+    pub cores: Vec<CpuStates>,
+    /// Logical CPU number of each entry in `cores`. CPUs that are offline, or came online since
+    /// the previous sample, are missing from both.
+    pub core_ids: Vec<u32>,
+    /// 1, 5 and 15 minute load averages
+    pub load_average: [f32; 3],
+    pub context_switches_per_sec: f64,
+    pub interrupts_per_sec: f64,
+}
+
+struct Stat {
+    global: [u64; 10],
+    /// Counters by logical CPU number, in the order `/proc/stat` lists them
+    cores: Vec<(u32, [u64; 10])>,
+    context_switches: u64,
+    interrupts: u64,
+}
+
+impl Stat {
+    /// Pairs each CPU with its own previous counters, so CPUs going offline or online do not
+    /// shift the others
+    fn cores_since(&self, previous: &Stat) -> (Vec<u32>, Vec<CpuStates>) {
+        let previous = previous.cores.iter().cloned().collect::<HashMap<_, _>>();
+        self.cores
+            .iter()
+            .filter_map(|(id, current)| Some((*id, CpuStates::between(previous.get(id)?, current))))
+            .unzip()
+    }
+}
+
+/// Turns the cumulative counters in `/proc/stat` into rates between two samples
+#[derive(Default)]
+pub struct CpuSampler {
+    previous: Option<(Instant, Stat)>,
+}
+
+impl CpuSampler {
+    pub fn sample(&mut self) -> Option<CpuActivity> {
+        let now = Instant::now();
+        let stat = read_stat()?;
+
+        let activity = self.previous.as_ref().map(|(then, previous)| {
+            let elapsed = now.duration_since(*then).as_secs_f64().max(f64::EPSILON);
+            let (core_ids, cores) = stat.cores_since(previous);
+            CpuActivity {
+                global: CpuStates::between(&previous.global, &stat.global),
+                cores,
+                core_ids,
+                load_average: read_load_average().unwrap_or_default(),
+                context_switches_per_sec: stat
+                    .context_switches
+                    .saturating_sub(previous.context_switches)
+                    as f64
+                    / elapsed,
+                interrupts_per_sec: stat.interrupts.saturating_sub(previous.interrupts) as f64
+                    / elapsed,
+            }
+        });
+
+        self.previous = Some((now, stat));
+        activity
+    }
+}
+
+fn read_stat() -> Option<Stat> {
+    parse_stat(&fs::read_to_string("/proc/stat").ok()?)
+}
+
+fn parse_stat(stat: &str) -> Option<Stat> {
+    let mut global = None;
+    let mut cores = Vec::new();
+    let mut context_switches = 0;
+    let mut interrupts = 0;
+    for line in stat.lines() {
+        let mut fields = line.split_whitespace();
+        let Some(key) = fields.next() else {
+            continue;
+        };
+        match key {
+            "cpu" => global = Some(parse_times(fields)),
+            "ctxt" => context_switches = fields.next()?.parse().ok()?,
+            // The first value is the total, the rest are per interrupt line
+            "intr" => interrupts = fields.next()?.parse().ok()?,
+            key if key.starts_with("cpu") => {
+                cores.push((key["cpu".len()..].parse().ok()?, parse_times(fields)));
+            }
+            _ => {}
+        }
+    }
+
+    Some(Stat {
+        global: global?,
+        cores,
+        context_switches,
+        interrupts,
+    })
+}
+
+fn parse_times<'a>(fields: impl Iterator<Item = &'a str>) -> [u64; 10] {
+    let mut times = [0; 10];
+    for (time, field) in times.iter_mut().zip(fields) {
+        *time = field.parse().unwrap_or_default();
+    }
+    times
+}
+
+fn read_load_average() -> Option<[f32; 3]> {
+    let loadavg = fs::read_to_string("/proc/loadavg").ok()?;
+    let mut fields = loadavg.split_whitespace();
+    Some([
+        fields.next()?.parse().ok()?,
+        fields.next()?.parse().ok()?,
+        fields.next()?.parse().ok()?,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STAT: &str = "\
+cpu  4000 100 1000 20000 200 0 100 0 0 0
+cpu0 1000 0 250 5000 50 0 25 0 0 0
+cpu1 1000 100 250 5000 50 0 25 0 0 0
+cpu2 1000 0 250 5000 50 0 25 0 0 0
+cpu3 1000 0 250 5000 50 0 25 0 0 0
+intr 123456 0 9 0
+ctxt 987654
+btime 1760000000
+processes 4321";
+
+    #[test]
+    fn parses_stat() {
+        let stat = parse_stat(STAT).unwrap();
+
+        assert_eq!(stat.global[..4], [4000, 100, 1000, 20000]);
+        assert_eq!(
+            stat.cores.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+        assert_eq!(stat.cores[1].1[1], 100);
+        assert_eq!((stat.context_switches, stat.interrupts), (987654, 123456));
+    }
+
+    #[test]
+    fn pairs_cores_by_id() {
+        let previous = parse_stat(STAT).unwrap();
+        // CPU 1 went offline and CPU 2 was busy since
+        let current = parse_stat(
+            "\
+cpu  4000 100 1000 20000 200 0 100 0 0 0
+cpu0 1000 0 250 5100 50 0 25 0 0 0
+cpu2 1100 0 250 5000 50 0 25 0 0 0
+cpu3 1000 0 250 5100 50 0 25 0 0 0
+cpu4 10 0 0 100 0 0 0 0 0 0",
+        )
+        .unwrap();
+
+        let (ids, cores) = current.cores_since(&previous);
+
+        // CPU 4 came online and has nothing to compare against yet
+        assert_eq!(ids, [0, 2, 3]);
+        assert_eq!(cores[0].user, 0.0);
+        assert_eq!(cores[1].user, 100.0);
+        assert_eq!(cores[2].user, 0.0);
+    }
+}
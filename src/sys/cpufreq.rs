@@ -0,0 +1,167 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use zbus::zvariant::OwnedValue;
+
+use super::read_trimmed;
+
+/// Frequency scaling state of every online CPU
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuFrequency {
+    pub cores: Vec<CoreFrequency>,
+    /// Governor of the first CPU, they are almost always set together
+    pub governor: Option<String>,
+    pub available_governors: Vec<String>,
+    /// Only exposed by drivers with hardware managed P-states, such as intel_pstate and amd-pstate
+    pub energy_performance_preference: Option<String>,
+    /// Thermal throttling events since boot, summed over physical cores
+    pub core_throttle_count: u64,
+    /// Thermal throttling events since boot, summed over packages
+    pub package_throttle_count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreFrequency {
+    pub cpu: u32,
+    pub current_khz: u64,
+    pub min_khz: u64,
+    pub max_khz: u64,
+    /// Missing unless the driver knows the non-turbo frequency
+    pub base_khz: Option<u64>,
+}
+
+/// Power profile state from power-profiles-daemon
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerProfiles {
+    pub active: String,
+    pub available: Vec<String>,
+}
+
+const CPU_ROOT: &str = "/sys/devices/system/cpu";
+
+pub fn read() -> CpuFrequency {
+    let Ok(entries) = fs::read_dir(CPU_ROOT) else {
+        return CpuFrequency::default();
+    };
+
+    let mut cpus = entries
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry
+                .file_name()
+                .to_string_lossy()
+                .strip_prefix("cpu")?
+                .parse::<u32>()
+                .ok()?;
+            Some((id, entry.path()))
+        })
+        .collect::<Vec<_>>();
+    cpus.sort_by_key(|(id, _)| *id);
+
+    let mut frequency = CpuFrequency::default();
+    // SMT siblings report the counters of their core, and every CPU reports its package's
+    let mut core_throttles = HashMap::new();
+    let mut package_throttles = HashMap::new();
+    for (id, path) in &cpus {
+        let cpufreq = path.join("cpufreq");
+        let read_khz = |name: &str| read_trimmed(cpufreq.join(name))?.parse::<u64>().ok();
+
+        if let Some(current_khz) = read_khz("scaling_cur_freq") {
+            frequency.cores.push(CoreFrequency {
+                cpu: *id,
+                current_khz,
+                min_khz: read_khz("cpuinfo_min_freq").unwrap_or_default(),
+                max_khz: read_khz("cpuinfo_max_freq").unwrap_or_default(),
+                base_khz: read_khz("base_frequency"),
+            });
+        }
+
+        if frequency.governor.is_none() {
+            frequency.governor = read_trimmed(cpufreq.join("scaling_governor"));
+            frequency.available_governors =
+                read_list(&cpufreq.join("scaling_available_governors"));
+            frequency.energy_performance_preference =
+                read_trimmed(cpufreq.join("energy_performance_preference"));
+        }
+
+        let topology = path.join("topology");
+        let package = read_trimmed(topology.join("physical_package_id"));
+        let core = read_trimmed(topology.join("core_id"));
+        let throttle = path.join("thermal_throttle");
+        let read_count = |name: &str| {
+            read_trimmed(throttle.join(name)).and_then(|count| count.parse::<u64>().ok())
+        };
+        if let Some(count) = read_count("core_throttle_count") {
+            core_throttles.insert((package.clone(), core), count);
+        }
+        if let Some(count) = read_count("package_throttle_count") {
+            package_throttles.insert(package, count);
+        }
+    }
+    frequency.core_throttle_count = core_throttles.values().sum();
+    frequency.package_throttle_count = package_throttles.values().sum();
+
+    frequency
+}
+
+fn read_list(path: &Path) -> Vec<String> {
+    read_trimmed(path)
+        .map(|list| list.split_whitespace().map(str::to_owned).collect())
+        .unwrap_or_default()
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.UPower.PowerProfiles",
+    default_service = "org.freedesktop.UPower.PowerProfiles",
+    default_path = "/org/freedesktop/UPower/PowerProfiles"
+)]
+trait PowerProfilesDaemon {
+    #[zbus(property)]
+    fn active_profile(&self) -> zbus::Result<String>;
+
+    /// Each profile as a dictionary, with its name under `Profile`
+    #[zbus(property)]
+    fn profiles(&self) -> zbus::Result<Vec<HashMap<String, OwnedValue>>>;
+}
+
+/// Asks power-profiles-daemon for the active and available profiles, `None` when it is not
+/// running
+pub async fn power_profiles(connection: &zbus::Connection) -> Option<PowerProfiles> {
+    // Releases before 0.20 only use the name the project started under
+    for (service, path) in [
+        (
+            "org.freedesktop.UPower.PowerProfiles",
+            "/org/freedesktop/UPower/PowerProfiles",
+        ),
+        ("net.hadess.PowerProfiles", "/net/hadess/PowerProfiles"),
+    ] {
+        // Properties are polled, so there is no point in caching them
+        let Some(daemon) = PowerProfilesDaemonProxy::builder(connection)
+            .destination(service)
+            .and_then(|builder| builder.path(path))
+            .and_then(|builder| builder.interface(service))
+            .ok()
+            .map(|builder| builder.cache_properties(zbus::proxy::CacheProperties::No))
+        else {
+            continue;
+        };
+        let Ok(daemon) = daemon.build().await else {
+            continue;
+        };
+        let Ok(active) = daemon.active_profile().await else {
+            continue;
+        };
+
+        // Listed from least to most performant
+        let available = daemon
+            .profiles()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|profile| <&str>::try_from(&**profile.get("Profile")?).ok())
+            .map(str::to_owned)
+            .collect();
+        return Some(PowerProfiles { active, available });
+    }
+
+    None
+}
@@ -0,0 +1,209 @@
+use std::{fs, path::Path};
+
+use super::read_trimmed;
+
+/// A populated memory slot
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryModule {
+    pub slot: String,
+    pub bank: Option<String>,
+    pub size_bytes: u64,
+    pub memory_type: Option<String>,
+    /// Rated speed in MT/s
+    pub speed_mts: Option<u32>,
+    /// Speed the memory controller runs the module at, in MT/s
+    pub configured_speed_mts: Option<u32>,
+    pub manufacturer: Option<String>,
+    pub part_number: Option<String>,
+    pub ecc: Option<bool>,
+    /// Error counters from EDAC, missing without an EDAC driver for the memory controller
+    pub corrected_errors: Option<u64>,
+    pub uncorrected_errors: Option<u64>,
+}
+
+const DMI_ENTRIES: &str = "/sys/firmware/dmi/entries";
+const EDAC_ROOT: &str = "/sys/devices/system/edac/mc";
+
+/// Memory devices read earlier from the SMBIOS tables, combined with the current EDAC counters
+pub fn read(smbios: &[MemoryModule]) -> Vec<MemoryModule> {
+    let mut modules = smbios.to_vec();
+    let edac = read_edac();
+
+    if modules.is_empty() {
+        return edac;
+    }
+
+    // EDAC labels usually name the slot as the firmware does, otherwise fall back to the order
+    let same_count = modules.len() == edac.len();
+    for (index, module) in modules.iter_mut().enumerate() {
+        let counters = edac
+            .iter()
+            .find(|dimm| !module.slot.is_empty() && dimm.slot.contains(&module.slot))
+            .or_else(|| edac.get(index).filter(|_| same_count));
+        if let Some(counters) = counters {
+            module.corrected_errors = counters.corrected_errors;
+            module.uncorrected_errors = counters.uncorrected_errors;
+            module.ecc = module.ecc.or(counters.ecc);
+        }
+    }
+    modules
+}
+
+/// Parses SMBIOS type 17 (memory device) structures, which only root can read. They do not
+/// change while the system runs, so reading them once is enough.
+pub fn read_smbios() -> Vec<MemoryModule> {
+    let Ok(entries) = fs::read_dir(DMI_ENTRIES) else {
+        return Vec::new();
+    };
+
+    let mut entries = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let index = name.strip_prefix("17-")?.parse::<u32>().ok()?;
+            Some((index, entry.path()))
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|(index, _)| *index);
+
+    entries
+        .into_iter()
+        .filter_map(|(_, path)| parse_memory_device(&fs::read(path.join("raw")).ok()?))
+        .collect()
+}
+
+fn parse_memory_device(raw: &[u8]) -> Option<MemoryModule> {
+    let length = *raw.get(1)? as usize;
+    let formatted = raw.get(..length)?;
+    let strings = raw[length..]
+        .split(|byte| *byte == 0)
+        .take_while(|string| !string.is_empty())
+        .map(|string| String::from_utf8_lossy(string).trim().to_owned())
+        .collect::<Vec<_>>();
+
+    let byte = |offset: usize| formatted.get(offset).copied();
+    let word = |offset: usize| Some(u16::from_le_bytes([byte(offset)?, byte(offset + 1)?]));
+    let dword = |offset: usize| {
+        Some(u32::from_le_bytes([
+            byte(offset)?,
+            byte(offset + 1)?,
+            byte(offset + 2)?,
+            byte(offset + 3)?,
+        ]))
+    };
+    // Strings are referenced by 1-based index, with 0 meaning none
+    let string = |offset: usize| {
+        let index = byte(offset)? as usize;
+        strings
+            .get(index.checked_sub(1)?)
+            .filter(|string| !string.is_empty() && !is_placeholder(string))
+            .cloned()
+    };
+
+    // Empty slots report a size of 0
+    let size_bytes = match word(0x0C)? {
+        0 | 0xFFFF => return None,
+        0x7FFF => dword(0x1C)? as u64 * 1024 * 1024,
+        size if size & 0x8000 != 0 => (size & 0x7FFF) as u64 * 1024,
+        size => size as u64 * 1024 * 1024,
+    };
+    let speed = |offset: usize, extended: usize| match word(offset)? {
+        0 => None,
+        0xFFFF => dword(extended),
+        speed => Some(speed as u32),
+    };
+    let ecc = match (word(0x08)?, word(0x0A)?) {
+        (0xFFFF, _) | (_, 0xFFFF) => None,
+        (total, data) => Some(total > data),
+    };
+
+    Some(MemoryModule {
+        slot: string(0x10).unwrap_or_default(),
+        bank: string(0x11),
+        size_bytes,
+        memory_type: byte(0x12).and_then(memory_type).map(str::to_owned),
+        speed_mts: speed(0x15, 0x54),
+        configured_speed_mts: speed(0x20, 0x58),
+        manufacturer: string(0x17),
+        part_number: string(0x1A),
+        ecc,
+        corrected_errors: None,
+        uncorrected_errors: None,
+    })
+}
+
+/// Firmware fills unknown fields with text like this instead of leaving them out
+fn is_placeholder(string: &str) -> bool {
+    matches!(
+        string.to_ascii_lowercase().as_str(),
+        "unknown" | "not specified" | "to be filled by o.e.m." | "undefined"
+    )
+}
+
+fn memory_type(value: u8) -> Option<&'static str> {
+    Some(match value {
+        0x0F => "SDRAM",
+        0x12 => "DDR",
+        0x13 => "DDR2",
+        0x14 => "DDR2 FB-DIMM",
+        0x18 => "DDR3",
+        0x1A => "DDR4",
+        0x1B => "LPDDR",
+        0x1C => "LPDDR2",
+        0x1D => "LPDDR3",
+        0x1E => "LPDDR4",
+        0x20 => "HBM",
+        0x21 => "HBM2",
+        0x22 => "DDR5",
+        0x23 => "LPDDR5",
+        0x24 => "HBM3",
+        _ => return None,
+    })
+}
+
+/// DIMMs known to EDAC, readable by everyone but without vendor details
+fn read_edac() -> Vec<MemoryModule> {
+    let Ok(controllers) = fs::read_dir(EDAC_ROOT) else {
+        return Vec::new();
+    };
+
+    let mut dimms = controllers
+        .flatten()
+        .flat_map(|controller| fs::read_dir(controller.path()).into_iter().flatten())
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            // Older drivers expose ranks instead of DIMMs
+            name.starts_with("dimm") || name.starts_with("rank")
+        })
+        .filter_map(|entry| read_edac_dimm(&entry.path()))
+        .collect::<Vec<_>>();
+    dimms.sort_by(|a, b| a.slot.cmp(&b.slot));
+    dimms
+}
+
+fn read_edac_dimm(path: &Path) -> Option<MemoryModule> {
+    let read_number = |name: &str| read_trimmed(path.join(name))?.parse::<u64>().ok();
+    let size_mib = read_number("size")?;
+    if size_mib == 0 {
+        return None;
+    }
+
+    let edac_mode = read_trimmed(path.join("dimm_edac_mode"));
+    Some(MemoryModule {
+        slot: read_trimmed(path.join("dimm_label"))
+            .or_else(|| Some(path.file_name()?.to_string_lossy().into_owned()))?,
+        bank: None,
+        size_bytes: size_mib * 1024 * 1024,
+        memory_type: read_trimmed(path.join("dimm_mem_type"))
+            .map(|kind| kind.trim_start_matches("Unbuffered-").to_owned()),
+        speed_mts: None,
+        configured_speed_mts: None,
+        manufacturer: None,
+        part_number: None,
+        ecc: edac_mode.map(|mode| mode != "None" && mode != "Unknown"),
+        corrected_errors: read_number("dimm_ce_count"),
+        uncorrected_errors: read_number("dimm_ue_count"),
+    })
+}
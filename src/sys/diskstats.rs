@@ -0,0 +1,202 @@
+use std::{collections::HashMap, fs, time::Instant};
+
+/// Extended statistics of one block device between two samples, as `iostat -x` reports them
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct IoStats {
+    pub reads_per_sec: f32,
+    pub writes_per_sec: f32,
+    /// Average time a read took to complete, including time spent queued
+    pub read_latency_ms: f32,
+    pub write_latency_ms: f32,
+    /// Average number of requests queued or being serviced
+    pub queue_depth: f32,
+    /// Requests in flight at the moment of the sample
+    pub in_flight: u64,
+    /// Share of time the device had requests in flight, in percent. Devices serving requests
+    /// in parallel, like SSDs and RAID arrays, can be far from saturated at 100%.
+    pub utilization: f32,
+}
+
+impl IoStats {
+    pub fn iops(&self) -> f32 {
+        self.reads_per_sec + self.writes_per_sec
+    }
+
+    /// Average time any request took to complete, weighted by the number of reads and writes
+    pub fn latency_ms(&self) -> f32 {
+        let iops = self.iops();
+        if iops == 0.0 {
+            return 0.0;
+        }
+        (self.read_latency_ms * self.reads_per_sec + self.write_latency_ms * self.writes_per_sec)
+            / iops
+    }
+}
+
+/// Cumulative counters from one line of `/proc/diskstats`
+struct Counters {
+    reads: u64,
+    read_ticks: u64,
+    writes: u64,
+    write_ticks: u64,
+    in_flight: u64,
+    io_ticks: u64,
+    queue_ticks: u64,
+}
+
+/// Turns the cumulative counters in `/proc/diskstats` into rates
+#[derive(Default)]
+pub struct DiskStatsSampler {
+    previous: Option<(Instant, HashMap<String, Counters>)>,
+}
+
+impl DiskStatsSampler {
+    /// Statistics keyed by kernel device name, `None` for the first sample
+    pub fn sample(&mut self) -> Option<HashMap<String, IoStats>> {
+        let now = Instant::now();
+        let counters = read_counters()?;
+
+        let stats = self.previous.as_ref().map(|(then, previous)| {
+            let elapsed_ms = (now.duration_since(*then).as_secs_f32() * 1000.0).max(1.0);
+            rates(previous, &counters, elapsed_ms)
+        });
+
+        self.previous = Some((now, counters));
+        stats
+    }
+}
+
+/// Rates of devices present in both samples, given the time between them
+fn rates(
+    previous: &HashMap<String, Counters>,
+    counters: &HashMap<String, Counters>,
+    elapsed_ms: f32,
+) -> HashMap<String, IoStats> {
+    counters
+        .iter()
+        .filter_map(|(name, current)| {
+            let previous = previous.get(name)?;
+            let reads = current.reads.saturating_sub(previous.reads);
+            let writes = current.writes.saturating_sub(previous.writes);
+            let latency = |ticks: u64, requests: u64| {
+                if requests == 0 {
+                    0.0
+                } else {
+                    ticks as f32 / requests as f32
+                }
+            };
+
+            Some((
+                name.clone(),
+                IoStats {
+                    reads_per_sec: reads as f32 / elapsed_ms * 1000.0,
+                    writes_per_sec: writes as f32 / elapsed_ms * 1000.0,
+                    read_latency_ms: latency(
+                        current.read_ticks.saturating_sub(previous.read_ticks),
+                        reads,
+                    ),
+                    write_latency_ms: latency(
+                        current.write_ticks.saturating_sub(previous.write_ticks),
+                        writes,
+                    ),
+                    queue_depth: current.queue_ticks.saturating_sub(previous.queue_ticks) as f32
+                        / elapsed_ms,
+                    in_flight: current.in_flight,
+                    utilization: (current.io_ticks.saturating_sub(previous.io_ticks) as f32
+                        / elapsed_ms
+                        * 100.0)
+                        .min(100.0),
+                },
+            ))
+        })
+        .collect()
+}
+
+fn read_counters() -> Option<HashMap<String, Counters>> {
+    fs::read_to_string("/proc/diskstats")
+        .ok()
+        .map(|content| parse_counters(&content))
+}
+
+/// Lines look like `259 0 nvme0n1 5243 1200 ...`, see `Documentation/admin-guide/iostats.rst`
+fn parse_counters(content: &str) -> HashMap<String, Counters> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let field = |index: usize| fields.get(index)?.parse::<u64>().ok();
+            Some((
+                fields.get(2)?.to_string(),
+                Counters {
+                    reads: field(3)?,
+                    read_ticks: field(6)?,
+                    writes: field(7)?,
+                    write_ticks: field(10)?,
+                    in_flight: field(11)?,
+                    io_ticks: field(12)?,
+                    queue_ticks: field(13)?,
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Two samples of `/proc/diskstats` a second apart. Kernels since 5.5 add discard and
+    // flush counters after the fields that are read.
+    const BEFORE: &str = "\
+ 259       0 nvme0n1 184210 51037 12036518 41205 402119 198244 29017384 301112 0 152340 349822 0 0 0 0 20931 7504
+ 259       1 nvme0n1p1 312 0 9872 51 2 0 2 0 0 60 51 0 0 0 0 0 0
+   8       0 sda 9120 210 602144 88120 1210 880 190232 40210 0 61200 128330
+   7       0 loop0 48 0 2112 5 0 0 0 0 0 12 5 0 0 0 0 0 0";
+
+    const AFTER: &str = "\
+ 259       0 nvme0n1 184410 51037 12046518 41305 402419 198244 29037384 301712 3 152840 350822 0 0 0 0 20931 7504
+ 259       1 nvme0n1p1 312 0 9872 51 2 0 2 0 0 60 51 0 0 0 0 0 0
+   8       0 sda 9120 210 602144 88120 1210 880 190232 40210 0 61200 128330
+   8       1 sda1 9000 210 600000 88000 1200 880 190000 40200 0 61000 128200";
+
+    #[test]
+    fn parses_counters() {
+        let counters = parse_counters(BEFORE);
+
+        assert_eq!(counters.len(), 4);
+        let nvme = &counters["nvme0n1"];
+        assert_eq!((nvme.reads, nvme.read_ticks), (184210, 41205));
+        assert_eq!((nvme.writes, nvme.write_ticks), (402119, 301112));
+        assert_eq!(nvme.in_flight, 0);
+        assert_eq!((nvme.io_ticks, nvme.queue_ticks), (152340, 349822));
+        // Kernels before 4.18 stop after the queue time
+        assert_eq!(counters["sda"].queue_ticks, 128330);
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let counters = parse_counters("   8       0 sda 9120 210 602144\n\nnot diskstats at all");
+
+        assert!(counters.is_empty());
+    }
+
+    #[test]
+    fn computes_rates() {
+        let stats = rates(&parse_counters(BEFORE), &parse_counters(AFTER), 1000.0);
+
+        // Devices missing from either sample have no rates
+        assert_eq!(stats.len(), 3);
+        let nvme = stats["nvme0n1"];
+        assert_eq!(nvme.reads_per_sec, 200.0);
+        assert_eq!(nvme.writes_per_sec, 300.0);
+        assert_eq!(nvme.read_latency_ms, 0.5);
+        assert_eq!(nvme.write_latency_ms, 2.0);
+        assert_eq!(nvme.iops(), 500.0);
+        assert_eq!(nvme.latency_ms(), 1.4);
+        assert_eq!(nvme.queue_depth, 1.0);
+        assert_eq!(nvme.in_flight, 3);
+        assert_eq!(nvme.utilization, 50.0);
+        // Idle devices report zero latency rather than dividing by zero
+        assert_eq!(stats["sda"], IoStats::default());
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    fs::{self, Metadata},
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+/// Entries kept per directory, smaller ones are only counted
+const KEPT_ENTRIES: usize = 64;
+
+/// A file or directory with the space it takes up on disk
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageNode {
+    pub name: String,
+    /// Allocated size, which is smaller than the apparent size for sparse files
+    pub size: u64,
+    pub is_dir: bool,
+    /// Largest entries first
+    pub children: Vec<UsageNode>,
+    /// Number and combined size of entries too small to be kept
+    pub other: (u64, u64),
+}
+
+/// Shared between a running scan and the page showing it
+#[derive(Debug, Default)]
+pub struct ScanProgress {
+    pub cancel: AtomicBool,
+    pub entries: AtomicU64,
+    pub bytes: AtomicU64,
+    /// Directories that could not be read for lack of permission
+    pub denied: AtomicU64,
+}
+
+/// Sums up the space taken below `root` without crossing into other filesystems or following
+/// symlinks. Hard linked files are counted once. Returns `None` when cancelled.
+///
+/// The tree is walked with an explicit stack rather than by recursion, so arbitrarily deep
+/// trees cannot overflow the thread's stack. Directories are listed in full when entered so
+/// no descriptor stays open for the directories above.
+pub fn scan(root: &Path, progress: &ScanProgress) -> Option<UsageNode> {
+    let metadata = fs::symlink_metadata(root).ok()?;
+    let device = metadata.dev();
+    let mut seen = HashSet::new();
+    let mut stack = vec![DirScan::open(
+        root.to_path_buf(),
+        root.to_string_lossy().into_owned(),
+        &metadata,
+        progress,
+    )];
+
+    loop {
+        let dir = stack.last_mut()?;
+        let entry = if progress.cancel.load(Ordering::Relaxed) {
+            None
+        } else {
+            dir.entries.next()
+        };
+
+        let Some((name, metadata)) = entry else {
+            // The directory is complete, hand it to its parent
+            let node = stack.pop()?.finish();
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(node),
+                None => return (!progress.cancel.load(Ordering::Relaxed)).then_some(node),
+            }
+            continue;
+        };
+        // Mount points below the root belong to other filesystems
+        if metadata.dev() != device {
+            continue;
+        }
+        if !metadata.is_dir() && metadata.nlink() > 1 && !seen.insert(metadata.ino()) {
+            continue;
+        }
+        progress.entries.fetch_add(1, Ordering::Relaxed);
+
+        let name = name.to_string_lossy().into_owned();
+        if metadata.is_dir() {
+            let path = dir.path.join(&name);
+            stack.push(DirScan::open(path, name, &metadata, progress));
+        } else {
+            let size = metadata.blocks() * 512;
+            progress.bytes.fetch_add(size, Ordering::Relaxed);
+            dir.children.push(UsageNode {
+                name,
+                size,
+                is_dir: false,
+                children: Vec::new(),
+                other: (0, 0),
+            });
+        }
+    }
+}
+
+/// A directory on the scan stack whose entries are still being visited
+struct DirScan {
+    path: PathBuf,
+    name: String,
+    /// Blocks taken by the directory's own listing
+    size: u64,
+    entries: std::vec::IntoIter<(OsString, Metadata)>,
+    children: Vec<UsageNode>,
+}
+
+impl DirScan {
+    fn open(path: PathBuf, name: String, metadata: &Metadata, progress: &ScanProgress) -> Self {
+        let entries = match fs::read_dir(&path) {
+            // Unlike `fs::metadata`, `DirEntry::metadata` does not follow symlinks
+            Ok(entries) => entries
+                .flatten()
+                .filter_map(|entry| Some((entry.file_name(), entry.metadata().ok()?)))
+                .collect(),
+            Err(err) => {
+                if err.kind() == std::io::ErrorKind::PermissionDenied {
+                    progress.denied.fetch_add(1, Ordering::Relaxed);
+                }
+                Vec::new()
+            }
+        };
+
+        Self {
+            path,
+            name,
+            size: metadata.blocks() * 512,
+            entries: entries.into_iter(),
+            children: Vec::new(),
+        }
+    }
+
+    fn finish(self) -> UsageNode {
+        let mut children = self.children;
+        children.sort_by(|a, b| b.size.cmp(&a.size));
+        let size = self.size + children.iter().map(|child| child.size).sum::<u64>();
+        let other = children
+            .drain(children.len().min(KEPT_ENTRIES)..)
+            .fold((0, 0), |(count, size), child| {
+                (count + 1, size + child.size)
+            });
+
+        UsageNode {
+            name: self.name,
+            size,
+            is_dir: true,
+            children,
+            other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scratch directory that is removed again when the test ends
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn sums_sizes_and_counts_hard_links_once() {
+        let root = TempDir::new("observatory-scan");
+        fs::create_dir(root.0.join("small")).unwrap();
+        fs::write(root.0.join("small/a"), vec![1; 4096]).unwrap();
+        fs::create_dir(root.0.join("large")).unwrap();
+        fs::write(root.0.join("large/b"), vec![1; 256 * 1024]).unwrap();
+        fs::hard_link(root.0.join("large/b"), root.0.join("large/c")).unwrap();
+        std::os::unix::fs::symlink(root.0.join("large"), root.0.join("link")).unwrap();
+
+        let progress = ScanProgress::default();
+        let node = scan(&root.0, &progress).unwrap();
+
+        let names = node
+            .children
+            .iter()
+            .map(|child| child.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names[..2], ["large", "small"]);
+        assert_eq!(node.children[0].children.len(), 1);
+        assert!(node.children[0].size >= 256 * 1024);
+        assert_eq!(
+            node.size,
+            fs::symlink_metadata(&root.0).unwrap().blocks() * 512
+                + node.children.iter().map(|child| child.size).sum::<u64>()
+        );
+        // Both directories, both files and the symlink, but not the second hard link
+        assert_eq!(progress.entries.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn walks_deep_trees() {
+        const DEPTH: usize = 1500;
+        let root = TempDir::new("observatory-deep-scan");
+        let mut path = root.0.clone();
+        for _ in 0..DEPTH {
+            path.push("d");
+        }
+        fs::create_dir_all(&path).unwrap();
+
+        let progress = ScanProgress::default();
+        let mut node = &scan(&root.0, &progress).unwrap();
+        let mut depth = 0;
+        while let Some(child) = node.children.first() {
+            node = child;
+            depth += 1;
+        }
+        assert_eq!(depth, DEPTH);
+    }
+
+    #[test]
+    fn cancels() {
+        let root = TempDir::new("observatory-cancelled-scan");
+        let progress = ScanProgress::default();
+        progress.cancel.store(true, Ordering::Relaxed);
+
+        assert_eq!(scan(&root.0, &progress), None);
+    }
+}
@@ -0,0 +1,248 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    time::Instant,
+};
+
+use super::read_trimmed;
+
+/// Per-process GPU usage, as reported by DRM in `/proc/<pid>/fdinfo`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuProcess {
+    pub pid: u32,
+    pub name: String,
+    /// PCI bus address of the GPU in use
+    pub address: String,
+    pub utilization_percent: f64,
+    pub vram_bytes: u64,
+    pub encode_percent: f64,
+    pub decode_percent: f64,
+    pub kind: GpuProcessKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuProcessKind {
+    Graphics,
+    Compute,
+}
+
+enum Engine {
+    Graphics,
+    Compute,
+    Encode,
+    Decode,
+    Other,
+}
+
+impl Engine {
+    /// Engine names differ between drivers, e.g. `gfx` on amdgpu and `render` on i915
+    fn classify(name: &str) -> Self {
+        match name {
+            "gfx" | "render" | "rcs" => Self::Graphics,
+            "compute" | "ccs" => Self::Compute,
+            // Video post-processing such as scaling and color conversion, neither encode nor
+            // decode
+            "video-enhance" | "vecs" => Self::Other,
+            name if name.starts_with("enc") => Self::Encode,
+            name if name.starts_with("dec")
+                || name.starts_with("video")
+                || name.starts_with("vcn")
+                || name.starts_with("jpeg")
+                || name == "vcs" =>
+            {
+                Self::Decode
+            }
+            _ => Self::Other,
+        }
+    }
+}
+
+/// A single DRM file description, several of which can belong to one process
+struct DrmClient {
+    pid: u32,
+    id: u64,
+    address: String,
+    // Busy time per engine in nanoseconds
+    engines: HashMap<String, u64>,
+    vram_bytes: u64,
+}
+
+/// Turns the cumulative engine times in fdinfo into utilization between two samples
+#[derive(Default)]
+pub struct DrmSampler {
+    engines: HashMap<(String, u64, String), u64>,
+    last_sample: Option<Instant>,
+}
+
+impl DrmSampler {
+    pub fn sample(&mut self) -> Vec<GpuProcess> {
+        let now = Instant::now();
+        let elapsed_ns = self
+            .last_sample
+            .map(|last| now.duration_since(last).as_nanos() as f64)
+            .filter(|elapsed| *elapsed > 0.0);
+
+        let mut engines = HashMap::new();
+        let mut processes = HashMap::<(u32, String), (GpuProcess, u64, u64)>::new();
+        for client in clients() {
+            let (process, graphics_ns, compute_ns) = processes
+                .entry((client.pid, client.address.clone()))
+                .or_insert_with(|| {
+                    (
+                        GpuProcess {
+                            pid: client.pid,
+                            name: read_trimmed(format!("/proc/{}/comm", client.pid))
+                                .unwrap_or_default(),
+                            address: client.address.clone(),
+                            utilization_percent: 0.0,
+                            vram_bytes: 0,
+                            encode_percent: 0.0,
+                            decode_percent: 0.0,
+                            kind: GpuProcessKind::Graphics,
+                        },
+                        0,
+                        0,
+                    )
+                });
+            process.vram_bytes += client.vram_bytes;
+
+            for (engine, ns) in client.engines {
+                let key = (client.address.clone(), client.id, engine);
+                let busy = match (elapsed_ns, self.engines.get(&key)) {
+                    (Some(elapsed), Some(previous)) => {
+                        ns.saturating_sub(*previous) as f64 / elapsed * 100.0
+                    }
+                    _ => 0.0,
+                };
+                match Engine::classify(&key.2) {
+                    Engine::Graphics => {
+                        process.utilization_percent += busy;
+                        *graphics_ns += ns;
+                    }
+                    Engine::Compute => {
+                        process.utilization_percent += busy;
+                        *compute_ns += ns;
+                    }
+                    Engine::Encode => process.encode_percent += busy,
+                    Engine::Decode => process.decode_percent += busy,
+                    Engine::Other => {}
+                }
+                engines.insert(key, ns);
+            }
+        }
+
+        self.engines = engines;
+        self.last_sample = Some(now);
+
+        processes
+            .into_values()
+            .map(|(mut process, graphics_ns, compute_ns)| {
+                process.utilization_percent = process.utilization_percent.min(100.0);
+                process.encode_percent = process.encode_percent.min(100.0);
+                process.decode_percent = process.decode_percent.min(100.0);
+                if compute_ns > graphics_ns {
+                    process.kind = GpuProcessKind::Compute;
+                }
+                process
+            })
+            .collect()
+    }
+}
+
+/// Collects every DRM client visible to us, processes of other users are skipped
+fn clients() -> Vec<DrmClient> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    let mut clients = Vec::new();
+    let mut seen = HashSet::new();
+    for entry in entries.flatten() {
+        let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+
+        for fd in fds.flatten() {
+            let is_drm =
+                fs::read_link(fd.path()).is_ok_and(|target| target.starts_with("/dev/dri/"));
+            if !is_drm {
+                continue;
+            }
+            let Ok(fdinfo) = fs::read_to_string(entry.path().join("fdinfo").join(fd.file_name()))
+            else {
+                continue;
+            };
+            // Duplicated descriptors share a client and would be counted twice
+            if let Some(client) = parse_fdinfo(pid, &fdinfo) {
+                if seen.insert((client.address.clone(), client.id)) {
+                    clients.push(client);
+                }
+            }
+        }
+    }
+    clients
+}
+
+fn parse_fdinfo(pid: u32, fdinfo: &str) -> Option<DrmClient> {
+    let mut address = None;
+    let mut id = None;
+    let mut engines = HashMap::new();
+    let mut vram_bytes = 0;
+
+    for line in fdinfo.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "drm-pdev" => address = Some(value.to_owned()),
+            "drm-client-id" => id = value.parse().ok(),
+            _ => {
+                if let Some(engine) = key.strip_prefix("drm-engine-") {
+                    if engine.starts_with("capacity-") {
+                        continue;
+                    }
+                    if let Some(ns) = value
+                        .strip_suffix("ns")
+                        .and_then(|ns| ns.trim().parse().ok())
+                    {
+                        engines.insert(engine.to_owned(), ns);
+                    }
+                } else if let Some(region) = key
+                    .strip_prefix("drm-resident-")
+                    .or_else(|| key.strip_prefix("drm-memory-"))
+                {
+                    // Older kernels only report drm-memory, newer ones report both
+                    if region == "vram" || region.starts_with("local") {
+                        vram_bytes = vram_bytes.max(parse_size(value));
+                    }
+                }
+            }
+        }
+    }
+
+    Some(DrmClient {
+        pid,
+        id: id?,
+        address: address?,
+        engines,
+        vram_bytes,
+    })
+}
+
+fn parse_size(value: &str) -> u64 {
+    let mut parts = value.split_whitespace();
+    let amount = parts
+        .next()
+        .and_then(|amount| amount.parse::<u64>().ok())
+        .unwrap_or_default();
+    match parts.next() {
+        Some("KiB") => amount * 1024,
+        Some("MiB") => amount * 1024u64.pow(2),
+        Some("GiB") => amount * 1024u64.pow(3),
+        _ => amount,
+    }
+}
@@ -0,0 +1,531 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::read_trimmed;
+
+/// Motherboard, firmware and product identification from the DMI tables
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dmi {
+    pub system_vendor: Option<String>,
+    pub product_name: Option<String>,
+    pub product_version: Option<String>,
+    pub board_vendor: Option<String>,
+    pub board_name: Option<String>,
+    pub board_version: Option<String>,
+    pub bios_vendor: Option<String>,
+    pub bios_version: Option<String>,
+    pub bios_date: Option<String>,
+    pub chassis: Option<&'static str>,
+}
+
+/// A PCI function and the functions behind it if it is a bridge
+#[derive(Debug, Clone, PartialEq)]
+pub struct PciDevice {
+    /// Bus address, e.g. `0000:01:00.0`
+    pub address: String,
+    pub vendor: String,
+    pub device: String,
+    pub class: String,
+    pub driver: Option<String>,
+    pub children: Vec<PciDevice>,
+}
+
+/// A USB device and the devices plugged into it if it is a hub
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsbDevice {
+    /// Port path, e.g. `1-2.1`, or `usb1` for a root hub
+    pub port: String,
+    /// Vendor and product ID, e.g. `046d:c52b`
+    pub id: String,
+    pub vendor: String,
+    pub product: String,
+    /// Negotiated speed in Mbit/s
+    pub speed: Option<String>,
+    /// Drivers bound to the device's interfaces
+    pub drivers: Vec<String>,
+    pub children: Vec<UsbDevice>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundCard {
+    pub index: u32,
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputDevice {
+    pub name: String,
+    /// Event handlers such as `kbd`, `mouse0` or `event3`
+    pub handlers: Vec<String>,
+}
+
+/// Everything in the box, as far as the kernel knows
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Inventory {
+    pub dmi: Dmi,
+    pub pci: Vec<PciDevice>,
+    pub usb: Vec<UsbDevice>,
+    pub sound: Vec<SoundCard>,
+    pub input: Vec<InputDevice>,
+}
+
+/// Names for numeric vendor, device and class IDs from the `pci.ids` and `usb.ids` databases
+#[derive(Debug, Default)]
+pub struct IdNames {
+    pci: IdDatabase,
+    usb: IdDatabase,
+}
+
+/// Vendors with their devices, and device classes with their subclasses
+#[derive(Debug, Default)]
+struct IdDatabase {
+    vendors: HashMap<String, (String, HashMap<String, String>)>,
+    classes: HashMap<String, (String, HashMap<String, String>)>,
+}
+
+impl IdNames {
+    /// Loads the databases from wherever the distribution keeps them. Missing ones leave
+    /// devices named by their IDs.
+    pub fn load() -> Self {
+        let read = |file: &str| {
+            ["/usr/share/hwdata", "/usr/share/misc", "/usr/share"]
+                .iter()
+                .find_map(|dir| fs::read_to_string(Path::new(dir).join(file)).ok())
+                .map(|content| IdDatabase::parse(&content))
+                .unwrap_or_default()
+        };
+
+        Self {
+            pci: read("pci.ids"),
+            usb: read("usb.ids"),
+        }
+    }
+}
+
+impl IdDatabase {
+    /// Vendors start at the beginning of a line, e.g. `8086  Intel Corporation`, with their
+    /// devices indented by a tab. Classes are introduced by `C`, e.g. `C 02  Network controller`.
+    /// Subsystems, indented by two tabs, are not needed.
+    fn parse(content: &str) -> Self {
+        let mut database = Self::default();
+        // Whether the entries being read are classes rather than vendors
+        let mut current: Option<(bool, String)> = None;
+
+        for line in content.lines() {
+            if line.starts_with('#') || line.trim().is_empty() || line.starts_with("\t\t") {
+                continue;
+            }
+            if let Some(entry) = line.strip_prefix('\t') {
+                let Some((id, name)) = entry.split_once("  ") else {
+                    continue;
+                };
+                let Some((is_class, parent)) = &current else {
+                    continue;
+                };
+                let table = if *is_class {
+                    &mut database.classes
+                } else {
+                    &mut database.vendors
+                };
+                if let Some((_, children)) = table.get_mut(parent) {
+                    children.insert(id.to_ascii_lowercase(), name.trim().to_owned());
+                }
+                continue;
+            }
+
+            let (is_class, entry) = match line.strip_prefix("C ") {
+                Some(entry) => (true, entry),
+                None => (false, line),
+            };
+            let Some((id, name)) = entry.split_once("  ") else {
+                current = None;
+                continue;
+            };
+            // Other lists like languages and HID usages follow the vendors in `usb.ids`, with
+            // tags like `L` or `HUT` before their IDs
+            let is_vendor = id.len() == 4 && id.chars().all(|c| c.is_ascii_hexdigit());
+            if !is_class && !is_vendor {
+                current = None;
+                continue;
+            }
+            let id = id.to_ascii_lowercase();
+            let table = if is_class {
+                &mut database.classes
+            } else {
+                &mut database.vendors
+            };
+            table.insert(id.clone(), (name.trim().to_owned(), HashMap::new()));
+            current = Some((is_class, id));
+        }
+
+        database
+    }
+
+    fn vendor(&self, vendor: &str) -> Option<&str> {
+        self.vendors.get(vendor).map(|(name, _)| name.as_str())
+    }
+
+    fn device(&self, vendor: &str, device: &str) -> Option<&str> {
+        self.vendors
+            .get(vendor)?
+            .1
+            .get(device)
+            .map(|name| name.as_str())
+    }
+
+    /// The subclass name if known, otherwise the class name
+    fn class(&self, class: &str, subclass: &str) -> Option<&str> {
+        let (name, subclasses) = self.classes.get(class)?;
+        Some(subclasses.get(subclass).unwrap_or(name))
+    }
+}
+
+pub fn read(names: &IdNames) -> Inventory {
+    Inventory {
+        dmi: read_dmi(),
+        pci: read_pci(&names.pci),
+        usb: read_usb(&names.usb),
+        sound: read_sound(),
+        input: read_input(),
+    }
+}
+
+fn read_dmi() -> Dmi {
+    let dmi = Path::new("/sys/class/dmi/id");
+    // Vendors leave placeholders in fields they do not use
+    let read = |file: &str| {
+        read_trimmed(dmi.join(file)).filter(|value| {
+            !matches!(
+                value.to_ascii_lowercase().as_str(),
+                "to be filled by o.e.m."
+                    | "default string"
+                    | "not applicable"
+                    | "system product name"
+            )
+        })
+    };
+
+    Dmi {
+        system_vendor: read("sys_vendor"),
+        product_name: read("product_name"),
+        product_version: read("product_version"),
+        board_vendor: read("board_vendor"),
+        board_name: read("board_name"),
+        board_version: read("board_version"),
+        bios_vendor: read("bios_vendor"),
+        bios_version: read("bios_version"),
+        bios_date: read("bios_date"),
+        chassis: read("chassis_type")
+            .and_then(|chassis| chassis.parse::<u8>().ok())
+            .and_then(chassis_name),
+    }
+}
+
+/// Chassis types from the SMBIOS specification, section 7.4.1
+fn chassis_name(chassis: u8) -> Option<&'static str> {
+    Some(match chassis {
+        3 => "Desktop",
+        4 => "Low Profile Desktop",
+        5 => "Pizza Box",
+        6 => "Mini Tower",
+        7 => "Tower",
+        8 => "Portable",
+        9 => "Laptop",
+        10 => "Notebook",
+        11 => "Hand Held",
+        12 => "Docking Station",
+        13 => "All in One",
+        14 => "Sub Notebook",
+        15 => "Space-saving",
+        16 => "Lunch Box",
+        17 => "Main Server Chassis",
+        23 => "Rack Mount Chassis",
+        24 => "Sealed-case PC",
+        30 => "Tablet",
+        31 => "Convertible",
+        32 => "Detachable",
+        33 => "IoT Gateway",
+        34 => "Embedded PC",
+        35 => "Mini PC",
+        36 => "Stick PC",
+        _ => return None,
+    })
+}
+
+/// Functions are nested under the bridge they sit behind, following their sysfs paths like
+/// `/sys/devices/pci0000:00/0000:00:1c.0/0000:03:00.0`
+fn read_pci(names: &IdDatabase) -> Vec<PciDevice> {
+    let devices = list_devices("/sys/bus/pci/devices")
+        .into_iter()
+        .map(|(address, path)| {
+            let id = |file: &str| {
+                read_trimmed(path.join(file))
+                    .map(|id| id.trim_start_matches("0x").to_ascii_lowercase())
+                    .unwrap_or_default()
+            };
+            let (vendor_id, device_id, class_id) = (id("vendor"), id("device"), id("class"));
+            let class = names
+                .class(
+                    class_id.get(0..2).unwrap_or_default(),
+                    class_id.get(2..4).unwrap_or_default(),
+                )
+                .map_or_else(|| format!("Class {class_id}"), str::to_owned);
+            let parent = path
+                .parent()
+                .and_then(|parent| parent.file_name())
+                .map(|parent| parent.to_string_lossy().into_owned());
+
+            let device = PciDevice {
+                vendor: names
+                    .vendor(&vendor_id)
+                    .map_or_else(|| vendor_id.clone(), str::to_owned),
+                device: names
+                    .device(&vendor_id, &device_id)
+                    .map_or_else(|| device_id.clone(), str::to_owned),
+                class,
+                driver: driver(&path),
+                address,
+                children: Vec::new(),
+            };
+            (device, parent)
+        })
+        .collect::<Vec<_>>();
+
+    nest(
+        devices,
+        |device| device.address.clone(),
+        |device| &mut device.children,
+    )
+}
+
+/// Devices like `1-2.1` hang off `1-2`, which hangs off the root hub `usb1`. Interfaces such as
+/// `1-2.1:1.0` are folded into their device.
+fn read_usb(names: &IdDatabase) -> Vec<UsbDevice> {
+    let entries = list_devices("/sys/bus/usb/devices");
+    let devices = entries
+        .iter()
+        .filter(|(port, _)| !port.contains(':'))
+        .map(|(port, path)| {
+            let vendor_id = read_trimmed(path.join("idVendor")).unwrap_or_default();
+            let product_id = read_trimmed(path.join("idProduct")).unwrap_or_default();
+            let parent = match port.rsplit_once(['.', '-']) {
+                Some((parent, _)) if port.contains('.') => Some(parent.to_owned()),
+                Some((bus, _)) => Some(format!("usb{bus}")),
+                None => None,
+            };
+
+            let mut drivers = entries
+                .iter()
+                .filter(|(interface, _)| {
+                    interface
+                        .split_once(':')
+                        .is_some_and(|(device, _)| device == port)
+                })
+                .filter_map(|(_, path)| driver(path))
+                .collect::<Vec<_>>();
+            drivers.sort();
+            drivers.dedup();
+
+            let device = UsbDevice {
+                vendor: read_trimmed(path.join("manufacturer"))
+                    .or_else(|| names.vendor(&vendor_id).map(str::to_owned))
+                    .unwrap_or_else(|| vendor_id.clone()),
+                product: read_trimmed(path.join("product"))
+                    .or_else(|| names.device(&vendor_id, &product_id).map(str::to_owned))
+                    .unwrap_or_else(|| product_id.clone()),
+                id: format!("{vendor_id}:{product_id}"),
+                speed: read_trimmed(path.join("speed")),
+                drivers,
+                port: port.clone(),
+                children: Vec::new(),
+            };
+            (device, parent)
+        })
+        .collect::<Vec<_>>();
+
+    nest(
+        devices,
+        |device| device.port.clone(),
+        |device| &mut device.children,
+    )
+}
+
+/// Lines come in pairs like ` 0 [PCH            ]: HDA-Intel - HDA Intel PCH` followed by a
+/// longer description
+fn read_sound() -> Vec<SoundCard> {
+    let Ok(cards) = fs::read_to_string("/proc/asound/cards") else {
+        return Vec::new();
+    };
+
+    cards
+        .lines()
+        .filter_map(|line| {
+            let (index, rest) = line.trim_start().split_once(' ')?;
+            let index = index.parse().ok()?;
+            let (id, rest) = rest.trim_start().strip_prefix('[')?.split_once(']')?;
+            let name = rest
+                .trim_start_matches(':')
+                .split_once(" - ")
+                .map_or(rest, |(_, name)| name);
+            Some(SoundCard {
+                index,
+                id: id.trim().to_owned(),
+                name: name.trim().to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Devices are blocks of lines such as `N: Name="AT Translated Set 2 keyboard"` and
+/// `H: Handlers=sysrq kbd event0`, separated by blank lines
+fn read_input() -> Vec<InputDevice> {
+    let Ok(devices) = fs::read_to_string("/proc/bus/input/devices") else {
+        return Vec::new();
+    };
+
+    devices
+        .split("\n\n")
+        .filter_map(|block| {
+            let field = |prefix: &str| {
+                block
+                    .lines()
+                    .find_map(|line| line.strip_prefix(prefix))
+                    .map(str::trim)
+            };
+            Some(InputDevice {
+                name: field("N: Name=")?.trim_matches('"').to_owned(),
+                handlers: field("H: Handlers=")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .map(str::to_owned)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Entries of a bus directory by name, with the real device paths they link to
+fn list_devices(bus: &str) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(bus) else {
+        return Vec::new();
+    };
+
+    let mut devices = entries
+        .flatten()
+        .filter_map(|entry| {
+            Some((
+                entry.file_name().to_string_lossy().into_owned(),
+                fs::canonicalize(entry.path()).ok()?,
+            ))
+        })
+        .collect::<Vec<_>>();
+    devices.sort_by(|a, b| a.0.cmp(&b.0));
+    devices
+}
+
+fn driver(path: &Path) -> Option<String> {
+    Some(
+        fs::read_link(path.join("driver"))
+            .ok()?
+            .file_name()?
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+/// Builds a forest from devices paired with the key of their parent. Devices whose parent is
+/// not among them become roots.
+fn nest<D>(
+    devices: Vec<(D, Option<String>)>,
+    key: impl Fn(&D) -> String,
+    children: impl Fn(&mut D) -> &mut Vec<D> + Copy,
+) -> Vec<D> {
+    let keys = devices
+        .iter()
+        .map(|(device, _)| key(device))
+        .collect::<Vec<_>>();
+    let mut by_parent = HashMap::<Option<String>, Vec<D>>::new();
+    for (device, parent) in devices {
+        let parent = parent.filter(|parent| keys.contains(parent));
+        by_parent.entry(parent).or_default().push(device);
+    }
+
+    fn attach<D>(
+        devices: Vec<D>,
+        by_parent: &mut HashMap<Option<String>, Vec<D>>,
+        key: &impl Fn(&D) -> String,
+        children: impl Fn(&mut D) -> &mut Vec<D> + Copy,
+    ) -> Vec<D> {
+        devices
+            .into_iter()
+            .map(|mut device| {
+                let below = by_parent.remove(&Some(key(&device))).unwrap_or_default();
+                *children(&mut device) = attach(below, by_parent, key, children);
+                device
+            })
+            .collect()
+    }
+
+    let roots = by_parent.remove(&None).unwrap_or_default();
+    attach(roots, &mut by_parent, &key, children)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Excerpt of `usb.ids`, whose vendors are followed by classes and several other lists
+    const USB_IDS: &str = "\
+# List of USB ID's
+046d  Logitech, Inc.
+\tc52b  Unifying Receiver
+\t\t0001  Subsystem entries are skipped
+8087  Intel Corp.
+\t0026  AX201 Bluetooth
+C 03  Human Interface Device
+\t01  Boot Interface Subclass
+\t\t02  Mouse
+C e0  Wireless
+\t01  Radio Frequency
+AT 0001  Audio Terminal
+\t0100  USB Undefined
+HID 00  Undefined
+R 00  Undefined
+BIAS 0  Not Biased
+PHY 00  None
+HUT 01  Generic Desktop Controls
+\t000  Undefined
+\t001  Pointer
+L 0436  Afrikaans
+\t01  Afrikaans
+HCC 00  Not supported
+VT 0000  Undefined
+";
+
+    #[test]
+    fn parses_vendors_and_classes() {
+        let database = IdDatabase::parse(USB_IDS);
+
+        assert_eq!(database.vendor("046d"), Some("Logitech, Inc."));
+        assert_eq!(database.device("046d", "c52b"), Some("Unifying Receiver"));
+        assert_eq!(database.device("8087", "0026"), Some("AX201 Bluetooth"));
+        assert_eq!(database.class("03", "01"), Some("Boot Interface Subclass"));
+        // Unknown subclasses fall back to the class
+        assert_eq!(database.class("e0", "02"), Some("Wireless"));
+    }
+
+    #[test]
+    fn skips_lists_after_the_vendors() {
+        let database = IdDatabase::parse(USB_IDS);
+
+        assert_eq!(database.vendors.len(), 2);
+        assert_eq!(database.classes.len(), 2);
+        // Entries of skipped lists are not added to the vendor or class before them
+        assert!(!database.classes["e0"].1.contains_key("0100"));
+        assert_eq!(database.device("8087", "01"), None);
+    }
+}
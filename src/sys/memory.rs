@@ -0,0 +1,236 @@
+use std::{collections::HashMap, fs};
+
+/// Where physical memory goes, split into parts that do not overlap
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MemoryComposition {
+    pub total: u64,
+    /// Memory held by processes and the kernel that fits none of the other parts
+    pub used: u64,
+    pub page_tables: u64,
+    pub slab: u64,
+    pub huge_pages: u64,
+    /// Shared memory and tmpfs, which the kernel accounts as cache
+    pub shared: u64,
+    /// Cache waiting to be written back, or being written back
+    pub dirty: u64,
+    /// Clean page cache that can be dropped
+    pub cached: u64,
+    pub buffers: u64,
+    pub free: u64,
+    /// Estimate of what can be allocated without swapping, overlaps cache and free
+    pub available: u64,
+}
+
+impl MemoryComposition {
+    /// Sizes in stacking order, from used to buffers, free memory is left out
+    pub fn values(&self) -> [u64; 8] {
+        [
+            self.used,
+            self.page_tables,
+            self.slab,
+            self.huge_pages,
+            self.shared,
+            self.dirty,
+            self.cached,
+            self.buffers,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SwapInfo {
+    pub total: u64,
+    pub used: u64,
+    /// Swapped out pages that are also still in memory
+    pub cached: u64,
+    pub zram: Option<Compression>,
+    pub zswap: Option<Compression>,
+}
+
+/// Compressed memory, in zram devices or the zswap pool
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Compression {
+    pub original_bytes: u64,
+    pub compressed_bytes: u64,
+}
+
+impl Compression {
+    pub fn ratio(&self) -> f64 {
+        self.original_bytes as f64 / self.compressed_bytes.max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryDetails {
+    pub composition: MemoryComposition,
+    pub swap: SwapInfo,
+}
+
+pub fn read() -> Option<MemoryDetails> {
+    let meminfo = parse_meminfo(&fs::read_to_string("/proc/meminfo").ok()?);
+    Some(details(&meminfo, read_zram()))
+}
+
+fn details(meminfo: &HashMap<String, u64>, zram: Option<Compression>) -> MemoryDetails {
+    let field = |key: &str| meminfo.get(key).copied().unwrap_or_default();
+
+    let total = field("MemTotal");
+    let free = field("MemFree");
+    let buffers = field("Buffers");
+    let cached = field("Cached");
+    let shared = field("Shmem");
+    let dirty = field("Dirty") + field("Writeback");
+    let slab = field("Slab");
+    let page_tables = field("PageTables");
+    // Hugetlb also counts gigantic pages, older kernels only have the default size
+    let huge_pages = meminfo
+        .get("Hugetlb")
+        .copied()
+        .unwrap_or_else(|| field("HugePages_Total") * field("Hugepagesize"));
+
+    let composition = MemoryComposition {
+        total,
+        used: total.saturating_sub(free + buffers + cached + slab + page_tables + huge_pages),
+        page_tables,
+        slab,
+        huge_pages,
+        shared: shared.min(cached),
+        dirty: dirty.min(cached.saturating_sub(shared)),
+        cached: cached.saturating_sub(shared + dirty),
+        buffers,
+        free,
+        available: field("MemAvailable"),
+    };
+
+    let zswap = meminfo.get("Zswapped").map(|original| Compression {
+        original_bytes: *original,
+        compressed_bytes: field("Zswap"),
+    });
+
+    MemoryDetails {
+        composition,
+        swap: SwapInfo {
+            total: field("SwapTotal"),
+            used: field("SwapTotal").saturating_sub(field("SwapFree")),
+            cached: field("SwapCached"),
+            // Nothing to show until something has been compressed
+            zram: zram.filter(|zram| zram.original_bytes > 0),
+            zswap: zswap.filter(|zswap| zswap.original_bytes > 0),
+        },
+    }
+}
+
+/// Values of `/proc/meminfo` in bytes, except for page counts
+fn parse_meminfo(meminfo: &str) -> HashMap<String, u64> {
+    meminfo
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let mut parts = value.split_whitespace();
+            let amount = parts.next()?.parse::<u64>().ok()?;
+            let amount = match parts.next() {
+                Some("kB") => amount * 1024,
+                _ => amount,
+            };
+            Some((key.to_owned(), amount))
+        })
+        .collect()
+}
+
+/// Sums the data stored in every zram device
+fn read_zram() -> Option<Compression> {
+    let devices = fs::read_dir("/sys/block")
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("zram"))
+        .filter_map(|entry| parse_mm_stat(&fs::read_to_string(entry.path().join("mm_stat")).ok()?))
+        .collect::<Vec<_>>();
+
+    if devices.is_empty() {
+        return None;
+    }
+    Some(Compression {
+        original_bytes: devices.iter().map(|zram| zram.original_bytes).sum(),
+        compressed_bytes: devices.iter().map(|zram| zram.compressed_bytes).sum(),
+    })
+}
+
+/// The first two fields of a zram device's `mm_stat` are the original and compressed sizes in
+/// bytes
+fn parse_mm_stat(stat: &str) -> Option<Compression> {
+    let mut fields = stat.split_whitespace();
+    Some(Compression {
+        original_bytes: fields.next()?.parse().ok()?,
+        compressed_bytes: fields.next()?.parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEMINFO: &str = "\
+MemTotal:       16000000 kB
+MemFree:         2000000 kB
+MemAvailable:    9000000 kB
+Buffers:          500000 kB
+Cached:          6000000 kB
+SwapCached:        10000 kB
+Shmem:           1000000 kB
+Slab:             800000 kB
+PageTables:       100000 kB
+Dirty:              5000 kB
+Writeback:          1000 kB
+SwapTotal:       8000000 kB
+SwapFree:        7000000 kB
+Zswap:            250000 kB
+Zswapped:        1000000 kB
+HugePages_Total:       0
+Hugepagesize:       2048 kB
+Hugetlb:          400000 kB";
+
+    #[test]
+    fn breaks_memory_down() {
+        let details = details(&parse_meminfo(MEMINFO), None);
+        let composition = details.composition;
+
+        let kib = |kib: u64| kib * 1024;
+        assert_eq!(composition.total, kib(16000000));
+        assert_eq!(composition.used, kib(6200000));
+        assert_eq!(composition.huge_pages, kib(400000));
+        assert_eq!(composition.shared, kib(1000000));
+        assert_eq!(composition.dirty, kib(6000));
+        assert_eq!(composition.cached, kib(4994000));
+        assert_eq!(composition.available, kib(9000000));
+        // The parts and free memory add up to the total
+        assert_eq!(
+            composition.values().iter().sum::<u64>() + composition.free,
+            composition.total
+        );
+    }
+
+    #[test]
+    fn reads_swap_and_compression() {
+        let zram = parse_mm_stat(
+            "  4096000  1024000  1100000        0  1200000      120     5000     3000\n",
+        );
+        let swap = details(&parse_meminfo(MEMINFO), zram).swap;
+
+        assert_eq!(swap.used, 1000000 * 1024);
+        assert_eq!(swap.cached, 10000 * 1024);
+        assert_eq!(swap.zram.unwrap().ratio(), 4.0);
+        assert_eq!(swap.zswap.unwrap().ratio(), 4.0);
+    }
+
+    #[test]
+    fn leaves_out_unused_compression() {
+        let zram = parse_mm_stat("0 0 0 0 0 0 0 0");
+        let swap = details(
+            &parse_meminfo("SwapTotal: 0 kB\nZswap: 0 kB\nZswapped: 0 kB"),
+            zram,
+        )
+        .swap;
+
+        assert_eq!((swap.zram, swap.zswap), (None, None));
+    }
+}
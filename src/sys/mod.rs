@@ -0,0 +1,32 @@
+//! Information the daemon does not report, read directly from sysfs and procfs
+
+pub mod block;
+pub mod boot;
+pub mod cpu;
+pub mod cpufreq;
+pub mod dimm;
+pub mod diskstats;
+pub mod diskusage;
+pub mod drm;
+pub mod hardware;
+pub mod memory;
+pub mod mounts;
+pub mod net;
+pub mod pci;
+pub mod pressure;
+pub mod sessions;
+pub mod smart;
+pub mod sockets;
+pub mod topology;
+pub mod traffic;
+pub mod vmstat;
+
+use std::{fs, path::Path};
+
+/// Reads a single value file, ignoring surrounding whitespace
+fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
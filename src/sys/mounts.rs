@@ -0,0 +1,148 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use zbus::zvariant::{OwnedObjectPath, Value};
+
+/// A process keeping a filesystem busy
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holder {
+    pub pid: u32,
+    pub name: String,
+    /// One of the paths it holds open below the mount point
+    pub path: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum UnmountError {
+    /// Files on the filesystem are still open
+    Busy,
+    Failed(String),
+}
+
+/// Mounts a filesystem where UDisks decides, usually below `/run/media`
+pub async fn mount(device: String) -> Result<(), String> {
+    udisksctl(&["mount", "-b", &device]).await
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.UDisks2.Manager",
+    default_service = "org.freedesktop.UDisks2",
+    default_path = "/org/freedesktop/UDisks2/Manager"
+)]
+trait UDisksManager {
+    /// Objects of the block devices matching a device file, symlinks included
+    fn resolve_device(
+        &self,
+        devspec: HashMap<&str, Value<'_>>,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<Vec<OwnedObjectPath>>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.UDisks2.Filesystem",
+    default_service = "org.freedesktop.UDisks2"
+)]
+trait UDisksFilesystem {
+    fn unmount(&self, options: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+}
+
+const DEVICE_BUSY: &str = "org.freedesktop.UDisks2.Error.DeviceBusy";
+
+/// Tells a busy filesystem apart by the name of the error UDisks replies with
+pub async fn unmount(device: String) -> Result<(), UnmountError> {
+    let failed = |err: zbus::Error| UnmountError::Failed(err.to_string());
+    let connection = zbus::Connection::system().await.map_err(failed)?;
+    let block = UDisksManagerProxy::new(&connection)
+        .await
+        .map_err(failed)?
+        .resolve_device(
+            HashMap::from([("path", Value::from(device.as_str()))]),
+            HashMap::new(),
+        )
+        .await
+        .map_err(failed)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| UnmountError::Failed(format!("{device} is not known to UDisks")))?;
+
+    UDisksFilesystemProxy::builder(&connection)
+        .path(block)
+        .map_err(failed)?
+        .build()
+        .await
+        .map_err(failed)?
+        .unmount(HashMap::new())
+        .await
+        .map_err(|err| match err {
+            zbus::Error::MethodError(name, ..) if name.as_str() == DEVICE_BUSY => {
+                UnmountError::Busy
+            }
+            zbus::Error::MethodError(_, Some(detail), _) => UnmountError::Failed(detail),
+            err => failed(err),
+        })
+}
+
+/// Powers off a whole disk so it can be unplugged, its filesystems have to be unmounted first
+pub async fn power_off(disk: String) -> Result<(), String> {
+    udisksctl(&["power-off", "-b", &disk]).await
+}
+
+/// UDisks asks for authorization through polkit when needed
+async fn udisksctl(args: &[&str]) -> Result<(), String> {
+    let output = tokio::process::Command::new("udisksctl")
+        .args(args)
+        .output()
+        .await
+        .map_err(|err| err.to_string())?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_owned())
+    }
+}
+
+/// Processes with open files, a working directory or a root below the mount point. Other
+/// users' processes are only found when running as root.
+pub fn holders(mount_point: &str) -> Vec<Holder> {
+    let mount_point = Path::new(mount_point);
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    let mut holders = Vec::new();
+    for process in processes.flatten() {
+        let Some(pid) = process
+            .file_name()
+            .to_str()
+            .and_then(|pid| pid.parse().ok())
+        else {
+            continue;
+        };
+
+        let links = ["cwd", "root", "exe"]
+            .into_iter()
+            .map(|link| process.path().join(link))
+            .chain(
+                fs::read_dir(process.path().join("fd"))
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|descriptor| descriptor.path()),
+            );
+        let held = links
+            .filter_map(|link| fs::read_link(link).ok())
+            .find(|target| target.starts_with(mount_point));
+
+        if let Some(path) = held {
+            holders.push(Holder {
+                pid,
+                name: fs::read_to_string(process.path().join("comm"))
+                    .map(|comm| comm.trim().to_owned())
+                    .unwrap_or_default(),
+                path: path.to_string_lossy().into_owned(),
+            });
+        }
+    }
+
+    holders
+}
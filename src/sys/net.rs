@@ -0,0 +1,364 @@
+use std::{
+    collections::HashMap,
+    fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::Path,
+    process::Command,
+};
+
+use zbus::zvariant::OwnedObjectPath;
+
+use super::read_trimmed;
+
+/// How an interface is addressed and connected
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Addressing {
+    /// Addresses with their prefix length
+    pub addresses: Vec<(IpAddr, u8)>,
+    pub gateways: Vec<IpAddr>,
+    pub dns_servers: Vec<IpAddr>,
+    /// Negotiated speed in Mbit/s, missing while the link is down or for virtual interfaces
+    pub speed_mbps: Option<u32>,
+    pub duplex: Option<String>,
+}
+
+/// Current association of a wireless interface
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WirelessLink {
+    pub ssid: Option<String>,
+    /// Signal quality as NetworkManager rates it, from 0 to 100
+    pub signal_percent: Option<u8>,
+    /// Bitrate in Mbit/s
+    pub bitrate_mbps: Option<f32>,
+    pub frequency_mhz: Option<u32>,
+}
+
+impl WirelessLink {
+    /// Frequency band in GHz, e.g. `2.4`
+    pub fn band(&self) -> Option<&'static str> {
+        match self.frequency_mhz? {
+            2400..=2500 => Some("2.4"),
+            4900..=5899 => Some("5"),
+            5925..=7125 => Some("6"),
+            58000..=71000 => Some("60"),
+            _ => None,
+        }
+    }
+}
+
+/// What kind of device an interface is
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InterfaceKind {
+    pub loopback: bool,
+    /// Software interfaces such as bridges, veth pairs, tunnels and VPNs
+    pub is_virtual: bool,
+    /// Bridge the interface is attached to
+    pub bridge: Option<String>,
+}
+
+/// Kinds of every interface, keyed by interface name
+pub fn read_kinds() -> HashMap<String, InterfaceKind> {
+    let Ok(entries) = fs::read_dir("/sys/class/net") else {
+        return HashMap::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let device = entry.path();
+            // ARPHRD_LOOPBACK from `include/uapi/linux/if_arp.h`
+            let loopback = read_trimmed(device.join("type")).as_deref() == Some("772");
+            // Only interfaces backed by hardware link to a parent device
+            let is_virtual = !loopback && !device.join("device").exists();
+            let bridge = fs::read_link(device.join("master"))
+                .ok()
+                .and_then(|master| Some(master.file_name()?.to_string_lossy().into_owned()))
+                .filter(|master| {
+                    Path::new("/sys/class/net")
+                        .join(master)
+                        .join("bridge")
+                        .exists()
+                });
+            (
+                name,
+                InterfaceKind {
+                    loopback,
+                    is_virtual,
+                    bridge,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Addressing of every interface, keyed by interface name, given the DNS servers
+/// systemd-resolved has for each interface index
+pub fn read_addressing(link_dns: HashMap<i32, Vec<IpAddr>>) -> HashMap<String, Addressing> {
+    // Interfaces without any address still have link details
+    let mut interfaces = fs::read_dir("/sys/class/net")
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    (name, Addressing::default())
+                })
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+
+    for (name, address) in ipv4_addresses().into_iter().chain(ipv6_addresses()) {
+        interfaces.entry(name).or_default().addresses.push(address);
+    }
+    for (name, gateway) in ipv4_gateways().into_iter().chain(ipv6_gateways()) {
+        interfaces.entry(name).or_default().gateways.push(gateway);
+    }
+
+    let global_dns = resolv_conf_servers();
+    for (name, addressing) in interfaces.iter_mut() {
+        let device = Path::new("/sys/class/net").join(name);
+        addressing.dns_servers = read_trimmed(device.join("ifindex"))
+            .and_then(|index| link_dns.get(&index.parse().ok()?).cloned())
+            .unwrap_or_else(|| global_dns.clone());
+
+        // Reading the speed of an interface without a link fails or returns -1
+        addressing.speed_mbps = read_trimmed(device.join("speed"))
+            .and_then(|speed| speed.parse::<i64>().ok())
+            .filter(|speed| *speed > 0)
+            .map(|speed| speed as u32);
+        addressing.duplex =
+            read_trimmed(device.join("duplex")).filter(|duplex| duplex != "unknown");
+    }
+
+    interfaces
+}
+
+/// The kernel keeps no file with IPv4 addresses, so they come from iproute2
+fn ipv4_addresses() -> Vec<(String, (IpAddr, u8))> {
+    let Ok(output) = Command::new("ip")
+        .args(["-o", "-4", "addr", "show"])
+        .output()
+    else {
+        return Vec::new();
+    };
+
+    // Lines look like `2: eth0    inet 192.168.1.5/24 brd 192.168.1.255 scope global eth0`
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1);
+            // Virtual pairs are listed as `veth0@if5`
+            let name = fields.next()?.split('@').next()?.to_owned();
+            let (address, prefix) = fields
+                .skip_while(|field| *field != "inet")
+                .nth(1)?
+                .split_once('/')?;
+            Some((name, (address.parse().ok()?, prefix.parse().ok()?)))
+        })
+        .collect()
+}
+
+/// Lines in `/proc/net/if_inet6` are `address index prefix scope flags name`, all in hex
+fn ipv6_addresses() -> Vec<(String, (IpAddr, u8))> {
+    let Ok(content) = fs::read_to_string("/proc/net/if_inet6") else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let address = parse_ipv6_hex(fields.first()?)?;
+            let prefix = u8::from_str_radix(fields.get(2)?, 16).ok()?;
+            Some((fields.get(5)?.to_string(), (IpAddr::V6(address), prefix)))
+        })
+        .collect()
+}
+
+/// Default routes from `/proc/net/route`, where addresses are little endian hex
+fn ipv4_gateways() -> Vec<(String, IpAddr)> {
+    let Ok(content) = fs::read_to_string("/proc/net/route") else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let destination = u32::from_str_radix(fields.get(1)?, 16).ok()?;
+            let mask = u32::from_str_radix(fields.get(7)?, 16).ok()?;
+            if destination != 0 || mask != 0 {
+                return None;
+            }
+            let gateway = u32::from_str_radix(fields.get(2)?, 16).ok()?;
+            let gateway = Ipv4Addr::from(gateway.to_le_bytes());
+            Some((fields.first()?.to_string(), IpAddr::V4(gateway)))
+        })
+        .collect()
+}
+
+/// Default routes from `/proc/net/ipv6_route`, which has a zero destination and prefix
+fn ipv6_gateways() -> Vec<(String, IpAddr)> {
+    let Ok(content) = fs::read_to_string("/proc/net/ipv6_route") else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let name = fields.get(9)?;
+            if *fields.first()? != "0".repeat(32) || *fields.get(1)? != "00" || *name == "lo" {
+                return None;
+            }
+            let gateway = parse_ipv6_hex(fields.get(4)?)?;
+            (!gateway.is_unspecified()).then(|| (name.to_string(), IpAddr::V6(gateway)))
+        })
+        .collect()
+}
+
+fn parse_ipv6_hex(hex: &str) -> Option<Ipv6Addr> {
+    u128::from_str_radix(hex, 16).ok().map(Ipv6Addr::from)
+}
+
+fn resolv_conf_servers() -> Vec<IpAddr> {
+    fs::read_to_string("/etc/resolv.conf")
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix("nameserver"))
+                .filter_map(|server| server.trim().parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.resolve1.Manager",
+    default_service = "org.freedesktop.resolve1",
+    default_path = "/org/freedesktop/resolve1"
+)]
+trait Resolved {
+    /// Servers as interface index, address family and address bytes, index 0 being global
+    #[zbus(property, name = "DNS")]
+    fn dns(&self) -> zbus::Result<Vec<(i32, i32, Vec<u8>)>>;
+}
+
+/// With systemd-resolved, resolv.conf only points at the local stub, the real servers are per
+/// link. Keyed by interface index, empty when resolved is not running.
+pub async fn resolved_link_servers(connection: &zbus::Connection) -> HashMap<i32, Vec<IpAddr>> {
+    let Ok(resolved) = ResolvedProxy::new(connection).await else {
+        return HashMap::new();
+    };
+    let Ok(servers) = resolved.dns().await else {
+        return HashMap::new();
+    };
+
+    let mut links = HashMap::<i32, Vec<IpAddr>>::new();
+    for (index, _, address) in servers.into_iter().filter(|(index, ..)| *index > 0) {
+        let address = if let Ok(octets) = <[u8; 4]>::try_from(address.as_slice()) {
+            IpAddr::from(octets)
+        } else if let Ok(octets) = <[u8; 16]>::try_from(address.as_slice()) {
+            IpAddr::from(octets)
+        } else {
+            continue;
+        };
+        links.entry(index).or_default().push(address);
+    }
+    links
+}
+
+/// Whether the interface is a wireless one, which only wireless drivers mark in sysfs
+pub fn is_wireless(name: &str) -> bool {
+    Path::new("/sys/class/net")
+        .join(name)
+        .join("wireless")
+        .exists()
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.NetworkManager",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager"
+)]
+trait NetworkManager {
+    fn get_device_by_ip_iface(&self, iface: &str) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.NetworkManager.Device.Wireless",
+    default_service = "org.freedesktop.NetworkManager"
+)]
+trait WirelessDevice {
+    #[zbus(property)]
+    fn active_access_point(&self) -> zbus::Result<OwnedObjectPath>;
+
+    /// In kbit/s
+    #[zbus(property)]
+    fn bitrate(&self) -> zbus::Result<u32>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.NetworkManager.AccessPoint",
+    default_service = "org.freedesktop.NetworkManager"
+)]
+trait AccessPoint {
+    #[zbus(property)]
+    fn ssid(&self) -> zbus::Result<Vec<u8>>;
+
+    #[zbus(property)]
+    fn strength(&self) -> zbus::Result<u8>;
+
+    #[zbus(property)]
+    fn frequency(&self) -> zbus::Result<u32>;
+}
+
+/// Association details from NetworkManager, `None` when not connected or the interface is not
+/// managed by it
+pub async fn read_wireless(connection: &zbus::Connection, name: &str) -> Option<WirelessLink> {
+    let device = NetworkManagerProxy::new(connection)
+        .await
+        .ok()?
+        .get_device_by_ip_iface(name)
+        .await
+        .ok()?;
+    // Properties are polled, so there is no point in caching them
+    let device = WirelessDeviceProxy::builder(connection)
+        .path(device)
+        .ok()?
+        .cache_properties(zbus::proxy::CacheProperties::No)
+        .build()
+        .await
+        .ok()?;
+
+    // Not being associated is reported as the root path
+    let access_point = device.active_access_point().await.ok()?;
+    if access_point.as_str() == "/" {
+        return None;
+    }
+    let access_point = AccessPointProxy::builder(connection)
+        .path(access_point)
+        .ok()?
+        .cache_properties(zbus::proxy::CacheProperties::No)
+        .build()
+        .await
+        .ok()?;
+
+    Some(WirelessLink {
+        ssid: access_point
+            .ssid()
+            .await
+            .ok()
+            .map(|ssid| String::from_utf8_lossy(&ssid).into_owned()),
+        signal_percent: access_point.strength().await.ok(),
+        bitrate_mbps: device
+            .bitrate()
+            .await
+            .ok()
+            .filter(|bitrate| *bitrate > 0)
+            .map(|bitrate| bitrate as f32 / 1000.0),
+        frequency_mhz: access_point.frequency().await.ok(),
+    })
+}
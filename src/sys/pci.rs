@@ -0,0 +1,92 @@
+use std::fs;
+
+use super::read_trimmed;
+
+/// A DRM card and the PCI function backing it
+#[derive(Debug, Clone)]
+pub struct GpuCard {
+    /// PCI bus address, e.g. `0000:01:00.0`
+    pub address: String,
+    /// PCI vendor ID, e.g. `0x10de`
+    pub vendor_id: String,
+    /// Marketing name, where the driver exposes the one the daemon reports
+    pub model: Option<String>,
+    /// Dedicated memory, where the driver exposes it
+    pub vram_total_bytes: Option<u64>,
+}
+
+/// Lists the GPUs known to DRM, sorted by PCI bus address
+pub fn gpu_cards() -> Vec<GpuCard> {
+    let Ok(entries) = fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+
+    let mut cards = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .strip_prefix("card")
+                .is_some_and(|index| index.chars().all(|c| c.is_ascii_digit()))
+        })
+        .filter_map(|entry| {
+            let device = entry.path().join("device");
+            let address = fs::canonicalize(&device)
+                .ok()?
+                .file_name()?
+                .to_string_lossy()
+                .into_owned();
+            let vendor_id = read_trimmed(device.join("vendor"))?;
+            // Only the proprietary NVIDIA driver names its GPUs the way NVML does
+            let model = nvidia_model(&address);
+            let vram_total_bytes =
+                read_trimmed(device.join("mem_info_vram_total")).and_then(|vram| vram.parse().ok());
+            Some(GpuCard {
+                address,
+                vendor_id,
+                model,
+                vram_total_bytes,
+            })
+        })
+        .collect::<Vec<GpuCard>>();
+    cards.sort_by(|a, b| a.address.cmp(&b.address));
+    cards.dedup_by(|a, b| a.address == b.address);
+    cards
+}
+
+/// Lines like `Model: 		 NVIDIA GeForce RTX 3080`
+fn nvidia_model(address: &str) -> Option<String> {
+    fs::read_to_string(format!("/proc/driver/nvidia/gpus/{address}/information"))
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("Model:"))
+        .map(|model| model.trim().to_owned())
+}
+
+/// Brings a bus address to the form sysfs uses, e.g. `0000:01:00.0`. NVML pads the domain to
+/// eight digits and writes hex digits in upper case.
+pub fn normalize_address(address: &str) -> String {
+    let address = address.trim().to_ascii_lowercase();
+    match address.split_once(':') {
+        Some((domain, rest)) if domain.len() > 4 && domain.is_ascii() => {
+            format!("{}:{rest}", &domain[domain.len() - 4..])
+        }
+        _ => address,
+    }
+}
+
+/// Whether a vendor name as reported by the daemon belongs to a PCI vendor ID, `None` when
+/// the vendor ID is not one we know the names of
+pub fn vendor_matches(vendor_id: &str, vendor: &str) -> Option<bool> {
+    let vendor = vendor.to_ascii_lowercase();
+    Some(match vendor_id {
+        "0x10de" => vendor.contains("nvidia"),
+        "0x1002" => vendor.contains("amd") || vendor.contains("advanced micro devices"),
+        "0x8086" => vendor.contains("intel"),
+        "0x1af4" => vendor.contains("virtio") || vendor.contains("red hat"),
+        "0x15ad" => vendor.contains("vmware"),
+        "0x5143" => vendor.contains("qualcomm"),
+        _ => return None,
+    })
+}
@@ -0,0 +1,186 @@
+use std::{fs, path::Path};
+
+/// Share of time some or all tasks were stalled on a resource, from the kernel's PSI accounting
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pressure {
+    pub some: PressureAverages,
+    /// Missing for CPU pressure on kernels before 5.13
+    pub full: Option<PressureAverages>,
+}
+
+/// Running averages over 10 seconds, 1 minute and 5 minutes, in percent
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PressureAverages {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+}
+
+/// Pressure of each resource, missing when the kernel is built without PSI
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SystemPressure {
+    pub cpu: Option<Pressure>,
+    pub memory: Option<Pressure>,
+    pub io: Option<Pressure>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CgroupPressure {
+    /// Path below the cgroup root, e.g. `system.slice/cups.service`
+    pub path: String,
+    pub pressure: SystemPressure,
+}
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+pub fn read() -> SystemPressure {
+    SystemPressure {
+        cpu: read_pressure("/proc/pressure/cpu"),
+        memory: read_pressure("/proc/pressure/memory"),
+        io: read_pressure("/proc/pressure/io"),
+    }
+}
+
+/// Pressure of the top two levels of the cgroup v2 hierarchy, i.e. slices and their units
+pub fn read_cgroups() -> Vec<CgroupPressure> {
+    let mut cgroups = Vec::new();
+    for slice in child_cgroups(Path::new(CGROUP_ROOT)) {
+        for unit in child_cgroups(&slice) {
+            cgroups.extend(read_cgroup(&unit));
+        }
+        cgroups.extend(read_cgroup(&slice));
+    }
+    cgroups
+}
+
+fn child_cgroups(path: &Path) -> Vec<std::path::PathBuf> {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn read_cgroup(path: &Path) -> Option<CgroupPressure> {
+    let pressure = SystemPressure {
+        cpu: read_pressure(path.join("cpu.pressure")),
+        memory: read_pressure(path.join("memory.pressure")),
+        io: read_pressure(path.join("io.pressure")),
+    };
+    if pressure == SystemPressure::default() {
+        return None;
+    }
+
+    Some(CgroupPressure {
+        path: path
+            .strip_prefix(CGROUP_ROOT)
+            .ok()?
+            .to_string_lossy()
+            .into_owned(),
+        pressure,
+    })
+}
+
+fn read_pressure(path: impl AsRef<Path>) -> Option<Pressure> {
+    parse_pressure(&fs::read_to_string(path).ok()?)
+}
+
+/// Parses lines such as `some avg10=0.31 avg60=0.12 avg300=0.05 total=1234`
+fn parse_pressure(content: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = None;
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+        let (mut avg10, mut avg60, mut avg300) = (None, None, None);
+        for field in fields {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+            let value = value.parse::<f32>().ok();
+            match key {
+                "avg10" => avg10 = value,
+                "avg60" => avg60 = value,
+                "avg300" => avg300 = value,
+                _ => {}
+            }
+        }
+        // Lines with malformed averages are left out rather than shown as no pressure
+        let (Some(avg10), Some(avg60), Some(avg300)) = (avg10, avg60, avg300) else {
+            continue;
+        };
+        let averages = PressureAverages {
+            avg10,
+            avg60,
+            avg300,
+        };
+        match kind {
+            Some("some") => some = Some(averages),
+            Some("full") => full = Some(averages),
+            _ => {}
+        }
+    }
+
+    Some(Pressure { some: some?, full })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_some_and_full() {
+        let pressure = parse_pressure(
+            "\
+some avg10=1.53 avg60=0.87 avg300=0.21 total=46231847
+full avg10=0.40 avg60=0.12 avg300=0.03 total=12003391
+",
+        );
+
+        assert_eq!(
+            pressure,
+            Some(Pressure {
+                some: PressureAverages {
+                    avg10: 1.53,
+                    avg60: 0.87,
+                    avg300: 0.21,
+                },
+                full: Some(PressureAverages {
+                    avg10: 0.40,
+                    avg60: 0.12,
+                    avg300: 0.03,
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn cpu_before_full_was_added() {
+        let pressure = parse_pressure("some avg10=12.50 avg60=8.01 avg300=3.66 total=998877665\n");
+
+        assert_eq!(pressure.map(|pressure| pressure.some.avg10), Some(12.5));
+        assert_eq!(pressure.and_then(|pressure| pressure.full), None);
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let pressure = parse_pressure(
+            "\
+some avg10=0.50 avg60=0.25 avg300=0.10 total=12
+full avg10=0.50 avg60=n/a avg300=0.10 total=4
+",
+        );
+
+        assert_eq!(pressure.map(|pressure| pressure.some.avg60), Some(0.25));
+        assert_eq!(pressure.and_then(|pressure| pressure.full), None);
+        // Without the line for some tasks there is nothing to show
+        assert_eq!(
+            parse_pressure("some avg10=0.50 avg60=0.25 total=12\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use zbus::zvariant::OwnedObjectPath;
+
+/// A login session as tracked by logind
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub id: String,
+    pub user: String,
+    pub seat: Option<String>,
+    pub tty: Option<String>,
+    /// Host the user logged in from, for remote sessions such as SSH
+    pub remote_host: Option<String>,
+    /// Kind of session, e.g. `wayland`, `x11` or `tty`
+    pub kind: String,
+    /// `online`, `active` or `closing`
+    pub state: String,
+    /// Missing while the session is in use
+    pub idle: Option<Duration>,
+}
+
+/// Session ID, user ID, user name, seat and object path
+type SessionEntry = (String, u32, String, String, OwnedObjectPath);
+
+#[zbus::proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+trait LoginManager {
+    fn list_sessions(&self) -> zbus::Result<Vec<SessionEntry>>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1"
+)]
+trait LoginSession {
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn name(&self) -> zbus::Result<String>;
+
+    /// Seat name and object path, both empty for sessions without a seat
+    #[zbus(property)]
+    fn seat(&self) -> zbus::Result<(String, OwnedObjectPath)>;
+
+    #[zbus(property, name = "TTY")]
+    fn tty(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn remote_host(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "Type")]
+    fn kind(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn idle_hint(&self) -> zbus::Result<bool>;
+
+    /// Wall clock time the session went idle, in microseconds since the epoch
+    #[zbus(property)]
+    fn idle_since_hint(&self) -> zbus::Result<u64>;
+}
+
+pub async fn sessions(connection: &zbus::Connection) -> zbus::Result<Vec<Session>> {
+    let list = LoginManagerProxy::new(connection)
+        .await?
+        .list_sessions()
+        .await?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let mut sessions = Vec::with_capacity(list.len());
+    for (.., path) in list {
+        // Properties are polled, so there is no point in caching them
+        let session = LoginSessionProxy::builder(connection)
+            .path(path)?
+            .cache_properties(zbus::proxy::CacheProperties::No)
+            .build()
+            .await?;
+        // Sessions may close between listing them and reading their properties
+        let Ok(id) = session.id().await else {
+            continue;
+        };
+
+        sessions.push(Session {
+            id,
+            user: session.name().await.unwrap_or_default(),
+            seat: session
+                .seat()
+                .await
+                .ok()
+                .and_then(|(seat, _)| non_empty(seat)),
+            tty: session.tty().await.ok().and_then(non_empty),
+            remote_host: session.remote_host().await.ok().and_then(non_empty),
+            kind: session.kind().await.unwrap_or_default(),
+            state: session.state().await.unwrap_or_default(),
+            idle: idle(
+                session.idle_hint().await.unwrap_or_default(),
+                session.idle_since_hint().await.unwrap_or_default(),
+                now,
+            ),
+        });
+    }
+
+    Ok(sessions)
+}
+
+/// logind reports missing values as empty strings
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+/// How long a session has been idle, given the wall clock time it went idle in microseconds
+fn idle(hint: bool, since: u64, now: Duration) -> Option<Duration> {
+    (hint && since > 0).then(|| now.saturating_sub(Duration::from_micros(since)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_sessions() {
+        let now = Duration::from_secs(1_792_238_403);
+        let since = now.saturating_sub(Duration::from_secs(600)).as_micros() as u64;
+
+        assert_eq!(idle(true, since, now), Some(Duration::from_secs(600)));
+        // The time of going idle is kept after the session is in use again
+        assert_eq!(idle(false, since, now), None);
+        // Sessions that never went idle report the epoch
+        assert_eq!(idle(true, 0, now), None);
+    }
+
+    #[test]
+    fn empty_values_are_missing() {
+        assert_eq!(non_empty(String::new()), None);
+        assert_eq!(non_empty("seat0".to_owned()).as_deref(), Some("seat0"));
+    }
+}
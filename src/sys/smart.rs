@@ -0,0 +1,496 @@
+use serde_json::Value;
+
+/// How worrying a health attribute is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Failing,
+}
+
+/// An ATA SMART attribute or an NVMe health log entry. NVMe entries only have a raw value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmartAttribute {
+    pub id: Option<u8>,
+    pub name: String,
+    pub value: Option<u8>,
+    pub worst: Option<u8>,
+    pub threshold: Option<u8>,
+    pub raw: String,
+    pub severity: Severity,
+}
+
+/// An entry of the self-test log, latest first
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfTest {
+    pub description: String,
+    pub status: String,
+    /// Power-on hours when the test ran
+    pub lifetime_hours: Option<u64>,
+}
+
+/// Progress of a self-test that is still running
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunningTest {
+    /// ATA drives report how much of the test is left, in percent
+    Remaining(u64),
+    /// NVMe drives report the kind of test and how much of it is done, in percent
+    Completed { operation: String, percent: u64 },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SmartReport {
+    /// Whether the drive passes its own overall health assessment
+    pub passed: Option<bool>,
+    pub attributes: Vec<SmartAttribute>,
+    pub power_on_hours: Option<u64>,
+    /// Share of the rated endurance used up, in percent
+    pub wear_percent: Option<u8>,
+    pub media_errors: Option<u64>,
+    pub running_test: Option<RunningTest>,
+    pub self_tests: Vec<SelfTest>,
+}
+
+/// ATA attributes counting sectors the drive could not read or had to remap
+const ATA_BAD_SECTOR_IDS: [u8; 4] = [5, 187, 197, 198];
+/// ATA attributes whose normalized value counts down from 100 as flash wears out
+const ATA_WEAR_IDS: [u8; 4] = [169, 177, 231, 233];
+/// Entries of the NVMe health log that are shown, with the names and units smartctl prints
+const NVME_FIELDS: [(&str, &str, &str); 17] = [
+    ("critical_warning", "Critical Warning", ""),
+    ("temperature", "Temperature", " Celsius"),
+    ("available_spare", "Available Spare", "%"),
+    (
+        "available_spare_threshold",
+        "Available Spare Threshold",
+        "%",
+    ),
+    ("percentage_used", "Percentage Used", "%"),
+    ("data_units_read", "Data Units Read", ""),
+    ("data_units_written", "Data Units Written", ""),
+    ("host_reads", "Host Read Commands", ""),
+    ("host_writes", "Host Write Commands", ""),
+    ("controller_busy_time", "Controller Busy Time", ""),
+    ("power_cycles", "Power Cycles", ""),
+    ("power_on_hours", "Power On Hours", ""),
+    ("unsafe_shutdowns", "Unsafe Shutdowns", ""),
+    ("media_errors", "Media and Data Integrity Errors", ""),
+    ("num_err_log_entries", "Error Information Log Entries", ""),
+    ("warning_temp_time", "Warning Comp. Temperature Time", ""),
+    ("critical_comp_time", "Critical Comp. Temperature Time", ""),
+];
+
+/// Reads health, attributes and the self-test log with smartctl, which needs administrator
+/// rights for the raw device. Nothing is written to the drive.
+pub async fn read(device: String) -> Result<SmartReport, String> {
+    let output = tokio::process::Command::new("pkexec")
+        .args(["smartctl", "--json", "-H", "-A", "-c", "-l", "selftest"])
+        .arg(&device)
+        .output()
+        .await
+        .map_err(|err| err.to_string())?;
+
+    // Without JSON, pkexec failed before smartctl ran, e.g. because authorization was denied
+    let Ok(json) = serde_json::from_slice::<Value>(&output.stdout) else {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+    };
+    parse(&json)
+}
+
+fn parse(json: &Value) -> Result<SmartReport, String> {
+    // The exit status is a bit mask, only the lowest two bits mean smartctl itself failed
+    let status = json["smartctl"]["exit_status"].as_u64().unwrap_or_default();
+    if status & 0b11 != 0 {
+        return Err(json["smartctl"]["messages"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|message| message["severity"] == "error")
+            .and_then(|message| message["string"].as_str())
+            .unwrap_or_default()
+            .to_owned());
+    }
+
+    let mut report = SmartReport {
+        passed: json["smart_status"]["passed"].as_bool(),
+        power_on_hours: json["power_on_time"]["hours"].as_u64(),
+        ..SmartReport::default()
+    };
+
+    if let Some(table) = json["ata_smart_attributes"]["table"].as_array() {
+        report
+            .attributes
+            .extend(table.iter().filter_map(parse_ata_attribute));
+    }
+    let nvme = &json["nvme_smart_health_information_log"];
+    if nvme.is_object() {
+        report.attributes.extend(parse_nvme_log(nvme));
+    }
+
+    if let Some(table) = json["ata_smart_self_test_log"]["standard"]["table"].as_array() {
+        report.self_tests.extend(table.iter().filter_map(|entry| {
+            Some(SelfTest {
+                description: entry["type"]["string"].as_str()?.to_owned(),
+                status: entry["status"]["string"].as_str()?.to_owned(),
+                lifetime_hours: entry["lifetime_hours"].as_u64(),
+            })
+        }));
+    }
+    if let Some(table) = json["nvme_self_test_log"]["table"].as_array() {
+        report.self_tests.extend(table.iter().filter_map(|entry| {
+            Some(SelfTest {
+                description: entry["self_test_code"]["string"].as_str()?.to_owned(),
+                status: entry["self_test_result"]["string"].as_str()?.to_owned(),
+                lifetime_hours: entry["power_on_hours"].as_u64(),
+            })
+        }));
+    }
+
+    // Only a test that is still running reports how far along it is
+    let ata_test = &json["ata_smart_data"]["self_test"]["status"];
+    let nvme_test = &json["nvme_self_test_log"];
+    report.running_test = ata_test["remaining_percent"]
+        .as_u64()
+        .map(RunningTest::Remaining)
+        .or_else(|| {
+            let percent = nvme_test["current_self_test_completion_percent"].as_u64()?;
+            Some(RunningTest::Completed {
+                operation: nvme_test["current_self_test_operation"]["string"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_owned(),
+                percent,
+            })
+        });
+
+    summarize(&mut report, nvme);
+    Ok(report)
+}
+
+fn parse_ata_attribute(attribute: &Value) -> Option<SmartAttribute> {
+    let id = u8::try_from(attribute["id"].as_u64()?).ok()?;
+    let number = |key: &str| {
+        attribute[key]
+            .as_u64()
+            .and_then(|value| u8::try_from(value).ok())
+    };
+    let value = number("value");
+    let threshold = number("thresh");
+    let raw_value = attribute["raw"]["value"].as_u64();
+
+    // Attributes that never failed have an empty `when_failed`, otherwise it is `now` or `past`
+    let failing = attribute["when_failed"]
+        .as_str()
+        .is_some_and(|when| !when.is_empty())
+        || matches!((value, threshold), (Some(value), Some(threshold)) if threshold > 0 && value <= threshold);
+    let severity = if failing {
+        Severity::Failing
+    } else if ATA_BAD_SECTOR_IDS.contains(&id) && raw_value.is_some_and(|raw| raw > 0) {
+        Severity::Warning
+    } else {
+        Severity::Ok
+    };
+
+    Some(SmartAttribute {
+        id: Some(id),
+        name: attribute["name"].as_str()?.replace('_', " "),
+        value,
+        worst: number("worst"),
+        threshold,
+        // Raw values may carry details such as `35 (Min/Max 20/45)`
+        raw: attribute["raw"]["string"]
+            .as_str()
+            .map(str::to_owned)
+            .or_else(|| Some(raw_value?.to_string()))?,
+        severity,
+    })
+}
+
+fn parse_nvme_log(log: &Value) -> Vec<SmartAttribute> {
+    let number = |key: &str| log[key].as_u64();
+
+    NVME_FIELDS
+        .iter()
+        .filter_map(|(key, name, unit)| {
+            let value = number(key)?;
+            let severity = match *key {
+                "critical_warning" if value != 0 => Severity::Failing,
+                "available_spare"
+                    if number("available_spare_threshold")
+                        .is_some_and(|threshold| value < threshold) =>
+                {
+                    Severity::Failing
+                }
+                "media_errors" if value > 0 => Severity::Warning,
+                "percentage_used" if value >= 100 => Severity::Failing,
+                "percentage_used" if value >= 90 => Severity::Warning,
+                _ => Severity::Ok,
+            };
+
+            Some(SmartAttribute {
+                id: None,
+                name: name.to_string(),
+                value: None,
+                worst: None,
+                threshold: None,
+                raw: if *key == "critical_warning" {
+                    format!("0x{value:02x}")
+                } else {
+                    format!("{value}{unit}")
+                },
+                severity,
+            })
+        })
+        .collect()
+}
+
+/// Pulls the headline numbers out of the attributes
+fn summarize(report: &mut SmartReport, nvme: &Value) {
+    let ata_raw = |ids: &[u8]| {
+        report
+            .attributes
+            .iter()
+            .filter(|attribute| attribute.id.is_some_and(|id| ids.contains(&id)))
+            .filter_map(|attribute| attribute.raw.split_whitespace().next()?.parse::<u64>().ok())
+            .reduce(|a, b| a + b)
+    };
+
+    report.power_on_hours = report
+        .power_on_hours
+        .or_else(|| ata_raw(&[9]))
+        .or_else(|| nvme["power_on_hours"].as_u64());
+    report.media_errors = ata_raw(&[187, 198]).or_else(|| nvme["media_errors"].as_u64());
+    report.wear_percent = report
+        .attributes
+        .iter()
+        .find(|attribute| attribute.id.is_some_and(|id| ATA_WEAR_IDS.contains(&id)))
+        .and_then(|attribute| attribute.value)
+        .map(|remaining| 100u8.saturating_sub(remaining))
+        .or_else(|| {
+            nvme["percentage_used"]
+                .as_u64()
+                .map(|used| used.min(255) as u8)
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `smartctl --json -H -A -c -l selftest` of a SATA SSD running a short self-test,
+    /// trimmed to the parts that are read
+    const ATA: &str = r#"{
+      "json_format_version": [1, 0],
+      "smartctl": {
+        "version": [7, 4],
+        "argv": ["smartctl", "--json", "-H", "-A", "-c", "-l", "selftest", "/dev/sda"],
+        "exit_status": 0
+      },
+      "device": {"name": "/dev/sda", "info_name": "/dev/sda [SAT]", "type": "sat", "protocol": "ATA"},
+      "smart_status": {"passed": true},
+      "ata_smart_data": {
+        "offline_data_collection": {"status": {"value": 0, "string": "was never started"}},
+        "self_test": {
+          "status": {"value": 249, "string": "in progress, 90% remaining", "remaining_percent": 90},
+          "polling_minutes": {"short": 2, "extended": 85}
+        }
+      },
+      "ata_smart_attributes": {
+        "revision": 1,
+        "table": [
+          {"id": 5, "name": "Reallocated_Sector_Ct", "value": 100, "worst": 100, "thresh": 10, "when_failed": "",
+           "flags": {"value": 51, "string": "PO--CK ", "prefailure": true},
+           "raw": {"value": 8, "string": "8"}},
+          {"id": 9, "name": "Power_On_Hours", "value": 95, "worst": 95, "thresh": 0, "when_failed": "",
+           "flags": {"value": 50, "string": "-O--CK ", "prefailure": false},
+           "raw": {"value": 21734, "string": "21734"}},
+          {"id": 177, "name": "Wear_Leveling_Count", "value": 93, "worst": 93, "thresh": 0, "when_failed": "",
+           "flags": {"value": 19, "string": "PO--C- ", "prefailure": true},
+           "raw": {"value": 61, "string": "61"}},
+          {"id": 190, "name": "Airflow_Temperature_Cel", "value": 66, "worst": 48, "thresh": 0, "when_failed": "",
+           "flags": {"value": 50, "string": "-O--CK ", "prefailure": false},
+           "raw": {"value": 34, "string": "34"}},
+          {"id": 194, "name": "Temperature_Celsius", "value": 34, "worst": 52, "thresh": 0, "when_failed": "",
+           "flags": {"value": 34, "string": "-O---K ", "prefailure": false},
+           "raw": {"value": 223339003938, "string": "34 (Min/Max 18/52)"}},
+          {"id": 198, "name": "Offline_Uncorrectable", "value": 100, "worst": 100, "thresh": 10, "when_failed": "past",
+           "flags": {"value": 48, "string": "----CK ", "prefailure": false},
+           "raw": {"value": 0, "string": "0"}}
+        ]
+      },
+      "power_on_time": {"hours": 21734},
+      "ata_smart_self_test_log": {
+        "standard": {
+          "revision": 1,
+          "table": [
+            {"type": {"value": 1, "string": "Short offline"},
+             "status": {"value": 0, "string": "Completed without error", "passed": true},
+             "lifetime_hours": 21700},
+            {"type": {"value": 2, "string": "Extended offline"},
+             "status": {"value": 121, "string": "Completed: read failure", "remaining_percent": 90, "passed": false},
+             "lifetime_hours": 20012, "lba": 12345678}
+          ],
+          "count": 2
+        }
+      }
+    }"#;
+
+    /// The same for an NVMe drive with no test running
+    const NVME: &str = r#"{
+      "json_format_version": [1, 0],
+      "smartctl": {
+        "version": [7, 4],
+        "argv": ["smartctl", "--json", "-H", "-A", "-c", "-l", "selftest", "/dev/nvme0"],
+        "exit_status": 0
+      },
+      "device": {"name": "/dev/nvme0", "info_name": "/dev/nvme0", "type": "nvme", "protocol": "NVMe"},
+      "smart_status": {"passed": true, "nvme": {"value": 0}},
+      "nvme_smart_health_information_log": {
+        "critical_warning": 0,
+        "temperature": 38,
+        "available_spare": 100,
+        "available_spare_threshold": 10,
+        "percentage_used": 92,
+        "data_units_read": 48102871,
+        "data_units_written": 51240019,
+        "host_reads": 612048521,
+        "host_writes": 1094118713,
+        "controller_busy_time": 2185,
+        "power_cycles": 1620,
+        "power_on_hours": 8312,
+        "unsafe_shutdowns": 91,
+        "media_errors": 2,
+        "num_err_log_entries": 3144,
+        "warning_temp_time": 0,
+        "critical_comp_time": 0,
+        "temperature_sensors": [38, 41]
+      },
+      "temperature": {"current": 38},
+      "power_cycle_count": 1620,
+      "power_on_time": {"hours": 8312},
+      "nvme_self_test_log": {
+        "current_self_test_operation": {"value": 0, "string": "No self-test in progress"},
+        "table": [
+          {"self_test_code": {"value": 1, "string": "Short"},
+           "self_test_result": {"value": 0, "string": "Completed without error"},
+           "power_on_hours": 8290}
+        ]
+      }
+    }"#;
+
+    fn report(json: &str) -> Result<SmartReport, String> {
+        parse(&serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn ata() {
+        let report = report(ATA).unwrap();
+
+        assert_eq!(report.passed, Some(true));
+        assert_eq!(report.power_on_hours, Some(21734));
+        assert_eq!(report.wear_percent, Some(7));
+        assert_eq!(report.media_errors, Some(0));
+        assert_eq!(report.running_test, Some(RunningTest::Remaining(90)));
+
+        assert_eq!(report.attributes.len(), 6);
+        let reallocated = &report.attributes[0];
+        assert_eq!(reallocated.id, Some(5));
+        assert_eq!(reallocated.name, "Reallocated Sector Ct");
+        assert_eq!(
+            (reallocated.value, reallocated.worst, reallocated.threshold),
+            (Some(100), Some(100), Some(10))
+        );
+        assert_eq!(reallocated.severity, Severity::Warning);
+        assert_eq!(report.attributes[4].raw, "34 (Min/Max 18/52)");
+        assert_eq!(report.attributes[4].severity, Severity::Ok);
+        assert_eq!(report.attributes[5].severity, Severity::Failing);
+
+        assert_eq!(
+            report.self_tests,
+            vec![
+                SelfTest {
+                    description: "Short offline".to_owned(),
+                    status: "Completed without error".to_owned(),
+                    lifetime_hours: Some(21700),
+                },
+                SelfTest {
+                    description: "Extended offline".to_owned(),
+                    status: "Completed: read failure".to_owned(),
+                    lifetime_hours: Some(20012),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn nvme() {
+        let report = report(NVME).unwrap();
+
+        assert_eq!(report.passed, Some(true));
+        assert_eq!(report.power_on_hours, Some(8312));
+        assert_eq!(report.wear_percent, Some(92));
+        assert_eq!(report.media_errors, Some(2));
+        assert_eq!(report.running_test, None);
+
+        assert_eq!(report.attributes.len(), NVME_FIELDS.len());
+        let attribute = |name: &str| {
+            report
+                .attributes
+                .iter()
+                .find(|attribute| attribute.name == name)
+                .unwrap()
+        };
+        assert_eq!(attribute("Critical Warning").raw, "0x00");
+        assert_eq!(attribute("Critical Warning").severity, Severity::Ok);
+        assert_eq!(attribute("Temperature").raw, "38 Celsius");
+        assert_eq!(attribute("Percentage Used").raw, "92%");
+        assert_eq!(attribute("Percentage Used").severity, Severity::Warning);
+        assert_eq!(
+            attribute("Media and Data Integrity Errors").severity,
+            Severity::Warning
+        );
+        assert!(report
+            .attributes
+            .iter()
+            .all(|attribute| attribute.id.is_none()));
+
+        assert_eq!(report.self_tests.len(), 1);
+        assert_eq!(report.self_tests[0].description, "Short");
+        assert_eq!(report.self_tests[0].lifetime_hours, Some(8290));
+    }
+
+    #[test]
+    fn nvme_test_running() {
+        let mut json = serde_json::from_str::<Value>(NVME).unwrap();
+        json["nvme_self_test_log"]["current_self_test_operation"] =
+            serde_json::json!({"value": 2, "string": "Extended self-test"});
+        json["nvme_self_test_log"]["current_self_test_completion_percent"] = 20.into();
+
+        assert_eq!(
+            parse(&json).unwrap().running_test,
+            Some(RunningTest::Completed {
+                operation: "Extended self-test".to_owned(),
+                percent: 20,
+            })
+        );
+    }
+
+    #[test]
+    fn error() {
+        let error = report(
+            r#"{
+              "smartctl": {
+                "exit_status": 2,
+                "messages": [
+                  {"string": "Smartctl open device: /dev/sdz failed: No such device", "severity": "error"}
+                ]
+              }
+            }"#,
+        );
+
+        assert_eq!(
+            error,
+            Err("Smartctl open device: /dev/sdz failed: No such device".to_owned())
+        );
+    }
+}
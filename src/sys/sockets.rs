@@ -0,0 +1,271 @@
+use std::{
+    collections::HashMap,
+    fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+/// Socket states as numbered in `include/net/tcp_states.h`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SocketState {
+    Established,
+    SynSent,
+    SynReceived,
+    FinWait1,
+    FinWait2,
+    TimeWait,
+    Close,
+    CloseWait,
+    LastAck,
+    Listen,
+    Closing,
+    Unknown,
+}
+
+impl SocketState {
+    fn from_hex(hex: &str) -> Self {
+        match u8::from_str_radix(hex, 16).unwrap_or_default() {
+            0x01 => Self::Established,
+            0x02 => Self::SynSent,
+            0x03 => Self::SynReceived,
+            0x04 => Self::FinWait1,
+            0x05 => Self::FinWait2,
+            0x06 => Self::TimeWait,
+            0x07 => Self::Close,
+            0x08 => Self::CloseWait,
+            0x09 => Self::LastAck,
+            0x0A => Self::Listen,
+            0x0B => Self::Closing,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// An open TCP or UDP socket
+#[derive(Debug, Clone, PartialEq)]
+pub struct Socket {
+    pub protocol: Protocol,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    pub state: SocketState,
+    pub inode: u64,
+    /// Owning process, missing for sockets of other users unless running as root
+    pub pid: Option<u32>,
+    pub process_name: Option<String>,
+}
+
+impl Socket {
+    /// Whether the socket accepts new connections or datagrams on its local port
+    pub fn is_listening(&self) -> bool {
+        match self.protocol {
+            Protocol::Tcp => self.state == SocketState::Listen,
+            // Bound UDP sockets without a peer are reported as closed
+            Protocol::Udp => self.state == SocketState::Close && self.remote.port() == 0,
+        }
+    }
+}
+
+/// Sockets of the current network namespace with the processes holding them
+pub fn read() -> Vec<Socket> {
+    let owners = socket_owners();
+
+    [
+        ("/proc/net/tcp", Protocol::Tcp),
+        ("/proc/net/tcp6", Protocol::Tcp),
+        ("/proc/net/udp", Protocol::Udp),
+        ("/proc/net/udp6", Protocol::Udp),
+    ]
+    .into_iter()
+    .flat_map(|(path, protocol)| {
+        fs::read_to_string(path)
+            .map(|content| {
+                content
+                    .lines()
+                    .skip(1)
+                    .filter_map(|line| parse_socket(line, protocol))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    })
+    .map(|mut socket| {
+        if let Some((pid, name)) = owners.get(&socket.inode) {
+            socket.pid = Some(*pid);
+            socket.process_name = Some(name.clone());
+        }
+        socket
+    })
+    .collect()
+}
+
+/// Lines look like `0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000 0 0 12345 ...`
+fn parse_socket(line: &str, protocol: Protocol) -> Option<Socket> {
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let inode = fields.get(9)?.parse().ok()?;
+
+    Some(Socket {
+        protocol,
+        local: parse_address(fields.get(1)?)?,
+        remote: parse_address(fields.get(2)?)?,
+        state: SocketState::from_hex(fields.get(3)?),
+        inode,
+        pid: None,
+        process_name: None,
+    })
+}
+
+/// Addresses are printed as 32 bit words in host byte order, the port in big endian
+fn parse_address(field: &str) -> Option<SocketAddr> {
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+
+    let mut bytes = Vec::with_capacity(16);
+    for index in (0..address.len()).step_by(8) {
+        let word = u32::from_str_radix(address.get(index..index + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+
+    let address = match bytes.len() {
+        4 => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?)),
+        16 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?)),
+        _ => return None,
+    };
+    Some(SocketAddr::new(address, port))
+}
+
+/// Maps socket inodes to the process holding them by following the links in `/proc/<pid>/fd`.
+/// Descriptors of other users' processes can only be followed by root.
+fn socket_owners() -> HashMap<u64, (u32, String)> {
+    let mut owners = HashMap::new();
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return owners;
+    };
+
+    for process in processes.flatten() {
+        let Some(pid) = process
+            .file_name()
+            .to_str()
+            .and_then(|pid| pid.parse().ok())
+        else {
+            continue;
+        };
+        let Ok(descriptors) = fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+
+        let mut name = None;
+        for descriptor in descriptors.flatten() {
+            let Ok(target) = fs::read_link(descriptor.path()) else {
+                continue;
+            };
+            let Some(inode) = target
+                .to_str()
+                .and_then(|target| target.strip_prefix("socket:["))
+                .and_then(|target| target.strip_suffix(']'))
+                .and_then(|inode| inode.parse().ok())
+            else {
+                continue;
+            };
+
+            let name = name.get_or_insert_with(|| {
+                fs::read_to_string(process.path().join("comm"))
+                    .map(|comm| comm.trim().to_owned())
+                    .unwrap_or_default()
+            });
+            // Sockets shared after a fork are attributed to the first process found
+            owners.entry(inode).or_insert_with(|| (pid, name.clone()));
+        }
+    }
+
+    owners
+}
+
+// Captured on a little endian machine, where the kernel prints addresses with bytes swapped
+#[cfg(all(test, target_endian = "little"))]
+mod tests {
+    use super::*;
+
+    const TCP: &str = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 3500007F:0035 00000000:0000 0A 00000000:00000000 00:00000000 00000000   991        0 21338 1 0000000000000000 100 0 0 10 5
+   1: 0F02000A:A2C4 2E1F7A8C:01BB 01 00000000:00000000 02:00000A3B 00000000  1000        0 104786 2 0000000000000000 24 4 30 10 -1";
+
+    const TCP6: &str = "\
+  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000000000000:0016 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 19823 1 0000000000000000 100 0 0 10 0
+   1: 0000000000000000FFFF00000F02000A:E0A2 0000000000000000FFFF00002E1F7A8C:01BB 01 00000000:00000000 00:00000000 00000000  1000        0 118201 1 0000000000000000 20 4 29 10 -1
+   2: B80D0120000000000000000001000000:8F3E B80D0120000000000000000002000000:01BB 06 00000000:00000000 03:0000176F 00000000     0        0 0 3 0000000000000000";
+
+    const UDP: &str = "\
+   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  221: 00000000:14E9 00000000:0000 07 00000000:00000000 00:00000000 00000000   104        0 20174 2 0000000000000000 0
+  905: 0F02000A:9C42 0101A8C0:0035 01 00000000:00000000 00:00000000 00000000  1000        0 131056 2 0000000000000000 0";
+
+    fn parse(content: &str, protocol: Protocol) -> Vec<Socket> {
+        content
+            .lines()
+            .skip(1)
+            .filter_map(|line| parse_socket(line, protocol))
+            .collect()
+    }
+
+    #[test]
+    fn tcp() {
+        let sockets = parse(TCP, Protocol::Tcp);
+
+        assert_eq!(sockets.len(), 2);
+        assert_eq!(sockets[0].local, "127.0.0.53:53".parse().unwrap());
+        assert_eq!(sockets[0].remote, "0.0.0.0:0".parse().unwrap());
+        assert_eq!(sockets[0].state, SocketState::Listen);
+        assert_eq!(sockets[0].inode, 21338);
+        assert!(sockets[0].is_listening());
+        assert_eq!(sockets[1].local, "10.0.2.15:41668".parse().unwrap());
+        assert_eq!(sockets[1].remote, "140.122.31.46:443".parse().unwrap());
+        assert_eq!(sockets[1].state, SocketState::Established);
+        assert_eq!(sockets[1].inode, 104786);
+        assert!(!sockets[1].is_listening());
+    }
+
+    #[test]
+    fn tcp6() {
+        let sockets = parse(TCP6, Protocol::Tcp);
+
+        assert_eq!(sockets.len(), 3);
+        assert_eq!(sockets[0].local, "[::]:22".parse().unwrap());
+        assert_eq!(sockets[0].state, SocketState::Listen);
+        assert_eq!(
+            sockets[1].local,
+            "[::ffff:10.0.2.15]:57506".parse().unwrap()
+        );
+        assert_eq!(
+            sockets[1].remote,
+            "[::ffff:140.122.31.46]:443".parse().unwrap()
+        );
+        assert_eq!(sockets[2].local, "[2001:db8::1]:36670".parse().unwrap());
+        assert_eq!(sockets[2].remote, "[2001:db8::2]:443".parse().unwrap());
+        assert_eq!(sockets[2].state, SocketState::TimeWait);
+    }
+
+    #[test]
+    fn udp() {
+        let sockets = parse(UDP, Protocol::Udp);
+
+        assert_eq!(sockets.len(), 2);
+        assert_eq!(sockets[0].local, "0.0.0.0:5353".parse().unwrap());
+        assert!(sockets[0].is_listening());
+        assert_eq!(sockets[1].remote, "192.168.1.1:53".parse().unwrap());
+        assert!(!sockets[1].is_listening());
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(parse_address("0100007F"), None);
+        assert_eq!(parse_address("0100007:0035"), None);
+        assert_eq!(parse_address("0100007F0100007F:0035"), None);
+        assert_eq!(parse_socket("   0: 0100007F:0035", Protocol::Tcp), None);
+    }
+}
@@ -0,0 +1,215 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use super::read_trimmed;
+
+/// How logical CPUs are laid out across sockets, NUMA nodes, caches and cores
+#[derive(Debug, Clone, Default)]
+pub struct CpuTopology {
+    pub packages: Vec<Package>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub id: u32,
+    pub nodes: Vec<NumaNode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NumaNode {
+    /// Missing on kernels built without NUMA support
+    pub id: Option<u32>,
+    pub caches: Vec<CacheDomain>,
+}
+
+/// Cores sharing the same last level cache
+#[derive(Debug, Clone)]
+pub struct CacheDomain {
+    pub level: u8,
+    pub size_bytes: u64,
+    pub cores: Vec<Core>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Core {
+    pub id: u32,
+    pub kind: CoreKind,
+    /// Logical CPUs running on this core, more than one with SMT
+    pub threads: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreKind {
+    Performance,
+    Efficiency,
+    /// Every core is the same
+    Uniform,
+}
+
+struct LogicalCpu {
+    id: u32,
+    package: u32,
+    core: u32,
+    node: Option<u32>,
+    cache: (u8, u64, String),
+    capacity: Option<u32>,
+}
+
+const CPU_ROOT: &str = "/sys/devices/system/cpu";
+
+pub fn read() -> CpuTopology {
+    let Ok(entries) = fs::read_dir(CPU_ROOT) else {
+        return CpuTopology::default();
+    };
+
+    let cpus = entries
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry
+                .file_name()
+                .to_string_lossy()
+                .strip_prefix("cpu")?
+                .parse()
+                .ok()?;
+            read_cpu(id, &entry.path())
+        })
+        .collect::<Vec<LogicalCpu>>();
+
+    // Intel hybrid parts list their core types, elsewhere a lower capacity marks efficiency cores
+    let performance = read_trimmed("/sys/devices/cpu_core/cpus").map(|list| parse_cpu_list(&list));
+    let efficiency = read_trimmed("/sys/devices/cpu_atom/cpus").map(|list| parse_cpu_list(&list));
+    let max_capacity = cpus.iter().filter_map(|cpu| cpu.capacity).max();
+    let kind = |cpu: &LogicalCpu| {
+        if let (Some(performance), Some(efficiency)) = (&performance, &efficiency) {
+            if performance.contains(&cpu.id) {
+                return CoreKind::Performance;
+            }
+            if efficiency.contains(&cpu.id) {
+                return CoreKind::Efficiency;
+            }
+        }
+        match (cpu.capacity, max_capacity) {
+            _ if cpus.iter().all(|other| other.capacity == cpu.capacity) => CoreKind::Uniform,
+            (Some(capacity), Some(max)) if capacity < max => CoreKind::Efficiency,
+            (Some(_), Some(_)) => CoreKind::Performance,
+            _ => CoreKind::Uniform,
+        }
+    };
+
+    type Cores = BTreeMap<u32, Core>;
+    type Caches = BTreeMap<String, (u8, u64, Cores)>;
+    let mut packages = BTreeMap::<u32, BTreeMap<Option<u32>, Caches>>::new();
+    for cpu in &cpus {
+        let (level, size_bytes, shared) = cpu.cache.clone();
+        let core = packages
+            .entry(cpu.package)
+            .or_default()
+            .entry(cpu.node)
+            .or_default()
+            .entry(shared)
+            .or_insert_with(|| (level, size_bytes, BTreeMap::new()))
+            .2
+            .entry(cpu.core)
+            .or_insert_with(|| Core {
+                id: cpu.core,
+                kind: kind(cpu),
+                threads: Vec::new(),
+            });
+        core.threads.push(cpu.id);
+    }
+
+    CpuTopology {
+        packages: packages
+            .into_iter()
+            .map(|(id, nodes)| Package {
+                id,
+                nodes: nodes
+                    .into_iter()
+                    .map(|(id, caches)| NumaNode {
+                        id,
+                        caches: caches
+                            .into_values()
+                            .map(|(level, size_bytes, cores)| CacheDomain {
+                                level,
+                                size_bytes,
+                                cores: cores
+                                    .into_values()
+                                    .map(|mut core| {
+                                        core.threads.sort();
+                                        core
+                                    })
+                                    .collect(),
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+fn read_cpu(id: u32, path: &Path) -> Option<LogicalCpu> {
+    // Offline CPUs have no topology directory
+    let topology = path.join("topology");
+    let package = read_trimmed(topology.join("physical_package_id"))?
+        .parse()
+        .ok()?;
+    let core = read_trimmed(topology.join("core_id"))?.parse().ok()?;
+
+    let node = fs::read_dir(path).ok()?.flatten().find_map(|entry| {
+        entry
+            .file_name()
+            .to_string_lossy()
+            .strip_prefix("node")?
+            .parse()
+            .ok()
+    });
+
+    Some(LogicalCpu {
+        id,
+        package,
+        core,
+        node,
+        cache: read_last_level_cache(path).unwrap_or_else(|| (0, 0, id.to_string())),
+        capacity: read_trimmed(path.join("cpu_capacity")).and_then(|value| value.parse().ok()),
+    })
+}
+
+/// Level, size and sharing CPUs of the highest cache level this CPU uses
+fn read_last_level_cache(path: &Path) -> Option<(u8, u64, String)> {
+    fs::read_dir(path.join("cache"))
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("index"))
+        .filter_map(|entry| {
+            let index = entry.path();
+            if read_trimmed(index.join("type"))? == "Instruction" {
+                return None;
+            }
+            let level = read_trimmed(index.join("level"))?.parse().ok()?;
+            let size = read_trimmed(index.join("size")).map(|size| parse_cache_size(&size))?;
+            let shared = read_trimmed(index.join("shared_cpu_list"))?;
+            Some((level, size, shared))
+        })
+        .max_by_key(|(level, _, _)| *level)
+}
+
+fn parse_cache_size(size: &str) -> u64 {
+    let (amount, multiplier) = match size.as_bytes().last() {
+        Some(b'K') => (&size[..size.len() - 1], 1024),
+        Some(b'M') => (&size[..size.len() - 1], 1024u64.pow(2)),
+        Some(b'G') => (&size[..size.len() - 1], 1024u64.pow(3)),
+        _ => (size, 1),
+    };
+    amount.parse::<u64>().unwrap_or_default() * multiplier
+}
+
+/// Parses kernel CPU lists such as `0-3,8-11`
+pub fn parse_cpu_list(list: &str) -> Vec<u32> {
+    list.split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((start, end)) => Some((start.parse::<u32>().ok()?..=end.parse().ok()?).collect()),
+            None => Some(vec![range.parse().ok()?]),
+        })
+        .flatten()
+        .collect()
+}
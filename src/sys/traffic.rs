@@ -0,0 +1,140 @@
+use std::{collections::HashMap, net::SocketAddr, process::Command, time::Instant};
+
+/// Network throughput of one process between two samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessTraffic {
+    pub pid: u32,
+    pub name: String,
+    pub rx_bytes_per_sec: u64,
+    pub tx_bytes_per_sec: u64,
+}
+
+/// Byte counters of one TCP connection and the process holding it
+struct SocketCounters {
+    pid: u32,
+    name: String,
+    received: u64,
+    sent: u64,
+}
+
+/// Estimates per-process throughput from the byte counters the kernel keeps for each TCP
+/// connection. Only TCP is counted, loopback traffic is left out, and connections of other
+/// users' processes are only attributed when running as root, so the numbers are a lower bound.
+#[derive(Default)]
+pub struct TrafficSampler {
+    previous: Option<(Instant, HashMap<(SocketAddr, SocketAddr), SocketCounters>)>,
+}
+
+impl TrafficSampler {
+    /// Throughput keyed by PID, `None` for the first sample or when `ss` is missing
+    pub fn sample(&mut self) -> Option<HashMap<u32, ProcessTraffic>> {
+        let now = Instant::now();
+        let sockets = read_tcp_counters()?;
+
+        let traffic = self.previous.as_ref().map(|(then, previous)| {
+            let elapsed = now.duration_since(*then).as_secs_f64().max(f64::EPSILON);
+            let mut traffic = HashMap::<u32, ProcessTraffic>::new();
+            for (key, socket) in &sockets {
+                // Connections opened since the last sample count from zero
+                let (received, sent) = previous
+                    .get(key)
+                    .map_or((0, 0), |previous| (previous.received, previous.sent));
+                let process = traffic.entry(socket.pid).or_insert_with(|| ProcessTraffic {
+                    pid: socket.pid,
+                    name: socket.name.clone(),
+                    rx_bytes_per_sec: 0,
+                    tx_bytes_per_sec: 0,
+                });
+                process.rx_bytes_per_sec += socket.received.saturating_sub(received);
+                process.tx_bytes_per_sec += socket.sent.saturating_sub(sent);
+            }
+            for process in traffic.values_mut() {
+                process.rx_bytes_per_sec = (process.rx_bytes_per_sec as f64 / elapsed) as u64;
+                process.tx_bytes_per_sec = (process.tx_bytes_per_sec as f64 / elapsed) as u64;
+            }
+            traffic
+        });
+
+        self.previous = Some((now, sockets));
+        traffic
+    }
+}
+
+/// Parses `ss -Htinp`, which prints each connection followed by an indented line of TCP info:
+///
+/// ```text
+/// ESTAB 0 0 192.168.1.5:55064 1.1.1.1:443 users:(("firefox",pid=1656,fd=16))
+///      cubic wscale:7,7 rto:204 ... bytes_sent:1776 bytes_acked:1777 bytes_received:2981 ...
+/// ```
+fn read_tcp_counters() -> Option<HashMap<(SocketAddr, SocketAddr), SocketCounters>> {
+    let output = Command::new("ss").arg("-Htinp").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let output = String::from_utf8_lossy(&output.stdout);
+
+    let mut sockets = HashMap::new();
+    let mut lines = output.lines().peekable();
+    while let Some(line) = lines.next() {
+        let info = match lines.peek() {
+            Some(next) if next.starts_with(char::is_whitespace) => lines.next(),
+            _ => None,
+        };
+
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        let (Some(local), Some(remote)) = (
+            fields.get(3).and_then(|local| parse_address(local)),
+            fields.get(4).and_then(|remote| parse_address(remote)),
+        ) else {
+            continue;
+        };
+        if local.ip().is_loopback() {
+            continue;
+        }
+        let Some((pid, name)) = fields.get(5).and_then(|users| parse_owner(users)) else {
+            continue;
+        };
+
+        let counter = |key: &str| {
+            info?
+                .split_whitespace()
+                .find_map(|field| field.strip_prefix(key)?.strip_prefix(':'))?
+                .parse()
+                .ok()
+        };
+        sockets.insert(
+            (local, remote),
+            SocketCounters {
+                pid,
+                name,
+                received: counter("bytes_received").unwrap_or_default(),
+                sent: counter("bytes_sent").unwrap_or_default(),
+            },
+        );
+    }
+
+    Some(sockets)
+}
+
+/// IPv6 addresses are printed in brackets and may carry an interface, e.g. `[fe80::1%eth0]:22`
+fn parse_address(address: &str) -> Option<SocketAddr> {
+    let (host, port) = address.rsplit_once(':')?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let host = host.split('%').next()?;
+    // IPv4 connections on IPv6 sockets are printed as mapped addresses
+    let host = host.strip_prefix("::ffff:").unwrap_or(host);
+    Some(SocketAddr::new(host.parse().ok()?, port.parse().ok()?))
+}
+
+/// The first process in `users:(("name",pid=123,fd=4),...)`
+fn parse_owner(users: &str) -> Option<(u32, String)> {
+    let (_, rest) = users.split_once("((\"")?;
+    let (name, rest) = rest.split_once('"')?;
+    let pid = rest
+        .strip_prefix(",pid=")?
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()?;
+    Some((pid, name.to_owned()))
+}
@@ -0,0 +1,89 @@
+use std::{collections::HashMap, fs, time::Instant};
+
+/// Paging activity between two samples of `/proc/vmstat`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PagingActivity {
+    /// Pages read from and written to swap per second
+    pub swap_in_per_sec: f64,
+    pub swap_out_per_sec: f64,
+    /// Bytes paged in from and out to block devices per second, including swap
+    pub page_in_bytes_per_sec: f64,
+    pub page_out_bytes_per_sec: f64,
+    pub major_faults_per_sec: f64,
+    /// Processes killed by the OOM killer since boot, missing before Linux 4.13
+    pub oom_kills: Option<u64>,
+}
+
+/// A process killed by the kernel OOM killer
+#[derive(Debug, Clone, PartialEq)]
+pub struct OomVictim {
+    pub pid: u32,
+    pub name: String,
+}
+
+/// Turns the cumulative counters in `/proc/vmstat` into rates between two samples
+#[derive(Default)]
+pub struct VmstatSampler {
+    previous: Option<(Instant, HashMap<String, u64>)>,
+}
+
+impl VmstatSampler {
+    pub fn sample(&mut self) -> Option<PagingActivity> {
+        let now = Instant::now();
+        let vmstat = read_vmstat()?;
+
+        let activity = self.previous.as_ref().map(|(then, previous)| {
+            let elapsed = now.duration_since(*then).as_secs_f64().max(f64::EPSILON);
+            let rate = |key: &str| {
+                let current = vmstat.get(key).copied().unwrap_or_default();
+                let previous = previous.get(key).copied().unwrap_or_default();
+                current.saturating_sub(previous) as f64 / elapsed
+            };
+            PagingActivity {
+                swap_in_per_sec: rate("pswpin"),
+                swap_out_per_sec: rate("pswpout"),
+                // Counted in kilobytes
+                page_in_bytes_per_sec: rate("pgpgin") * 1024.0,
+                page_out_bytes_per_sec: rate("pgpgout") * 1024.0,
+                major_faults_per_sec: rate("pgmajfault"),
+                oom_kills: vmstat.get("oom_kill").copied(),
+            }
+        });
+
+        self.previous = Some((now, vmstat));
+        activity
+    }
+}
+
+fn read_vmstat() -> Option<HashMap<String, u64>> {
+    let vmstat = fs::read_to_string("/proc/vmstat").ok()?;
+    Some(
+        vmstat
+            .lines()
+            .filter_map(|line| {
+                let (key, value) = line.split_once(' ')?;
+                Some((key.to_owned(), value.trim().parse().ok()?))
+            })
+            .collect(),
+    )
+}
+
+/// Finds the victim in a `/dev/kmsg` record such as
+/// `3,1234,5678,-;Out of memory: Killed process 4321 (firefox) total-vm:...`
+pub fn parse_oom_victim(record: &str) -> Option<OomVictim> {
+    let message = record
+        .split_once(';')
+        .map_or(record, |(_, message)| message);
+    // Older kernels say "Kill process", memory cgroups prefix the message
+    let (_, process) = message
+        .split_once("out of memory: Killed process ")
+        .or_else(|| message.split_once("Out of memory: Killed process "))
+        .or_else(|| message.split_once("Out of memory: Kill process "))?;
+
+    let (pid, rest) = process.split_once(' ')?;
+    let name = rest.strip_prefix('(')?.split_once(')')?.0;
+    Some(OomVictim {
+        pid: pid.parse().ok()?,
+        name: name.to_owned(),
+    })
+}
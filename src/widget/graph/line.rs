@@ -1,3 +1,5 @@
+use std::cell::{Cell, RefCell};
+
 use cosmic::{iced, prelude::*, widget::canvas::*};
 
 #[derive(Clone)]
@@ -6,12 +8,25 @@ pub struct LineGraph {
     pub points: Vec<f32>,
 }
 
+/// Cached geometry layers, kept by the canvas between redraws
+#[derive(Default)]
+pub struct LineGraphState {
+    // Background grid, regenerated on resize or theme change
+    grid: Cache,
+    // Data path, regenerated when new samples arrive
+    data: Cache,
+    // Masking and border, regenerated on resize or theme change
+    border: Cache,
+    points: RefCell<Vec<f32>>,
+    colors: Cell<Option<[iced::Color; 3]>>,
+}
+
 impl Program<crate::app::Message, Theme> for LineGraph {
-    type State = ();
+    type State = LineGraphState;
 
     fn draw(
         &self,
-        _state: &Self::State,
+        state: &Self::State,
         renderer: &Renderer,
         theme: &Theme,
         bounds: iced::Rectangle,
@@ -19,6 +34,23 @@ impl Program<crate::app::Message, Theme> for LineGraph {
     ) -> Vec<Geometry<Renderer>> {
         let cosmic = theme.cosmic();
 
+        // Caches already regenerate on resize, theme changes have to be tracked by hand
+        let colors: [iced::Color; 3] = [
+            cosmic.bg_divider().into(),
+            cosmic.accent_color().into(),
+            cosmic.bg_color().into(),
+        ];
+        if state.colors.get() != Some(colors) {
+            state.colors.set(Some(colors));
+            state.grid.clear();
+            state.data.clear();
+            state.border.clear();
+        }
+        if *state.points.borrow() != self.points {
+            state.points.replace(self.points.clone());
+            state.data.clear();
+        }
+
         let bounds = iced::Rectangle::new(
             iced::Point::new(0.0, 0.0),
             iced::Size::new(
@@ -26,33 +58,25 @@ impl Program<crate::app::Message, Theme> for LineGraph {
                 bounds.height.min(bounds.width),
             ),
         );
-        let mut frame = Frame::new(renderer, bounds.size());
 
-        // Draw background grid
-        let mut builder = path::Builder::new();
-        let x_step = bounds.width / 10.0;
-        let y_step = bounds.height / 10.0;
-        for i in 1..10 {
-            // Vertical line
-            builder.move_to(iced::Point::new(x_step * i as f32, bounds.y));
-            builder.line_to(iced::Point::new(
-                x_step * i as f32,
-                bounds.y + bounds.height,
-            ));
-            // Horizontal line
-            builder.move_to(iced::Point::new(bounds.x, y_step * i as f32));
-            builder.line_to(iced::Point::new(bounds.x + bounds.width, y_step * i as f32));
-        }
-        frame.stroke(
-            &builder.build(),
-            Stroke {
-                style: Style::Solid(cosmic.bg_divider().into()),
-                width: 1.0,
-                ..Default::default()
-            },
-        );
+        vec![
+            state.grid.draw(renderer, bounds.size(), |frame| {
+                draw_grid(frame, theme, bounds)
+            }),
+            state.data.draw(renderer, bounds.size(), |frame| {
+                self.draw_points(frame, theme, bounds)
+            }),
+            state.border.draw(renderer, bounds.size(), |frame| {
+                draw_border(frame, theme, bounds)
+            }),
+        ]
+    }
+}
+
+impl LineGraph {
+    fn draw_points(&self, frame: &mut Frame, theme: &Theme, bounds: iced::Rectangle) {
+        let cosmic = theme.cosmic();
 
-        // Draw points
         let bounds = bounds.shrink(1.0);
         let x_step = bounds.width / (self.points.len() as f32 - 1.0);
 
@@ -99,35 +123,65 @@ impl Program<crate::app::Message, Theme> for LineGraph {
                 ..Default::default()
             },
         );
+    }
+}
 
-        // This is bs but it works, draw a background colored rounded rectangle to "hide" things drawing outside of the graph
-        let mut square = path::Builder::new();
-        let ex_bounds = bounds.expand(8.0);
-        square.rounded_rectangle(
-            ex_bounds.position(),
-            ex_bounds.size(),
-            cosmic.radius_l().into(),
-        );
-        frame.stroke(
-            &square.build(),
-            Stroke {
-                style: Style::Solid(cosmic.bg_color().into()),
-                width: 12.0,
-                ..Default::default()
-            },
-        );
-        // Draw background square
-        let mut square = path::Builder::new();
-        square.rounded_rectangle(bounds.position(), bounds.size(), cosmic.radius_m().into());
-        frame.stroke(
-            &square.build(),
-            Stroke {
-                style: Style::Solid(cosmic.accent_color().into()),
-                width: 2.0,
-                ..Default::default()
-            },
-        );
+pub(super) fn draw_grid(frame: &mut Frame, theme: &Theme, bounds: iced::Rectangle) {
+    let cosmic = theme.cosmic();
 
-        vec![frame.into_geometry()]
+    let mut builder = path::Builder::new();
+    let x_step = bounds.width / 10.0;
+    let y_step = bounds.height / 10.0;
+    for i in 1..10 {
+        // Vertical line
+        builder.move_to(iced::Point::new(x_step * i as f32, bounds.y));
+        builder.line_to(iced::Point::new(
+            x_step * i as f32,
+            bounds.y + bounds.height,
+        ));
+        // Horizontal line
+        builder.move_to(iced::Point::new(bounds.x, y_step * i as f32));
+        builder.line_to(iced::Point::new(bounds.x + bounds.width, y_step * i as f32));
     }
+    frame.stroke(
+        &builder.build(),
+        Stroke {
+            style: Style::Solid(cosmic.bg_divider().into()),
+            width: 1.0,
+            ..Default::default()
+        },
+    );
+}
+
+pub(super) fn draw_border(frame: &mut Frame, theme: &Theme, bounds: iced::Rectangle) {
+    let cosmic = theme.cosmic();
+    let bounds = bounds.shrink(1.0);
+
+    // This is bs but it works, draw a background colored rounded rectangle to "hide" things drawing outside of the graph
+    let mut square = path::Builder::new();
+    let ex_bounds = bounds.expand(8.0);
+    square.rounded_rectangle(
+        ex_bounds.position(),
+        ex_bounds.size(),
+        cosmic.radius_l().into(),
+    );
+    frame.stroke(
+        &square.build(),
+        Stroke {
+            style: Style::Solid(cosmic.bg_color().into()),
+            width: 12.0,
+            ..Default::default()
+        },
+    );
+    // Draw background square
+    let mut square = path::Builder::new();
+    square.rounded_rectangle(bounds.position(), bounds.size(), cosmic.radius_m().into());
+    frame.stroke(
+        &square.build(),
+        Stroke {
+            style: Style::Solid(cosmic.accent_color().into()),
+            width: 2.0,
+            ..Default::default()
+        },
+    );
 }
@@ -1,2 +1,4 @@
 pub mod line;
+pub mod stacked;
 pub use line::LineGraph;
+pub use stacked::{LegendSwatch, StackedBar, StackedGraph};
@@ -0,0 +1,226 @@
+use std::cell::{Cell, RefCell};
+
+use cosmic::{iced, prelude::*, widget::canvas::*};
+
+use super::line::{draw_border, draw_grid};
+
+/// Several series drawn on top of each other, so their sum reads as one total
+#[derive(Clone)]
+pub struct StackedGraph {
+    // One series per layer from the bottom up, with points (out of 1)
+    pub series: Vec<Vec<f32>>,
+    // Sample indices to flag with a vertical line, e.g. when something went wrong
+    pub markers: Vec<usize>,
+}
+
+/// Cached geometry layers, kept by the canvas between redraws
+#[derive(Default)]
+pub struct StackedGraphState {
+    // Background grid, regenerated on resize or theme change
+    grid: Cache,
+    // Stacked areas, regenerated when new samples arrive
+    data: Cache,
+    // Masking and border, regenerated on resize or theme change
+    border: Cache,
+    series: RefCell<Vec<Vec<f32>>>,
+    markers: RefCell<Vec<usize>>,
+    colors: Cell<Option<[iced::Color; 3]>>,
+}
+
+/// Color of a layer, shared with [`LegendSwatch`] so legends match the graph
+pub fn layer_color(theme: &Theme, index: usize) -> iced::Color {
+    let palette = &theme.cosmic().palette;
+    [
+        palette.accent_blue,
+        palette.accent_green,
+        palette.accent_red,
+        palette.accent_orange,
+        palette.accent_yellow,
+        palette.accent_purple,
+        palette.accent_pink,
+        palette.accent_indigo,
+    ][index % 8]
+        .into()
+}
+
+impl Program<crate::app::Message, Theme> for StackedGraph {
+    type State = StackedGraphState;
+
+    fn draw(
+        &self,
+        state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: iced::Rectangle,
+        _cursor: iced::core::mouse::Cursor,
+    ) -> Vec<Geometry<Renderer>> {
+        let cosmic = theme.cosmic();
+
+        // Caches already regenerate on resize, theme changes have to be tracked by hand
+        let colors: [iced::Color; 3] = [
+            cosmic.bg_divider().into(),
+            cosmic.accent_color().into(),
+            cosmic.bg_color().into(),
+        ];
+        if state.colors.get() != Some(colors) {
+            state.colors.set(Some(colors));
+            state.grid.clear();
+            state.data.clear();
+            state.border.clear();
+        }
+        if *state.series.borrow() != self.series {
+            state.series.replace(self.series.clone());
+            state.data.clear();
+        }
+        if *state.markers.borrow() != self.markers {
+            state.markers.replace(self.markers.clone());
+            state.data.clear();
+        }
+
+        let bounds = iced::Rectangle::new(
+            iced::Point::new(0.0, 0.0),
+            iced::Size::new(
+                bounds.width.min(bounds.height),
+                bounds.height.min(bounds.width),
+            ),
+        );
+
+        vec![
+            state.grid.draw(renderer, bounds.size(), |frame| {
+                draw_grid(frame, theme, bounds)
+            }),
+            state.data.draw(renderer, bounds.size(), |frame| {
+                self.draw_layers(frame, theme, bounds)
+            }),
+            state.border.draw(renderer, bounds.size(), |frame| {
+                draw_border(frame, theme, bounds)
+            }),
+        ]
+    }
+}
+
+impl StackedGraph {
+    fn draw_layers(&self, frame: &mut Frame, theme: &Theme, bounds: iced::Rectangle) {
+        let bounds = bounds.shrink(1.0);
+        let len = self.series.iter().map(Vec::len).max().unwrap_or_default();
+        if len < 2 {
+            return;
+        }
+        let x_step = bounds.width / (len as f32 - 1.0);
+        let bottom = bounds.y + bounds.height;
+
+        let mut below = vec![0.0; len];
+        for (index, series) in self.series.iter().enumerate() {
+            let above = below
+                .iter()
+                .enumerate()
+                .map(|(i, base)| (base + series.get(i).cloned().unwrap_or_default()).min(1.0))
+                .collect::<Vec<f32>>();
+
+            // Trace the top edge forwards and the layer below backwards
+            let mut builder = path::Builder::new();
+            builder.move_to(iced::Point::new(0.0, bottom - above[0] * bounds.height));
+            for (i, value) in above.iter().enumerate().skip(1) {
+                builder.line_to(iced::Point::new(
+                    i as f32 * x_step,
+                    bottom - value * bounds.height,
+                ));
+            }
+            for (i, value) in below.iter().enumerate().rev() {
+                builder.line_to(iced::Point::new(
+                    i as f32 * x_step,
+                    bottom - value * bounds.height,
+                ));
+            }
+            builder.close();
+
+            let color = layer_color(theme, index);
+            frame.fill(
+                &builder.build(),
+                Fill {
+                    style: Style::Solid(iced::Color { a: 0.6, ..color }),
+                    ..Default::default()
+                },
+            );
+
+            below = above;
+        }
+
+        let marker_color: iced::Color = theme.cosmic().destructive_color().into();
+        for marker in self.markers.iter().filter(|marker| **marker < len) {
+            let x = *marker as f32 * x_step;
+            frame.stroke(
+                &Path::line(iced::Point::new(x, bounds.y), iced::Point::new(x, bottom)),
+                Stroke {
+                    style: Style::Solid(marker_color),
+                    width: 2.0,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+/// Colored square matching a layer of a [`StackedGraph`], used in legends
+pub struct LegendSwatch {
+    pub index: usize,
+}
+
+impl Program<crate::app::Message, Theme> for LegendSwatch {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: iced::Rectangle,
+        _cursor: iced::core::mouse::Cursor,
+    ) -> Vec<Geometry<Renderer>> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        frame.fill_rectangle(
+            iced::Point::ORIGIN,
+            bounds.size(),
+            layer_color(theme, self.index),
+        );
+        vec![frame.into_geometry()]
+    }
+}
+
+/// A single horizontal bar split into the layers of a [`StackedGraph`]
+pub struct StackedBar {
+    // One value per layer from the left (out of 1)
+    pub values: Vec<f32>,
+}
+
+impl Program<crate::app::Message, Theme> for StackedBar {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: iced::Rectangle,
+        _cursor: iced::core::mouse::Cursor,
+    ) -> Vec<Geometry<Renderer>> {
+        let mut frame = Frame::new(renderer, bounds.size());
+
+        // The unfilled remainder shows through as the background
+        let background: iced::Color = theme.cosmic().bg_divider().into();
+        frame.fill_rectangle(iced::Point::ORIGIN, bounds.size(), background);
+
+        let mut x = 0.0;
+        for (index, value) in self.values.iter().enumerate() {
+            let width = (value.clamp(0.0, 1.0) * bounds.width).min(bounds.width - x);
+            frame.fill_rectangle(
+                iced::Point::new(x, 0.0),
+                iced::Size::new(width, bounds.height),
+                layer_color(theme, index),
+            );
+            x += width;
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
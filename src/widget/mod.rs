@@ -1 +1,3 @@
 pub mod graph;
+pub mod treemap;
+pub mod usage_cell;
@@ -0,0 +1,93 @@
+use std::ops::Range;
+
+use cosmic::{iced, widget::canvas::*};
+
+use super::graph::stacked::layer_color;
+
+/// Splits items sorted by size into rows for a strip treemap, keeping tiles close to square.
+/// `aspect` is the width of the area divided by its height.
+pub fn strips(sizes: &[u64], aspect: f32) -> Vec<Range<usize>> {
+    let total = sizes.iter().sum::<u64>().max(1) as f32;
+    // Tile areas in a space of `aspect` by 1
+    let areas = sizes
+        .iter()
+        .map(|size| *size as f32 / total * aspect)
+        .collect::<Vec<_>>();
+    let worst = |row: &[f32]| {
+        let height = row.iter().sum::<f32>() / aspect;
+        row.iter()
+            .map(|area| {
+                let width = area / height;
+                (width / height).max(height / width)
+            })
+            .fold(0.0, f32::max)
+    };
+
+    let mut strips = Vec::new();
+    let mut start = 0;
+    for end in 1..=areas.len() {
+        // Close the row once adding the next tile would make it less square
+        if end > start + 1 && worst(&areas[start..end]) > worst(&areas[start..end - 1]) {
+            strips.push(start..end - 1);
+            start = end - 1;
+        }
+    }
+    if start < areas.len() {
+        strips.push(start..areas.len());
+    }
+    strips
+}
+
+/// One tile of a treemap, colored like the layers of a stacked graph
+pub struct TreemapTile {
+    pub label: String,
+    pub index: usize,
+}
+
+impl Program<crate::app::Message, Theme> for TreemapTile {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: iced::Rectangle,
+        cursor: iced::core::mouse::Cursor,
+    ) -> Vec<Geometry<Renderer>> {
+        let cosmic = theme.cosmic();
+        let mut frame = Frame::new(renderer, bounds.size());
+
+        let mut tile = path::Builder::new();
+        tile.rounded_rectangle(
+            iced::Point::ORIGIN,
+            bounds.size(),
+            cosmic.radius_xs().into(),
+        );
+        let tile = tile.build();
+        let color = layer_color(theme, self.index);
+        frame.fill(
+            &tile,
+            Fill {
+                // Tiles light up under the cursor since they can be clicked
+                style: Style::Solid(iced::Color {
+                    a: if cursor.is_over(bounds) { 0.9 } else { 0.6 },
+                    ..color
+                }),
+                ..Default::default()
+            },
+        );
+
+        // Labels are left out of tiles too small to hold them
+        if bounds.width > 48.0 && bounds.height > 20.0 {
+            frame.fill_text(Text {
+                content: self.label.clone(),
+                position: iced::Point::new(cosmic.space_xxs() as f32, cosmic.space_xxs() as f32),
+                color: cosmic.on_bg_color().into(),
+                ..Default::default()
+            });
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
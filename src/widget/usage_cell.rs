@@ -0,0 +1,66 @@
+use cosmic::{iced, prelude::*, widget::canvas::*};
+
+/// A labelled tile whose fill follows a utilization, e.g. one logical CPU in a topology map
+#[derive(Clone)]
+pub struct UsageCell {
+    pub label: String,
+    // Utilization (out of 1)
+    pub usage: f32,
+}
+
+impl Program<crate::app::Message, Theme> for UsageCell {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: iced::Rectangle,
+        _cursor: iced::core::mouse::Cursor,
+    ) -> Vec<Geometry<Renderer>> {
+        let cosmic = theme.cosmic();
+        let mut frame = Frame::new(renderer, bounds.size());
+
+        let mut tile = path::Builder::new();
+        tile.rounded_rectangle(
+            iced::Point::ORIGIN,
+            bounds.size(),
+            cosmic.radius_xs().into(),
+        );
+        let tile = tile.build();
+        frame.fill(
+            &tile,
+            Fill {
+                style: Style::Solid(
+                    cosmic
+                        .accent_color()
+                        .apply(|mut color| {
+                            color.alpha = 0.15 + 0.85 * self.usage.clamp(0.0, 1.0);
+                            color
+                        })
+                        .into(),
+                ),
+                ..Default::default()
+            },
+        );
+        frame.stroke(
+            &tile,
+            Stroke {
+                style: Style::Solid(cosmic.accent_color().into()),
+                width: 1.0,
+                ..Default::default()
+            },
+        );
+        frame.fill_text(Text {
+            content: self.label.clone(),
+            position: frame.center(),
+            color: cosmic.on_accent_color().into(),
+            horizontal_alignment: iced::alignment::Horizontal::Center,
+            vertical_alignment: iced::alignment::Vertical::Center,
+            ..Default::default()
+        });
+
+        vec![frame.into_geometry()]
+    }
+}